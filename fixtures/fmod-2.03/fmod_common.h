@@ -0,0 +1,106 @@
+/* ======================================================================================== */
+/* FMOD Core API - Common C/C++ header file.                                                */
+/* Excerpt of FMOD 2.03 declarations changed since 2.02, used by parser tests.              */
+/* ======================================================================================== */
+#ifndef _FMOD_COMMON_H
+#define _FMOD_COMMON_H
+
+/*
+    Library import helpers
+*/
+#if defined(_WIN32) || defined(__CYGWIN__)
+    #define F_CALL __stdcall
+#else
+    #define F_CALL
+#endif
+
+#define F_CALLBACK F_CALL
+
+/*
+    FMOD core types
+*/
+typedef int                        FMOD_BOOL;
+typedef struct FMOD_SYSTEM         FMOD_SYSTEM;
+typedef unsigned long long         FMOD_PORT_INDEX;
+
+/*
+    FMOD constants
+*/
+#define FMOD_VERSION    0x00020309                     /* 0xaaaabbcc -> aaaa = product version, bb = major version, cc = minor version.*/
+#define FMOD_BUILDNUMBER 155273
+
+typedef unsigned int FMOD_SYSTEM_CALLBACK_TYPE;
+#define FMOD_SYSTEM_CALLBACK_DEVICELISTCHANGED      0x00000001
+#define FMOD_SYSTEM_CALLBACK_DEVICELOST             0x00000002
+#define FMOD_SYSTEM_CALLBACK_MEMORYALLOCATIONFAILED 0x00000004
+#define FMOD_SYSTEM_CALLBACK_THREADCREATED          0x00000008
+#define FMOD_SYSTEM_CALLBACK_BADDSPCONNECTION       0x00000010
+#define FMOD_SYSTEM_CALLBACK_PREMIX                 0x00000020
+#define FMOD_SYSTEM_CALLBACK_POSTMIX                0x00000040
+#define FMOD_SYSTEM_CALLBACK_ERROR                  0x00000080
+#define FMOD_SYSTEM_CALLBACK_THREADDESTROYED        0x00000100
+#define FMOD_SYSTEM_CALLBACK_PREUPDATE              0x00000200
+#define FMOD_SYSTEM_CALLBACK_POSTUPDATE             0x00000400
+#define FMOD_SYSTEM_CALLBACK_RECORDLISTCHANGED      0x00000800
+#define FMOD_SYSTEM_CALLBACK_BUFFEREDNOMIX          0x00001000
+#define FMOD_SYSTEM_CALLBACK_DEVICEREINITIALIZE     0x00002000
+#define FMOD_SYSTEM_CALLBACK_OUTPUTUNDERRUN         0x00004000
+#define FMOD_SYSTEM_CALLBACK_RECORDPOSITIONCHANGED  0x00008000
+#define FMOD_SYSTEM_CALLBACK_ALL                    0xFFFFFFFF
+
+typedef enum FMOD_OUTPUTTYPE
+{
+    FMOD_OUTPUTTYPE_AUTODETECT,
+    FMOD_OUTPUTTYPE_UNKNOWN,
+    FMOD_OUTPUTTYPE_NOSOUND,
+    FMOD_OUTPUTTYPE_WAVWRITER,
+    FMOD_OUTPUTTYPE_NOSOUND_NRT,
+    FMOD_OUTPUTTYPE_WAVWRITER_NRT,
+    FMOD_OUTPUTTYPE_WASAPI,
+    FMOD_OUTPUTTYPE_ASIO,
+    FMOD_OUTPUTTYPE_PULSEAUDIO,
+    FMOD_OUTPUTTYPE_ALSA,
+    FMOD_OUTPUTTYPE_COREAUDIO,
+    FMOD_OUTPUTTYPE_AUDIOTRACK,
+    FMOD_OUTPUTTYPE_OPENSL,
+    FMOD_OUTPUTTYPE_AUDIOOUT,
+    FMOD_OUTPUTTYPE_AUDIO3D,
+    FMOD_OUTPUTTYPE_WEBAUDIO,
+    FMOD_OUTPUTTYPE_NNAUDIO,
+    FMOD_OUTPUTTYPE_WINSONIC,
+    FMOD_OUTPUTTYPE_AAUDIO,
+    FMOD_OUTPUTTYPE_AUDIOWORKLET,
+    FMOD_OUTPUTTYPE_PHASE,
+    FMOD_OUTPUTTYPE_OHAUDIO,
+
+    FMOD_OUTPUTTYPE_MAX,
+    FMOD_OUTPUTTYPE_FORCEINT = 65536
+} FMOD_OUTPUTTYPE;
+
+typedef struct FMOD_ADVANCEDSETTINGS
+{
+    int                 cbSize;
+    int                 maxMPEGCodecs;
+    int                 maxADPCMCodecs;
+    int                 maxXMACodecs;
+    int                 maxVorbisCodecs;
+    int                 maxAT9Codecs;
+    int                 maxFADPCMCodecs;
+    int                 maxOpusCodecs;
+    int                 ASIONumChannels;
+    char              **ASIOChannelList;
+    FMOD_SPEAKER       *ASIOSpeakerList;
+    float               vol0virtualvol;
+    unsigned int        defaultDecodeBufferSize;
+    unsigned short      profilePort;
+    unsigned int        geometryMaxFadeTime;
+    float               distanceFilterCenterFreq;
+    int                 reverb3Dinstance;
+    int                 DSPBufferPoolSize;
+    FMOD_DSP_RESAMPLER  resamplerMethod;
+    unsigned int        randomSeed;
+    int                 maxConvolutionThreads;
+    int                 maxSpatialObjects;
+} FMOD_ADVANCEDSETTINGS;
+
+#endif
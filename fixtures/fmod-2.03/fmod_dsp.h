@@ -0,0 +1,34 @@
+/* ======================================================================================== */
+/* FMOD Core API - DSP header file.                                                         */
+/* Excerpt of FMOD 2.03 declarations changed since 2.02, used by parser tests.              */
+/* ======================================================================================== */
+#ifndef _FMOD_DSP_H
+#define _FMOD_DSP_H
+
+#include "fmod_dsp_effects.h"
+
+typedef enum
+{
+    FMOD_DSP_PARAMETER_DATA_TYPE_USER =                 0,
+    FMOD_DSP_PARAMETER_DATA_TYPE_OVERALLGAIN =         -1,
+    FMOD_DSP_PARAMETER_DATA_TYPE_3DATTRIBUTES =        -2,
+    FMOD_DSP_PARAMETER_DATA_TYPE_SIDECHAIN =           -3,
+    FMOD_DSP_PARAMETER_DATA_TYPE_FFT =                 -4,
+    FMOD_DSP_PARAMETER_DATA_TYPE_3DATTRIBUTES_MULTI =  -5,
+    FMOD_DSP_PARAMETER_DATA_TYPE_ATTENUATION_RANGE =   -6,
+    FMOD_DSP_PARAMETER_DATA_TYPE_DYNAMIC_RESPONSE =    -7,
+} FMOD_DSP_PARAMETER_DATA_TYPE;
+
+typedef struct FMOD_DSP_PARAMETER_ATTENUATION_RANGE
+{
+    float min;
+    float max;
+} FMOD_DSP_PARAMETER_ATTENUATION_RANGE;
+
+typedef struct FMOD_DSP_PARAMETER_DYNAMIC_RESPONSE
+{
+    int   numchannels;
+    float rms[32];
+} FMOD_DSP_PARAMETER_DYNAMIC_RESPONSE;
+
+#endif
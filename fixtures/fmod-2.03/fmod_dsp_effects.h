@@ -0,0 +1,104 @@
+/* ======================================================================================== */
+/* FMOD Core API - Built-in effects header file.                                            */
+/* Excerpt of FMOD 2.03 declarations changed since 2.02, used by parser tests.              */
+/* ======================================================================================== */
+#ifndef _FMOD_DSP_EFFECTS_H
+#define _FMOD_DSP_EFFECTS_H
+
+typedef enum
+{
+    FMOD_DSP_TYPE_UNKNOWN,
+    FMOD_DSP_TYPE_MIXER,
+    FMOD_DSP_TYPE_OSCILLATOR,
+    FMOD_DSP_TYPE_LOWPASS,
+    FMOD_DSP_TYPE_ITLOWPASS,
+    FMOD_DSP_TYPE_HIGHPASS,
+    FMOD_DSP_TYPE_ECHO,
+    FMOD_DSP_TYPE_FADER,
+    FMOD_DSP_TYPE_FLANGE,
+    FMOD_DSP_TYPE_DISTORTION,
+    FMOD_DSP_TYPE_NORMALIZE,
+    FMOD_DSP_TYPE_LIMITER,
+    FMOD_DSP_TYPE_PARAMEQ,
+    FMOD_DSP_TYPE_PITCHSHIFT,
+    FMOD_DSP_TYPE_CHORUS,
+    FMOD_DSP_TYPE_ITECHO,
+    FMOD_DSP_TYPE_COMPRESSOR,
+    FMOD_DSP_TYPE_SFXREVERB,
+    FMOD_DSP_TYPE_LOWPASS_SIMPLE,
+    FMOD_DSP_TYPE_DELAY,
+    FMOD_DSP_TYPE_TREMOLO,
+    FMOD_DSP_TYPE_SEND,
+    FMOD_DSP_TYPE_RETURN,
+    FMOD_DSP_TYPE_HIGHPASS_SIMPLE,
+    FMOD_DSP_TYPE_PAN,
+    FMOD_DSP_TYPE_THREE_EQ,
+    FMOD_DSP_TYPE_FFT,
+    FMOD_DSP_TYPE_LOUDNESS_METER,
+    FMOD_DSP_TYPE_CONVOLUTIONREVERB,
+    FMOD_DSP_TYPE_CHANNELMIX,
+    FMOD_DSP_TYPE_TRANSCEIVER,
+    FMOD_DSP_TYPE_OBJECTPAN,
+    FMOD_DSP_TYPE_MULTIBAND_EQ,
+    FMOD_DSP_TYPE_MULTIBAND_DYNAMICS,
+
+    FMOD_DSP_TYPE_MAX,
+    FMOD_DSP_TYPE_FORCEINT = 65536
+} FMOD_DSP_TYPE;
+
+typedef enum
+{
+    FMOD_DSP_MULTIBAND_DYNAMICS_LOWER_FREQUENCY,
+    FMOD_DSP_MULTIBAND_DYNAMICS_UPPER_FREQUENCY,
+    FMOD_DSP_MULTIBAND_DYNAMICS_LINKED,
+    FMOD_DSP_MULTIBAND_DYNAMICS_USE_SIDECHAIN,
+    FMOD_DSP_MULTIBAND_DYNAMICS_A_MODE,
+    FMOD_DSP_MULTIBAND_DYNAMICS_A_GAIN,
+    FMOD_DSP_MULTIBAND_DYNAMICS_A_THRESHOLD,
+    FMOD_DSP_MULTIBAND_DYNAMICS_A_RATIO,
+    FMOD_DSP_MULTIBAND_DYNAMICS_A_ATTACK,
+    FMOD_DSP_MULTIBAND_DYNAMICS_A_RELEASE,
+    FMOD_DSP_MULTIBAND_DYNAMICS_A_GAIN_MAKEUP,
+    FMOD_DSP_MULTIBAND_DYNAMICS_A_RESPONSE_DATA,
+} FMOD_DSP_MULTIBAND_DYNAMICS;
+
+typedef enum
+{
+    FMOD_DSP_MULTIBAND_DYNAMICS_MODE_DISABLED,
+    FMOD_DSP_MULTIBAND_DYNAMICS_MODE_COMPRESS_UP,
+    FMOD_DSP_MULTIBAND_DYNAMICS_MODE_COMPRESS_DOWN,
+    FMOD_DSP_MULTIBAND_DYNAMICS_MODE_EXPAND_UP,
+    FMOD_DSP_MULTIBAND_DYNAMICS_MODE_EXPAND_DOWN
+} FMOD_DSP_MULTIBAND_DYNAMICS_MODE_TYPE;
+
+typedef enum
+{
+    FMOD_DSP_FFT_WINDOW_RECT,
+    FMOD_DSP_FFT_WINDOW_TRIANGLE,
+    FMOD_DSP_FFT_WINDOW_HAMMING,
+    FMOD_DSP_FFT_WINDOW_HANNING,
+    FMOD_DSP_FFT_WINDOW_BLACKMAN,
+    FMOD_DSP_FFT_WINDOW_BLACKMANHARRIS
+} FMOD_DSP_FFT_WINDOW_TYPE;
+
+typedef enum
+{
+    FMOD_DSP_FFT_DOWNMIX_NONE,
+    FMOD_DSP_FFT_DOWNMIX_MONO,
+} FMOD_DSP_FFT_DOWNMIX_TYPE;
+
+typedef enum
+{
+    FMOD_DSP_FFT_WINDOWSIZE,
+    FMOD_DSP_FFT_WINDOW,
+    FMOD_DSP_FFT_BAND_START_FREQ,
+    FMOD_DSP_FFT_BAND_STOP_FREQ,
+    FMOD_DSP_FFT_SPECTRUMDATA,
+    FMOD_DSP_FFT_RMS,
+    FMOD_DSP_FFT_SPECTRAL_CENTROID,
+    FMOD_DSP_FFT_IMMEDIATE_MODE,
+    FMOD_DSP_FFT_DOWNMIX,
+    FMOD_DSP_FFT_CHANNEL,
+} FMOD_DSP_FFT;
+
+#endif
@@ -0,0 +1,209 @@
+use std::collections::{HashMap, HashSet};
+
+use regex::Regex;
+
+use crate::generators::lib::{extract_method_name, extract_struct_key};
+use crate::models::Type::{FundamentalType, UserType};
+use crate::models::{Api, Argument, Function, FunctionRole, MethodNaming, Modifier, Pointer};
+use crate::patching::dictionary::{ARRAY_LENGTHS, KEYWORDS};
+
+fn extract_suffix(name: &str) -> &str {
+    match name.rfind('_') {
+        Some(index) => &name[index + 1..],
+        None => name,
+    }
+}
+
+fn is_int(argument: &Argument) -> bool {
+    argument.argument_type == FundamentalType("int".into()) && argument.pointer.is_none()
+}
+
+fn is_length(argument: &Argument) -> bool {
+    argument.pointer.is_none()
+        && (argument.argument_type == FundamentalType("int".into())
+            || argument.argument_type == FundamentalType("unsigned int".into()))
+}
+
+impl Api {
+    pub fn classify_function(&self, function: &Function) -> FunctionRole {
+        let suffix = extract_suffix(&function.name);
+        let arguments = &function.arguments;
+        let is_output = |argument: &Argument| {
+            self.get_modifier(&function.name, &argument.name) == Modifier::Out
+        };
+        let is_callback = |argument: &Argument| match &argument.argument_type {
+            UserType(name) => self.callbacks.iter().any(|callback| &callback.name == name),
+            FundamentalType(_) => false,
+        };
+        let owner_created = arguments
+            .first()
+            .is_some_and(|argument| matches!(argument.pointer, Some(Pointer::DoublePointer(_))));
+        if suffix == "Create" && owner_created {
+            return FunctionRole::Constructor;
+        }
+        if suffix == "Release" && arguments.len() == 1 {
+            return FunctionRole::Destructor;
+        }
+        if suffix.starts_with("Set")
+            && suffix.ends_with("Callback")
+            && arguments.iter().any(is_callback)
+        {
+            return FunctionRole::CallbackSetter;
+        }
+        if arguments.len() == 2
+            && (suffix.starts_with("GetNum") || suffix.ends_with("Count"))
+            && arguments[1].argument_type == FundamentalType("int".into())
+            && is_output(&arguments[1])
+        {
+            return FunctionRole::Count;
+        }
+        if suffix.ends_with("List")
+            && arguments.len() == 4
+            && is_int(&arguments[2])
+            && is_output(&arguments[3])
+        {
+            return FunctionRole::List;
+        }
+        if arguments.len() == 3 && is_int(&arguments[1]) && is_output(&arguments[2]) {
+            return FunctionRole::IndexedGetter;
+        }
+        if arguments.len() > 1 && suffix.starts_with("Get") && arguments[1..].iter().any(is_output)
+        {
+            return FunctionRole::Getter;
+        }
+        if arguments.len() > 1 && suffix.starts_with("Set") && !arguments.iter().any(is_output) {
+            return FunctionRole::Setter;
+        }
+        FunctionRole::Method
+    }
+
+    /// Assigns roles to functions, must be called after parameter modifiers are final.
+    pub fn classify_functions(&mut self) {
+        let mut functions = std::mem::take(&mut self.functions);
+        for (_, functions) in functions.iter_mut() {
+            for function in functions.iter_mut() {
+                function.role = self.classify_function(function);
+            }
+        }
+        self.functions = functions;
+    }
+
+    /// Renames generated methods according to naming setting, `get_` prefix is kept
+    /// when stripped name is taken by another method of the type or isn't valid identifier.
+    /// Returns functions which keep their names because of collision.
+    pub fn assign_method_names(&mut self) -> Vec<String> {
+        let mut collisions = vec![];
+        if self.settings.method_naming == MethodNaming::Fmod {
+            return collisions;
+        }
+        let declaration = Regex::new("\\bfn ([a-z_0-9]+)").unwrap();
+        let mut taken: HashMap<String, HashSet<String>> = HashMap::new();
+        for function in self.functions.iter().flat_map(|(_, functions)| functions) {
+            let names = taken.entry(extract_struct_key(&function.name)).or_default();
+            names.insert(extract_method_name(&function.name));
+            if let Some(patch) = self.function_patches.get(&function.name) {
+                let patch = patch.to_string();
+                names.extend(
+                    declaration
+                        .captures_iter(&patch)
+                        .map(|name| name[1].to_string()),
+                );
+            }
+        }
+        for (key, patch) in &self.opaque_type_patches {
+            let patch = patch.to_string();
+            let names = taken.entry(key.clone()).or_default();
+            names.extend(
+                declaration
+                    .captures_iter(&patch)
+                    .map(|name| name[1].to_string()),
+            );
+        }
+        let mut names = HashMap::new();
+        for function in self.functions.iter().flat_map(|(_, functions)| functions) {
+            if self.function_patches.contains_key(&function.name) {
+                continue;
+            }
+            let method = extract_method_name(&function.name);
+            let stripped = match method.strip_prefix("get_") {
+                Some(stripped) => stripped.to_string(),
+                None => continue,
+            };
+            let owner = extract_struct_key(&function.name);
+            let valid = !KEYWORDS.contains(&&stripped[..])
+                && !stripped.starts_with(|character: char| character.is_ascii_digit());
+            let owner_names = taken.entry(owner).or_default();
+            if !valid || owner_names.contains(&stripped) {
+                collisions.push(function.name.clone());
+                continue;
+            }
+            owner_names.insert(stripped.clone());
+            names.insert(function.name.clone(), stripped);
+        }
+        self.method_names = names;
+        collisions
+    }
+
+    fn is_array_input(&self, function: &Function, argument: &Argument) -> bool {
+        if !matches!(argument.pointer, Some(Pointer::NormalPointer(_)))
+            || self.get_modifier(&function.name, &argument.name) != Modifier::None
+        {
+            return false;
+        }
+        match &argument.argument_type {
+            FundamentalType(name) => {
+                ["int", "unsigned int", "float", "double", "unsigned char"].contains(&&name[..])
+            }
+            UserType(name) => self.is_structure(name) && !self.is_union(name),
+        }
+    }
+
+    /// Pairs array inputs with arguments passing their length, so wrappers take slices.
+    /// Length named after array (`points` and `numpoints`) or generic length following
+    /// array is detected, other pairs come from curated table.
+    pub fn pair_array_lengths(&mut self) {
+        let mut pairs = vec![];
+        for function in self.functions.iter().flat_map(|(_, functions)| functions) {
+            let arguments = &function.arguments;
+            for (index, array) in arguments.iter().enumerate() {
+                if !self.is_array_input(function, array) {
+                    continue;
+                }
+                let curated = ARRAY_LENGTHS
+                    .iter()
+                    .find(|pair| pair.0 == function.name && pair.1 == array.name)
+                    .map(|pair| pair.2.to_string());
+                let named = format!("num{}", array.name);
+                let length = arguments
+                    .iter()
+                    .find(|argument| argument.name == named)
+                    .or_else(|| {
+                        arguments
+                            .get(index + 1)
+                            .filter(|argument| ["length", "count"].contains(&&argument.name[..]))
+                    })
+                    .filter(|argument| is_length(argument))
+                    .map(|argument| argument.name.clone());
+                if let Some(length) = curated.or(length) {
+                    pairs.push((format!("{}+{}", function.name, array.name), length));
+                }
+            }
+        }
+        self.array_lengths.extend(pairs);
+    }
+
+    /// Orders declarations by name, so generated code doesn't depend on header layout
+    /// and regenerated crate diffs show only real API changes. Constants keep declaration
+    /// order because their values refer to previously defined ones, ffi sorts them on output.
+    pub fn sort_declarations(&mut self) {
+        self.opaque_types.sort_by(|a, b| a.name.cmp(&b.name));
+        self.type_aliases.sort_by(|a, b| a.name.cmp(&b.name));
+        self.enumerations.sort_by(|a, b| a.name.cmp(&b.name));
+        self.flags.sort_by(|a, b| a.name.cmp(&b.name));
+        self.structures.sort_by(|a, b| a.name.cmp(&b.name));
+        self.callbacks.sort_by(|a, b| a.name.cmp(&b.name));
+        for (_, functions) in &mut self.functions {
+            functions.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+    }
+}
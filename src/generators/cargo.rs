@@ -0,0 +1,170 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use regex::Regex;
+
+use crate::models::Error;
+
+/// Optional dependencies and features enabled by features of generated crate.
+const FEATURE_DEPENDENCIES: &[(&str, &[&str])] = &[
+    ("flags", &["bitflags"]),
+    ("mint", &["mint"]),
+    ("glam", &["glam"]),
+    ("bevy", &["bevy_ecs", "send-sync"]),
+    ("serde", &["dep:serde"]),
+];
+
+/// Features read by build script of destination crate to choose linked libraries,
+/// generated code doesn't reference them but manifest must declare them.
+const BUILD_FEATURES: &[&str] = &["core-only", "logging-libs"];
+
+/// Features enabled by default when they first appear in destination manifest,
+/// so regeneration doesn't take away behaviour of previous versions.
+const DEFAULT_FEATURES: &[&str] = &["send-sync"];
+
+type FeatureTable = BTreeMap<String, Vec<String>>;
+
+/// Collects features referenced by `cfg` and `cfg_attr` attributes of generated code.
+pub fn collect_features<'a>(sources: impl IntoIterator<Item = &'a str>) -> BTreeSet<String> {
+    let pattern = Regex::new("feature\\s*=\\s*\"([\\w-]+)\"").unwrap();
+    sources
+        .into_iter()
+        .flat_map(|source| pattern.captures_iter(source))
+        .map(|captures| captures[1].to_string())
+        .chain(BUILD_FEATURES.iter().map(|feature| feature.to_string()))
+        .collect()
+}
+
+fn format_feature(name: &str, values: &[String]) -> String {
+    let values: Vec<String> = values.iter().map(|value| format!("{:?}", value)).collect();
+    format!("{} = [{}]\n", name, values.join(", "))
+}
+
+/// Declares every generated feature missing in existing table,
+/// features written by hand are kept as is.
+pub fn merge_features(mut table: FeatureTable, features: &BTreeSet<String>) -> FeatureTable {
+    for feature in features {
+        if table.contains_key(feature) {
+            continue;
+        }
+        let dependencies = FEATURE_DEPENDENCIES
+            .iter()
+            .find(|(name, _)| name == feature)
+            .map(|(_, dependencies)| *dependencies)
+            .unwrap_or_default();
+        table.insert(
+            feature.clone(),
+            dependencies.iter().map(|value| value.to_string()).collect(),
+        );
+        if DEFAULT_FEATURES.contains(&feature.as_str()) {
+            table
+                .entry("default".to_string())
+                .or_default()
+                .push(feature.clone());
+        }
+    }
+    table.entry("default".to_string()).or_default();
+    table
+}
+
+/// Generates `[features]` table, `default` goes first.
+pub fn generate_features(table: &FeatureTable) -> String {
+    let mut code = String::from("[features]\n");
+    if let Some(default) = table.get("default") {
+        code.push_str(&format_feature("default", default));
+    }
+    for (name, values) in table {
+        if name != "default" {
+            code.push_str(&format_feature(name, values));
+        }
+    }
+    code
+}
+
+/// Updates `[features]` table of destination manifest declaring features referenced in
+/// generated code, so declarations don't drift on regeneration. Existing features are kept.
+pub fn update_manifest(manifest: &str, features: &BTreeSet<String>) -> Result<String, Error> {
+    let mut parsed: toml::Table = manifest.parse()?;
+    let existing: FeatureTable = match parsed.remove("features") {
+        Some(table) => table.try_into()?,
+        None => FeatureTable::new(),
+    };
+    let table = merge_features(existing, features);
+
+    let header = Regex::new(r"^\s*\[\[?\s*[\w-][^\[\]=]*\]\]?\s*(#.*)?$").unwrap();
+    let mut before = vec![];
+    let mut after = vec![];
+    let mut section = 0;
+    for line in manifest.lines() {
+        let is_header = header.is_match(line);
+        if is_header && line.trim() == "[features]" {
+            section = 1;
+            continue;
+        }
+        if is_header && section == 1 {
+            section = 2;
+        }
+        match section {
+            0 => before.push(line),
+            1 => {}
+            _ => after.push(line),
+        }
+    }
+    let mut code = before.join("\n").trim_end().to_string();
+    code.push_str("\n\n");
+    code.push_str(&generate_features(&table));
+    if !after.is_empty() {
+        code.push('\n');
+        code.push_str(after.join("\n").trim_end());
+        code.push('\n');
+    }
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn features(names: &[&str]) -> BTreeSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn keeps_hand_written_features() {
+        let manifest = "[package]\nname = \"libfmod\"\n\n[features]\ndefault = [\"flags\"]\nserde = [\"dep:serde\", \"bitflags/serde\"]\nextra = []\n\n[dependencies]\nserde = { version = \"1\", optional = true }\n";
+        let code = update_manifest(manifest, &features(&["flags", "serde"])).unwrap();
+        assert!(code.contains("serde = [\"dep:serde\", \"bitflags/serde\"]\n"));
+        assert!(code.contains("extra = []\n"));
+        assert!(code.contains("flags = [\"bitflags\"]\n"));
+        assert!(code.ends_with("[dependencies]\nserde = { version = \"1\", optional = true }\n"));
+    }
+
+    #[test]
+    fn keeps_multiline_default() {
+        let manifest = "[package]\nname = \"libfmod\"\n\n[features]\ndefault = [\n    \"flags\",\n    \"mint\",\n]\n\n[dependencies]\n";
+        let code = update_manifest(manifest, &features(&["flags", "mint"])).unwrap();
+        assert!(code.contains("[features]\ndefault = [\"flags\", \"mint\"]\n"));
+        assert!(!code.contains("    \"mint\",\n"));
+        let parsed: toml::Table = code.parse().unwrap();
+        assert!(parsed.contains_key("dependencies"));
+    }
+
+    #[test]
+    fn declares_crate_features() {
+        let manifest = "[package]\nname = \"libfmod\"\n";
+        let generated = "#[cfg(feature = \"send-sync\")]\n#[cfg_attr(feature = \"serde\", derive(serde::Serialize))]";
+        let code = update_manifest(manifest, &collect_features([generated])).unwrap();
+        let parsed: toml::Table = code.parse().unwrap();
+        let table: FeatureTable = parsed["features"].clone().try_into().unwrap();
+        assert_eq!(table["default"], vec!["send-sync"]);
+        assert_eq!(table["serde"], vec!["dep:serde"]);
+        assert!(table.contains_key("core-only"));
+        assert!(table.contains_key("logging-libs"));
+    }
+
+    #[test]
+    fn keeps_default_without_declared_feature() {
+        let manifest = "[features]\ndefault = []\nsend-sync = []\n";
+        let code = update_manifest(manifest, &features(&["send-sync"])).unwrap();
+        assert!(code.contains("default = []\n"));
+    }
+}
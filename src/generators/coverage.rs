@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+
+use crate::lib::{extract_struct_key, format_struct_ident, generate_method, OutputStructures};
+use crate::models::{Api, Function};
+
+#[derive(Debug, Default)]
+struct Coverage<'a> {
+    generated: Vec<&'a Function>,
+    overridden: Vec<&'a Function>,
+    unsupported: Vec<&'a Function>,
+}
+
+/// Classifies functions of opaque types by the way methods are generated,
+/// functions of other prefixes are reported as global ones not bound to any type.
+pub fn generate_coverage(api: &Api) -> String {
+    let mut types: BTreeMap<&str, Coverage> = api
+        .opaque_types
+        .iter()
+        .map(|opaque_type| (opaque_type.name.as_str(), Coverage::default()))
+        .collect();
+    let mut globals = vec![];
+    let outputs = OutputStructures::default();
+    for function in api.functions.iter().flat_map(|(_, functions)| functions) {
+        let key = extract_struct_key(&function.name);
+        let coverage = match types.get_mut(key.as_str()) {
+            Some(coverage) => coverage,
+            None => {
+                globals.push(function);
+                continue;
+            }
+        };
+        if api.function_patches.contains_key(&function.name) {
+            coverage.overridden.push(function);
+            continue;
+        }
+        match generate_method(&key, function, api, &outputs) {
+            Ok(_) => coverage.generated.push(function),
+            Err(_) => coverage.unsupported.push(function),
+        }
+    }
+
+    let mut report = vec![
+        "# API Coverage".to_string(),
+        String::new(),
+        "| Type | Functions | Generated | Overridden | Unsupported |".to_string(),
+        "|------|-----------|-----------|------------|-------------|".to_string(),
+    ];
+    for (key, coverage) in &types {
+        report.push(format!(
+            "| {} | {} | {} | {} | {} |",
+            format_struct_ident(key),
+            coverage.generated.len() + coverage.overridden.len() + coverage.unsupported.len(),
+            coverage.generated.len(),
+            coverage.overridden.len(),
+            coverage.unsupported.len()
+        ));
+    }
+    let unsupported: Vec<&Function> = types
+        .values()
+        .flat_map(|coverage| coverage.unsupported.iter().copied())
+        .collect();
+    for (title, functions) in [
+        ("Unsupported Signatures", unsupported),
+        ("Global Functions", globals),
+    ] {
+        report.push(String::new());
+        report.push(format!("## {}", title));
+        report.push(String::new());
+        if functions.is_empty() {
+            report.push("None.".to_string());
+        }
+        for function in functions {
+            report.push(format!("- `{}`", function.name));
+        }
+    }
+    report.push(String::new());
+    report.join("\n")
+}
@@ -0,0 +1,212 @@
+use crate::models::{Api, Error, ErrorLogging};
+use quote::__private::TokenStream;
+
+pub fn generate_error_code(api: &Api) -> TokenStream {
+    let error_logging = match api.settings.error_logging {
+        ErrorLogging::None => quote! {},
+        ErrorLogging::Log => quote! {
+            log::warn!("{} failed: {} ({})", $function, $crate::ffi::map_fmod_error($code), $code);
+        },
+        ErrorLogging::Tracing => quote! {
+            tracing::event!(
+                tracing::Level::WARN,
+                function = $function,
+                code = $code,
+                "{}",
+                $crate::ffi::map_fmod_error($code)
+            );
+        },
+    };
+
+    // static errors avoid allocations on hot paths polling for benign results
+    let (function_type, fmod_error, fmod_display, fmod_construct) = if api.settings.static_errors {
+        (
+            quote! { &'static str },
+            quote! {
+                Fmod {
+                    function: &'static str,
+                    code: FmodResult,
+                }
+            },
+            quote! {
+                Error::Fmod { function, code } => {
+                    write!(f, "{}: {} ({})", function, code.message(), code.to_raw())
+                }
+            },
+            quote! {
+                $crate::Error::Fmod {
+                    function: $function,
+                    code: $crate::FmodResult::from_raw_lossy($code),
+                }
+            },
+        )
+    } else {
+        (
+            quote! { String },
+            quote! {
+                Fmod {
+                    function: String,
+                    code: FmodResult,
+                    message: String,
+                }
+            },
+            quote! {
+                Error::Fmod {
+                    function,
+                    code,
+                    message,
+                } => {
+                    write!(f, "{}: {} ({})", function, message, code.to_raw())
+                }
+            },
+            quote! {
+                $crate::Error::Fmod {
+                    function: $function.to_string(),
+                    code: $crate::FmodResult::from_raw_lossy($code),
+                    message: $crate::ffi::map_fmod_error($code).to_string(),
+                }
+            },
+        )
+    };
+
+    quote! {
+        use crate::{ffi, FmodResult};
+        use std::ffi::{IntoStringError, NulError};
+        use std::fmt::{Display, Formatter};
+        use std::str::Utf8Error;
+
+        #[derive(Debug)]
+        #[non_exhaustive]
+        pub enum Error {
+            #fmod_error,
+            EnumBindgen {
+                enumeration: String,
+                value: String
+            },
+            String(IntoStringError),
+            StringNul(NulError),
+            Utf8(Utf8Error),
+            NotDspFft,
+            NotDspLoudnessMeter,
+            DriverNotFound(String),
+            UseAfterRelease {
+                function: #function_type,
+            },
+            LengthMismatch {
+                name: &'static str,
+                expected: usize,
+                actual: usize,
+            },
+            InvalidParameter {
+                function: &'static str,
+                reason: &'static str,
+            }
+        }
+
+        impl Display for Error {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                match self {
+                    #fmod_display
+                    Error::EnumBindgen { enumeration, value } => {
+                        write!(f, "FMOD returns unexpected value {} for {} enum", value, enumeration)
+                    }
+                    Error::String(_) => {
+                        write!(f, "invalid UTF-8 when converting C string")
+                    }
+                    Error::Utf8(_) => {
+                        write!(f, "invalid UTF-8 when reading C string")
+                    }
+                    Error::StringNul(_) => {
+                        write!(f, "nul byte was found in the middle, C strings can't contain it")
+                    }
+                    Error::NotDspFft => {
+                        write!(f, "trying get FFT from DSP which not FFT")
+                    }
+                    Error::NotDspLoudnessMeter => {
+                        write!(f, "trying get loudness from DSP which not loudness meter")
+                    }
+                    Error::DriverNotFound(name) => {
+                        write!(f, "output driver {} not found", name)
+                    }
+                    Error::UseAfterRelease { function } => {
+                        write!(f, "{}: handle is used after release", function)
+                    }
+                    Error::LengthMismatch { name, expected, actual } => {
+                        write!(f, "{} has {} items, expected {}", name, actual, expected)
+                    }
+                    Error::InvalidParameter { function, reason } => {
+                        write!(f, "{}: {}", function, reason)
+                    }
+                }
+            }
+        }
+
+        impl std::error::Error for Error {}
+
+        impl Error {
+            pub fn code(&self) -> ffi::FMOD_RESULT {
+                self.result().to_raw()
+            }
+
+            pub fn result(&self) -> FmodResult {
+                match self {
+                    Error::Fmod { code, .. } => *code,
+                    Error::UseAfterRelease { .. } => FmodResult::ErrInvalidHandle,
+                    Error::InvalidParameter { .. } => FmodResult::ErrInvalidParam,
+                    _ => FmodResult::ErrInternal,
+                }
+            }
+
+            pub fn is(&self, result: FmodResult) -> bool {
+                self.result() == result
+            }
+        }
+
+        impl FmodResult {
+            pub fn from_raw(value: ffi::FMOD_RESULT) -> Option<FmodResult> {
+                FmodResult::from(value).ok()
+            }
+
+            pub fn to_raw(self) -> ffi::FMOD_RESULT {
+                self.into()
+            }
+
+            pub fn message(self) -> &'static str {
+                ffi::map_fmod_error(self.into())
+            }
+        }
+
+        impl From<Error> for FmodResult {
+            fn from(error: Error) -> FmodResult {
+                error.result()
+            }
+        }
+
+        impl From<NulError> for Error {
+            fn from(error: NulError) -> Self {
+                Error::StringNul(error)
+            }
+        }
+
+        macro_rules! err_fmod {
+            ($ function : expr , $ code : expr) => {{
+                #error_logging
+                #fmod_construct
+            }};
+        }
+
+        macro_rules! err_enum {
+            ($ enumeration : expr , $ value : expr) => {
+                $crate::Error::EnumBindgen {
+                    enumeration: $enumeration.to_string(),
+                    value: $value.to_string(),
+                }
+            };
+        }
+    }
+}
+
+pub fn generate(api: &Api) -> Result<String, Error> {
+    let code = generate_error_code(api);
+    rustfmt_wrapper::rustfmt(code).map_err(Error::from)
+}
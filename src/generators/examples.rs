@@ -0,0 +1,252 @@
+use std::env;
+use std::path::Path;
+use std::process::Command;
+
+use quote::__private::{Ident, TokenStream};
+
+use crate::models::{Api, Error};
+
+pub fn generate_bevy_example() -> Result<String, Error> {
+    let code = quote! {
+        use bevy::prelude::*;
+        use libfmod::{Error, InitFlags, Studio, StudioInitFlags};
+
+        fn main() -> Result<(), Error> {
+            let studio = Studio::create()?;
+            studio.initialize(1024, StudioInitFlags::NORMAL, InitFlags::NORMAL, None)?;
+            App::new()
+                .add_plugins(DefaultPlugins)
+                .insert_resource(studio)
+                .add_systems(Update, update_studio)
+                .add_systems(Last, release_studio.run_if(on_event::<AppExit>()))
+                .run();
+            Ok(())
+        }
+
+        fn update_studio(studio: Res<Studio>) {
+            if let Err(error) = studio.update() {
+                error!("unable to update FMOD Studio, {:?}", error);
+            }
+        }
+
+        fn release_studio(studio: Res<Studio>) {
+            if let Err(error) = studio.release() {
+                error!("unable to release FMOD Studio, {:?}", error);
+            }
+        }
+    };
+    rustfmt_wrapper::rustfmt(code).map_err(Error::from)
+}
+
+/// Method names of generated API, example is skipped if any function is missing.
+/// Signatures changed by patches are verified by building examples, see [`check_examples`].
+pub fn methods<const N: usize>(api: &Api, functions: [&str; N]) -> Option<[Ident; N]> {
+    let exists = |name: &str| {
+        api.functions
+            .iter()
+            .flat_map(|(_, functions)| functions)
+            .any(|function| function.name == name)
+    };
+    if !functions.iter().all(|name| exists(name)) {
+        return None;
+    }
+    Some(functions.map(|name| format_ident!("{}", api.method_name(name))))
+}
+
+fn generate_load_bank_example(api: &Api) -> Option<TokenStream> {
+    let [create, initialize, load_bank, get_path, get_event_count, release] = methods(
+        api,
+        [
+            "FMOD_Studio_System_Create",
+            "FMOD_Studio_System_Initialize",
+            "FMOD_Studio_System_LoadBankFile",
+            "FMOD_Studio_Bank_GetPath",
+            "FMOD_Studio_Bank_GetEventCount",
+            "FMOD_Studio_System_Release",
+        ],
+    )?;
+    Some(quote! {
+        use libfmod::{Error, InitFlags, LoadBankFlags, Studio, StudioInitFlags};
+
+        fn main() -> Result<(), Error> {
+            let bank = std::env::args().nth(1).unwrap_or("Master.bank".to_string());
+            let studio = Studio::#create()?;
+            studio.#initialize(1024, StudioInitFlags::NORMAL, InitFlags::NORMAL, None)?;
+            let bank = studio.#load_bank(&bank, LoadBankFlags::NORMAL)?;
+            println!("bank {} events {}", bank.#get_path()?, bank.#get_event_count()?);
+            studio.#release()
+        }
+    })
+}
+
+fn generate_play_event_example(api: &Api) -> Option<TokenStream> {
+    let [create, initialize, load_bank, get_event, create_instance, start, update, get_playback_state, release_instance, release] =
+        methods(
+            api,
+            [
+                "FMOD_Studio_System_Create",
+                "FMOD_Studio_System_Initialize",
+                "FMOD_Studio_System_LoadBankFile",
+                "FMOD_Studio_System_GetEvent",
+                "FMOD_Studio_EventDescription_CreateInstance",
+                "FMOD_Studio_EventInstance_Start",
+                "FMOD_Studio_System_Update",
+                "FMOD_Studio_EventInstance_GetPlaybackState",
+                "FMOD_Studio_EventInstance_Release",
+                "FMOD_Studio_System_Release",
+            ],
+        )?;
+    Some(quote! {
+        use libfmod::{Error, InitFlags, LoadBankFlags, PlaybackState, Studio, StudioInitFlags};
+        use std::thread::sleep;
+        use std::time::Duration;
+
+        fn main() -> Result<(), Error> {
+            let mut args = std::env::args().skip(1);
+            let event = args.next().unwrap_or("event:/Music/Level 01".to_string());
+            let studio = Studio::#create()?;
+            studio.#initialize(1024, StudioInitFlags::NORMAL, InitFlags::NORMAL, None)?;
+            for bank in args {
+                studio.#load_bank(&bank, LoadBankFlags::NORMAL)?;
+            }
+            let instance = studio.#get_event(&event)?.#create_instance()?;
+            instance.#start()?;
+            loop {
+                studio.#update()?;
+                if instance.#get_playback_state()? == PlaybackState::Stopped {
+                    break;
+                }
+                sleep(Duration::from_millis(20));
+            }
+            instance.#release_instance()?;
+            studio.#release()
+        }
+    })
+}
+
+fn generate_record_example(api: &Api) -> Option<TokenStream> {
+    let [create, init, create_sound, record_start, update, get_record_position, record_stop, release_sound, release] =
+        methods(
+            api,
+            [
+                "FMOD_System_Create",
+                "FMOD_System_Init",
+                "FMOD_System_CreateSound",
+                "FMOD_System_RecordStart",
+                "FMOD_System_Update",
+                "FMOD_System_GetRecordPosition",
+                "FMOD_System_RecordStop",
+                "FMOD_Sound_Release",
+                "FMOD_System_Release",
+            ],
+        )?;
+    Some(quote! {
+        use libfmod::{ffi, CreateSoundexInfo, Error, InitFlags, Mode, SoundFormat, System};
+        use std::thread::sleep;
+        use std::time::Duration;
+
+        fn main() -> Result<(), Error> {
+            let system = System::#create()?;
+            system.#init(32, InitFlags::NORMAL, None)?;
+            let rate = 48000;
+            let mut exinfo = CreateSoundexInfo::try_from(ffi::FMOD_CREATESOUNDEXINFO::default())?;
+            exinfo.numchannels = 1;
+            exinfo.format = SoundFormat::Pcm16;
+            exinfo.defaultfrequency = rate;
+            exinfo.length = rate as u32 * 2 * 5;
+            let sound = system.#create_sound("", Mode::LOOP_NORMAL | Mode::OPENUSER, Some(exinfo))?;
+            system.#record_start(0, sound, true)?;
+            for _ in 0..50 {
+                system.#update()?;
+                println!("record position {:?}", system.#get_record_position(0)?);
+                sleep(Duration::from_millis(100));
+            }
+            system.#record_stop(0)?;
+            sound.#release_sound()?;
+            system.#release()
+        }
+    })
+}
+
+fn generate_listener_example(api: &Api) -> Option<TokenStream> {
+    let [create, initialize, load_bank, get_event, create_instance, set_listener_attributes, set_3d_attributes, start, update, release] =
+        methods(
+            api,
+            [
+                "FMOD_Studio_System_Create",
+                "FMOD_Studio_System_Initialize",
+                "FMOD_Studio_System_LoadBankFile",
+                "FMOD_Studio_System_GetEvent",
+                "FMOD_Studio_EventDescription_CreateInstance",
+                "FMOD_Studio_System_SetListenerAttributes",
+                "FMOD_Studio_EventInstance_Set3DAttributes",
+                "FMOD_Studio_EventInstance_Start",
+                "FMOD_Studio_System_Update",
+                "FMOD_Studio_System_Release",
+            ],
+        )?;
+    Some(quote! {
+        use libfmod::{Attributes3d, Error, InitFlags, LoadBankFlags, Studio, StudioInitFlags, Vector};
+        use std::thread::sleep;
+        use std::time::Duration;
+
+        fn main() -> Result<(), Error> {
+            let mut args = std::env::args().skip(1);
+            let event = args.next().unwrap_or("event:/Vehicles/Ride-on Mower".to_string());
+            let studio = Studio::#create()?;
+            studio.#initialize(1024, StudioInitFlags::NORMAL, InitFlags::NORMAL, None)?;
+            for bank in args {
+                studio.#load_bank(&bank, LoadBankFlags::NORMAL)?;
+            }
+            studio.#set_listener_attributes(0, &Attributes3d::at(Vector::new(0.0, 0.0, 0.0)), None)?;
+            let instance = studio.#get_event(&event)?.#create_instance()?;
+            instance.#start()?;
+            for step in 0..500 {
+                let angle = step as f32 * 0.02;
+                let position = Vector::new(angle.cos() * 10.0, 0.0, angle.sin() * 10.0);
+                instance.#set_3d_attributes(&Attributes3d::at(position))?;
+                studio.#update()?;
+                sleep(Duration::from_millis(20));
+            }
+            studio.#release()
+        }
+    })
+}
+
+/// Sample programs of destination crate, they break on compilation
+/// when common workflows are changed by generation.
+pub fn generate_examples(api: &Api) -> Result<Vec<(String, String)>, Error> {
+    let examples = [
+        ("load_bank", generate_load_bank_example(api)),
+        ("play_event", generate_play_event_example(api)),
+        ("record", generate_record_example(api)),
+        ("listener_3d", generate_listener_example(api)),
+    ];
+    let mut files = vec![];
+    for (name, code) in examples {
+        if let Some(code) = code {
+            let code = rustfmt_wrapper::rustfmt(code)?;
+            files.push((format!("examples/{}.rs", name), code));
+        }
+    }
+    Ok(files)
+}
+
+/// Builds examples of destination crate with `cargo check --examples` (`CARGO` if set),
+/// so examples are verified against signatures of generated and patched methods.
+pub fn check_examples(destination: &Path) -> Result<(), Error> {
+    let cargo = env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let output = Command::new(&cargo)
+        .arg("check")
+        .arg("--examples")
+        .current_dir(destination)
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::Io(format!(
+            "unable to build examples of {}: {}",
+            destination.display(),
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    Ok(())
+}
@@ -1,14 +1,18 @@
+use std::collections::HashMap;
 use std::num::{ParseFloatError, ParseIntError};
 use std::str::FromStr;
 
 use quote::__private::{Ident, LexError, Literal, TokenStream};
 use quote::quote;
 
+use crate::generators::idents::format_safe_ident;
+use crate::generators::layout::generate_layout_assertions;
 use crate::models::Type::FundamentalType;
 use crate::models::{
-    Api, Argument, Callback, Constant, Enumeration, Error, ErrorStringMapping, Field, Flags,
-    Function, OpaqueType, Pointer, Preset, Structure, Type, TypeAlias, Union,
+    Api, Argument, Callback, Constant, Enumeration, Error, ErrorStringMapping, Field, FieldRole,
+    Flags, Function, OpaqueType, Pointer, Preset, Structure, Type, TypeAlias, Union,
 };
+use crate::patching::dictionary::WASM_UNSUPPORTED_FUNCTIONS;
 
 impl From<rustfmt_wrapper::Error> for Error {
     fn from(error: rustfmt_wrapper::Error) -> Self {
@@ -46,33 +50,209 @@ pub fn generate_opaque_type(value: &OpaqueType) -> TokenStream {
     }
 }
 
-pub fn generate_constant(constant: &Constant) -> Result<TokenStream, Error> {
+pub fn generate_constant(
+    constant: &Constant,
+    known: &mut HashMap<String, i128>,
+) -> Result<TokenStream, Error> {
     let name = format_ident!("{}", &constant.name);
     let value = &constant.value;
 
     let tokens = if value.len() == "0xFFFFFFFFFFFFFFFF".len() && value.starts_with("0x") {
+        known.insert(constant.name.clone(), evaluate_expression(value, known)?);
         let value = TokenStream::from_str(value)?;
         quote! {
             pub const #name: c_ulonglong = #value;
         }
     } else if value.len() == "0xaaaabbcc".len() && value.starts_with("0x") {
+        known.insert(constant.name.clone(), evaluate_expression(value, known)?);
         let value = TokenStream::from_str(value)?;
         quote! {
             pub const #name: c_uint = #value;
         }
     } else {
-        let value = Literal::u32_unsuffixed(value.parse()?);
-        quote! {
-            pub const #name: c_uint = #value;
+        let evaluated = evaluate_expression(value, known)?;
+        known.insert(constant.name.clone(), evaluated);
+        if let Ok(value) = u32::try_from(evaluated) {
+            let value = Literal::u32_unsuffixed(value);
+            quote! {
+                pub const #name: c_uint = #value;
+            }
+        } else if let Ok(value) = i32::try_from(evaluated) {
+            let value = Literal::i32_unsuffixed(value);
+            quote! {
+                pub const #name: c_int = #value;
+            }
+        } else if let Ok(value) = u64::try_from(evaluated) {
+            let value = Literal::u64_unsuffixed(value);
+            quote! {
+                pub const #name: c_ulonglong = #value;
+            }
+        } else if let Ok(value) = i64::try_from(evaluated) {
+            let value = Literal::i64_unsuffixed(value);
+            quote! {
+                pub const #name: c_longlong = #value;
+            }
+        } else {
+            return Err(Error::ParseInt(format!(
+                "constant {} out of range",
+                constant.name
+            )));
         }
     };
 
-    Ok(tokens)
+    let doc = format_declaration_doc(&constant.declaration);
+    Ok(quote! {
+        #doc
+        #tokens
+    })
+}
+
+/// Evaluates integer constant expression of C preprocessor definition,
+/// supports literals, previously defined constants, parentheses,
+/// unary `-` `~` and binary `*` `+` `-` `<<` `>>` `&` `^` `|` operators.
+pub fn evaluate_expression(expression: &str, known: &HashMap<String, i128>) -> Result<i128, Error> {
+    let mut tokens = vec![];
+    let mut chars = expression.chars().peekable();
+    while let Some(&character) = chars.peek() {
+        if character.is_whitespace() {
+            chars.next();
+        } else if character.is_ascii_alphanumeric() || character == '_' {
+            let mut token = String::new();
+            while let Some(&character) = chars.peek() {
+                if !(character.is_ascii_alphanumeric() || character == '_') {
+                    break;
+                }
+                token.push(character);
+                chars.next();
+            }
+            tokens.push(token);
+        } else {
+            chars.next();
+            let mut token = character.to_string();
+            if (character == '<' || character == '>') && chars.peek() == Some(&character) {
+                chars.next();
+                token.push(character);
+            }
+            tokens.push(token);
+        }
+    }
+    let mut position = 0;
+    let value = parse_binary(&tokens, &mut position, 0, known)?;
+    if position != tokens.len() {
+        return Err(Error::ParseInt(format!(
+            "unexpected expression {}",
+            expression
+        )));
+    }
+    Ok(value)
+}
+
+const BINARY_OPERATORS: [&[&str]; 6] = [&["|"], &["^"], &["&"], &["<<", ">>"], &["+", "-"], &["*"]];
+
+fn parse_binary(
+    tokens: &[String],
+    position: &mut usize,
+    level: usize,
+    known: &HashMap<String, i128>,
+) -> Result<i128, Error> {
+    if level == BINARY_OPERATORS.len() {
+        return parse_unary(tokens, position, known);
+    }
+    let mut value = parse_binary(tokens, position, level + 1, known)?;
+    while let Some(operator) = tokens.get(*position) {
+        if !BINARY_OPERATORS[level].contains(&operator.as_str()) {
+            break;
+        }
+        *position += 1;
+        let other = parse_binary(tokens, position, level + 1, known)?;
+        let shift = u32::try_from(other).ok();
+        let result = match operator.as_str() {
+            "|" => Some(value | other),
+            "^" => Some(value ^ other),
+            "&" => Some(value & other),
+            "<<" => shift.and_then(|shift| value.checked_shl(shift)),
+            ">>" => shift.and_then(|shift| value.checked_shr(shift)),
+            "+" => value.checked_add(other),
+            "-" => value.checked_sub(other),
+            _ => value.checked_mul(other),
+        };
+        value = result.ok_or_else(|| {
+            Error::ParseInt(format!("overflow in {} {} {}", value, operator, other))
+        })?;
+    }
+    Ok(value)
+}
+
+fn parse_unary(
+    tokens: &[String],
+    position: &mut usize,
+    known: &HashMap<String, i128>,
+) -> Result<i128, Error> {
+    let token = tokens
+        .get(*position)
+        .ok_or_else(|| Error::ParseInt("unexpected end of expression".to_string()))?;
+    *position += 1;
+    match token.as_str() {
+        "-" => {
+            let value = parse_unary(tokens, position, known)?;
+            value
+                .checked_neg()
+                .ok_or_else(|| Error::ParseInt(format!("overflow in -{}", value)))
+        }
+        "~" => Ok(!parse_unary(tokens, position, known)?),
+        "(" => {
+            let value = parse_binary(tokens, position, 0, known)?;
+            match tokens.get(*position) {
+                Some(token) if token == ")" => {
+                    *position += 1;
+                    Ok(value)
+                }
+                _ => Err(Error::ParseInt("unbalanced parentheses".to_string())),
+            }
+        }
+        token => {
+            if let Some(value) = known.get(token) {
+                return Ok(*value);
+            }
+            let literal = token.trim_end_matches(['u', 'U', 'l', 'L']);
+            let value = match literal.strip_prefix("0x").or(literal.strip_prefix("0X")) {
+                Some(hex) => i128::from_str_radix(hex, 16)?,
+                None => literal.parse()?,
+            };
+            Ok(value)
+        }
+    }
+}
+
+/// Maps fixed width and pointer sized C types directly to Rust primitives,
+/// they have no counterparts in std::os::raw.
+pub fn map_sized_type(name: &str) -> Option<TokenStream> {
+    let rust_type = match name {
+        "size_t" | "uintptr_t" => quote! { usize },
+        "ptrdiff_t" | "intptr_t" => quote! { isize },
+        "int8_t" => quote! { i8 },
+        "uint8_t" => quote! { u8 },
+        "int16_t" => quote! { i16 },
+        "uint16_t" => quote! { u16 },
+        "int32_t" => quote! { i32 },
+        "uint32_t" => quote! { u32 },
+        "int64_t" => quote! { i64 },
+        "uint64_t" => quote! { u64 },
+        _ => return None,
+    };
+    Some(rust_type)
 }
 
 pub fn map_c_type(c_type: &Type) -> TokenStream {
     let name = match c_type {
         FundamentalType(name) => {
+            if let Some(rust_type) = map_sized_type(name) {
+                return rust_type;
+            }
+            // not imported from std::os::raw, so headers without doubles keep imports used
+            if name == "double" {
+                return quote! { f64 };
+            }
             let name = name.replace("unsigned", "u").replace(" ", "");
             format_ident!("c_{}", name)
         }
@@ -145,13 +325,12 @@ pub fn generate_enumeration(enumeration: &Enumeration) -> Result<TokenStream, Er
     })
 }
 
-pub fn format_rust_ident(name: &str) -> Ident {
-    let name = Api::patch_ident(&name);
-    format_ident!("{}", name)
+pub fn format_rust_ident(name: &str, api: &Api) -> Ident {
+    format_safe_ident(name, api.settings.raw_identifiers)
 }
 
-pub fn generate_argument(argument: &Argument) -> TokenStream {
-    let name = format_rust_ident(&argument.name);
+pub fn generate_argument(argument: &Argument, api: &Api) -> TokenStream {
+    let name = format_rust_ident(&argument.name, api);
     let argument_type = format_rust_type(
         &argument.argument_type,
         &argument.as_const,
@@ -180,9 +359,12 @@ impl Callback {
     }
 }
 
-pub fn generate_callback(callback: &Callback) -> TokenStream {
+pub fn generate_callback(callback: &Callback, api: &Api) -> TokenStream {
     let name = format_ident!("{}", callback.name);
-    let arguments = callback.arguments.iter().map(generate_argument);
+    let arguments = callback
+        .arguments
+        .iter()
+        .map(|argument| generate_argument(argument, api));
     let varargs = if callback.varargs.is_some() {
         Some(quote! {, ...})
     } else {
@@ -229,8 +411,8 @@ impl Field {
     }
 }
 
-pub fn generate_field(field: &Field) -> TokenStream {
-    let name = format_rust_ident(&field.name);
+pub fn generate_field(field: &Field, api: &Api) -> TokenStream {
+    let name = format_rust_ident(&field.name, api);
     let field_type = format_rust_type(
         &field.field_type,
         &field.as_const,
@@ -242,10 +424,24 @@ pub fn generate_field(field: &Field) -> TokenStream {
     }
 }
 
-pub fn generate_structure_default(structure: &Structure) -> TokenStream {
+pub fn generate_structure_default(structure: &Structure, api: &Api) -> TokenStream {
     let name = format_ident!("{}", structure.name);
-    match Api::patch_ffi_structure_default(&structure.name) {
-        Some(definition) => definition,
+    let size = structure.fields.iter().find(|field| {
+        api.get_field_role(&structure.name, &field.name) == Some(&FieldRole::SizeOfSelf)
+    });
+    match size {
+        Some(field) => {
+            let field = format_rust_ident(&field.name, api);
+            quote! {
+                impl Default for #name {
+                    fn default() -> Self {
+                        let mut value: Self = unsafe { std::mem::zeroed() };
+                        value.#field = std::mem::size_of::<#name>() as _;
+                        value
+                    }
+                }
+            }
+        }
         None => quote! {
             impl Default for #name {
                 fn default() -> Self {
@@ -256,8 +452,8 @@ pub fn generate_structure_default(structure: &Structure) -> TokenStream {
     }
 }
 
-pub fn generate_structure_union(name: &Ident, union: &Union) -> TokenStream {
-    let fields = union.fields.iter().map(generate_field);
+pub fn generate_structure_union(name: &Ident, union: &Union, api: &Api) -> TokenStream {
+    let fields = union.fields.iter().map(|field| generate_field(field, api));
     quote! {
         #[repr(C)]
         #[derive(Copy, Clone)]
@@ -267,13 +463,45 @@ pub fn generate_structure_union(name: &Ident, union: &Union) -> TokenStream {
     }
 }
 
-pub fn generate_structure(structure: &Structure) -> TokenStream {
+/// Doc comment quoting C declaration the item is generated from.
+fn format_declaration_doc(declaration: &str) -> TokenStream {
+    if declaration.is_empty() {
+        return quote! {};
+    }
+    let doc = if declaration.contains('\n') {
+        format!("```c\n{}\n```", declaration)
+    } else {
+        format!(
+            "`{}`",
+            declaration.split_whitespace().collect::<Vec<_>>().join(" ")
+        )
+    };
+    quote! { #[doc = #doc] }
+}
+
+pub fn generate_structure(structure: &Structure, api: &Api) -> TokenStream {
     let name = format_ident!("{}", structure.name);
-    let fields = structure.fields.iter().map(generate_field);
-    let default = generate_structure_default(&structure);
+    let fields = structure
+        .fields
+        .iter()
+        .map(|field| generate_field(field, api));
+    let default = generate_structure_default(structure, api);
+    let doc = format_declaration_doc(&structure.declaration);
+    if structure.is_union {
+        return quote! {
+            #doc
+            #[repr(C)]
+            #[derive(Copy, Clone)]
+            pub union #name {
+                #(#fields),*
+            }
+            #default
+        };
+    }
     match &structure.union {
         None => {
             quote! {
+                #doc
                 #[repr(C)]
                 #[derive(Debug, Copy, Clone)]
                 pub struct #name {
@@ -284,8 +512,9 @@ pub fn generate_structure(structure: &Structure) -> TokenStream {
         }
         Some(union) => {
             let union_name = format_ident!("{}_UNION", structure.name);
-            let union = generate_structure_union(&union_name, union);
+            let union = generate_structure_union(&union_name, union, api);
             quote! {
+                #doc
                 #[repr(C)]
                 #[derive(Copy, Clone)]
                 pub struct #name {
@@ -299,25 +528,101 @@ pub fn generate_structure(structure: &Structure) -> TokenStream {
     }
 }
 
-pub fn generate_function(function: &Function) -> TokenStream {
+pub fn generate_function(function: &Function, api: &Api) -> TokenStream {
     let name = format_ident!("{}", function.name);
-    let arguments = function.arguments.iter().map(generate_argument);
+    let arguments = function
+        .arguments
+        .iter()
+        .map(|argument| generate_argument(argument, api));
     let return_type = map_c_type(&function.return_type);
+    let varargs = if function.varargs.is_some() {
+        Some(quote! {, ...})
+    } else {
+        None
+    };
+    let gate = if WASM_UNSUPPORTED_FUNCTIONS.contains(&function.name.as_str()) {
+        quote! { #[cfg(not(target_family = "wasm"))] }
+    } else {
+        quote! {}
+    };
+    let doc = format_declaration_doc(&function.declaration);
+    quote! {
+        #doc
+        #gate
+        pub fn #name(#(#arguments),* #varargs) -> #return_type;
+    }
+}
+
+/// Function body standing in for FMOD library when it isn't available, e.g. on docs.rs.
+pub fn generate_stub(function: &Function, api: &Api) -> TokenStream {
+    let name = format_ident!("{}", function.name);
+    let arguments = function
+        .arguments
+        .iter()
+        .map(|argument| generate_argument(argument, api));
+    let return_type = map_c_type(&function.return_type);
+    let doc = format_declaration_doc(&function.declaration);
     quote! {
-        pub fn #name(#(#arguments),*) -> #return_type;
+        #doc
+        pub unsafe extern "C" fn #name(#(#arguments),*) -> #return_type {
+            unimplemented!("FMOD is not linked in docs-only build")
+        }
     }
 }
 
-pub fn generate_extern(_link: &String, api: &Vec<Function>) -> TokenStream {
-    let functions = api.iter().map(generate_function);
+pub fn generate_extern(link: &String, functions: &[Function], api: &Api) -> TokenStream {
+    let declarations = functions
+        .iter()
+        .map(|function| generate_function(function, api));
+    // variadic functions can't be defined in stable Rust, they stay declared
+    let variadic = functions
+        .iter()
+        .filter(|function| function.varargs.is_some())
+        .map(|function| generate_function(function, api));
+    let stubs = functions
+        .iter()
+        .filter(|function| function.varargs.is_none())
+        .map(|function| generate_stub(function, api));
+    let module = format_ident!("{}_stubs", link);
+    let logging = format!("{}L", link);
+    // Emscripten build of FMOD is distributed as static libraries only
     quote! {
+        #[cfg(not(feature = "docs-only"))]
+        #[cfg_attr(
+            all(target_family = "wasm", not(feature = "logging-libs")),
+            link(name = #link, kind = "static")
+        )]
+        #[cfg_attr(
+            all(target_family = "wasm", feature = "logging-libs"),
+            link(name = #logging, kind = "static")
+        )]
+        extern "C" {
+            #(#declarations)*
+        }
+
+        #[cfg(feature = "docs-only")]
         extern "C" {
-            #(#functions)*
+            #(#variadic)*
         }
+
+        #[cfg(feature = "docs-only")]
+        #[allow(unused_variables)]
+        mod #module {
+            use super::*;
+
+            #(#stubs)*
+        }
+
+        #[cfg(feature = "docs-only")]
+        pub use #module::*;
     }
 }
 
-pub fn generate_preset(structure: &Structure, preset: &Preset) -> Result<TokenStream, Error> {
+pub fn generate_preset(
+    structure: &Structure,
+    preset: &Preset,
+    api: &Api,
+) -> Result<TokenStream, Error> {
     let name = format_ident!("{}", preset.name);
     let mut fields: Vec<TokenStream> = vec![];
     for (index, value) in preset.values.iter().enumerate() {
@@ -327,7 +632,7 @@ pub fn generate_preset(structure: &Structure, preset: &Preset) -> Result<TokenSt
             &value[..]
         };
         let value: f32 = value.parse()?;
-        let field = format_rust_ident(&structure.fields[index].name);
+        let field = format_rust_ident(&structure.fields[index].name, api);
         let value = Literal::f32_unsuffixed(value);
         fields.push(quote! {
             #field: #value
@@ -361,14 +666,50 @@ pub fn generate_errors_mapping_code(mapping: &ErrorStringMapping) -> TokenStream
     }
 }
 
+pub fn generate_defines_tests(api: &Api) -> TokenStream {
+    let names = api.constants.iter().map(|constant| &constant.name).chain(
+        api.flags
+            .iter()
+            .flat_map(|flags| &flags.flags)
+            .map(|flag| &flag.name),
+    );
+    let mut assertions = vec![];
+    for name in names {
+        if let Some(value) = api.defines.get(name) {
+            let value = Literal::u64_suffixed(*value);
+            let name = format_ident!("{}", name);
+            assertions.push(quote! {
+                assert_eq!(#name as u64, #value, stringify!(#name));
+            });
+        }
+    }
+    if assertions.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        #[cfg(test)]
+        mod defines {
+            use super::*;
+
+            #[test]
+            fn values_match_headers() {
+                #(#assertions)*
+            }
+        }
+    }
+}
+
 pub fn generate_ffi_code(api: &Api) -> Result<TokenStream, Error> {
     let opaque_types: Vec<TokenStream> =
         api.opaque_types.iter().map(generate_opaque_type).collect();
 
     let mut constants = vec![];
+    let mut known = HashMap::new();
     for constant in &api.constants {
-        constants.push(generate_constant(constant)?);
+        constants.push((&constant.name, generate_constant(constant, &mut known)?));
     }
+    constants.sort_by(|a, b| a.0.cmp(b.0));
+    let constants = constants.into_iter().map(|(_, constant)| constant);
 
     let type_aliases: Vec<TokenStream> = api.type_aliases.iter().map(generate_type_alias).collect();
 
@@ -377,7 +718,11 @@ pub fn generate_ffi_code(api: &Api) -> Result<TokenStream, Error> {
         enumerations.push(generate_enumeration(enumeration)?);
     }
 
-    let callbacks: Vec<TokenStream> = api.callbacks.iter().map(generate_callback).collect();
+    let callbacks: Vec<TokenStream> = api
+        .callbacks
+        .iter()
+        .map(|callback| generate_callback(callback, api))
+        .collect();
 
     let mut flags = vec![];
     for flag in &api.flags {
@@ -386,12 +731,12 @@ pub fn generate_ffi_code(api: &Api) -> Result<TokenStream, Error> {
 
     let mut structures = vec![];
     for structure in &api.structures {
-        structures.push(generate_structure(structure));
+        structures.push(generate_structure(structure, api));
     }
 
     let mut libraries = vec![];
     for (link, functions) in &api.functions {
-        libraries.push(generate_extern(link, functions));
+        libraries.push(generate_extern(link, functions, api));
     }
 
     let mut presets = vec![];
@@ -401,7 +746,7 @@ pub fn generate_ffi_code(api: &Api) -> Result<TokenStream, Error> {
         .find(|structure| structure.name == "FMOD_REVERB_PROPERTIES")
     {
         for preset in &api.presets {
-            presets.push(generate_preset(structure, preset)?);
+            presets.push(generate_preset(structure, preset, api)?);
         }
     }
 
@@ -411,6 +756,9 @@ pub fn generate_ffi_code(api: &Api) -> Result<TokenStream, Error> {
         Some(generate_errors_mapping_code(&api.errors))
     };
 
+    let tests = generate_defines_tests(api);
+    let layout = generate_layout_assertions(api);
+
     Ok(quote! {
         #![allow(non_camel_case_types)]
         #![allow(non_snake_case)]
@@ -427,6 +775,8 @@ pub fn generate_ffi_code(api: &Api) -> Result<TokenStream, Error> {
         #(#callbacks)*
         #(#libraries)*
         #errors
+        #tests
+        #layout
     })
 }
 
@@ -434,3 +784,122 @@ pub fn generate(api: &Api) -> Result<String, Error> {
     let code = generate_ffi_code(api)?;
     rustfmt_wrapper::rustfmt(code).map_err(Error::from)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn evaluate(expression: &str) -> Result<i128, Error> {
+        evaluate_expression(expression, &HashMap::new())
+    }
+
+    #[test]
+    fn evaluates_shifts() {
+        assert_eq!(evaluate("1 << 4").unwrap(), 16);
+        assert_eq!(evaluate("0x100 >> 8").unwrap(), 1);
+        assert_eq!(evaluate("(1 << 2) + 1").unwrap(), 5);
+    }
+
+    #[test]
+    fn evaluates_ors() {
+        assert_eq!(evaluate("0x1 | 0x2 | 0x8").unwrap(), 11);
+        assert_eq!(evaluate("1 << 1 | 1 << 3").unwrap(), 10);
+    }
+
+    #[test]
+    fn evaluates_negatives() {
+        assert_eq!(evaluate("-1").unwrap(), -1);
+        assert_eq!(evaluate("-(2 * 3)").unwrap(), -6);
+        assert_eq!(evaluate("~0").unwrap(), -1);
+    }
+
+    #[test]
+    fn evaluates_known_constants_and_suffixes() {
+        let known = HashMap::from([("FMOD_MAX_CHANNEL_WIDTH".to_string(), 32)]);
+        assert_eq!(
+            evaluate_expression("FMOD_MAX_CHANNEL_WIDTH * 2", &known).unwrap(),
+            64
+        );
+        assert_eq!(evaluate("0xFFFFFFFFu").unwrap(), 0xFFFFFFFF);
+    }
+
+    #[test]
+    fn rejects_overflow() {
+        assert!(evaluate("1 << 200").is_err());
+        assert!(evaluate("1 << -1").is_err());
+        assert!(evaluate("0x7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF + 1").is_err());
+        assert!(evaluate("0x7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF * 2").is_err());
+    }
+
+    const SIZED_TYPES: &[(&str, &str)] = &[
+        ("size_t", "usize"),
+        ("uintptr_t", "usize"),
+        ("intptr_t", "isize"),
+        ("ptrdiff_t", "isize"),
+        ("int8_t", "i8"),
+        ("uint8_t", "u8"),
+        ("int16_t", "i16"),
+        ("uint16_t", "u16"),
+        ("int32_t", "i32"),
+        ("uint32_t", "u32"),
+        ("int64_t", "i64"),
+        ("uint64_t", "u64"),
+    ];
+
+    fn fundamental(name: &str) -> Type {
+        FundamentalType(name.to_string())
+    }
+
+    #[test]
+    fn maps_sized_types_to_primitives() {
+        for (c_type, rust_type) in SIZED_TYPES {
+            assert_eq!(map_c_type(&fundamental(c_type)).to_string(), *rust_type);
+        }
+    }
+
+    #[test]
+    fn formats_sized_type_pointers() {
+        let pointer = Some(Pointer::NormalPointer("*".into()));
+        for (c_type, rust_type) in SIZED_TYPES {
+            let c_type = fundamental(c_type);
+            assert_eq!(
+                format_rust_type(&c_type, &None, &pointer, &None).to_string(),
+                format!("* mut {}", rust_type)
+            );
+            assert_eq!(
+                format_rust_type(&c_type, &Some("const".into()), &pointer, &None).to_string(),
+                format!("* const {}", rust_type)
+            );
+        }
+    }
+
+    #[test]
+    fn maps_double_to_f64() {
+        let double = fundamental("double");
+        assert_eq!(map_c_type(&double).to_string(), "f64");
+        let pointer = Some(Pointer::NormalPointer("*".into()));
+        assert_eq!(
+            format_rust_type(&double, &None, &pointer, &None).to_string(),
+            "* mut f64"
+        );
+        assert_eq!(
+            format_rust_type(&double, &None, &None, &Some(quote! { 4 })).to_string(),
+            "[f64 ; 4 as usize]"
+        );
+    }
+
+    #[test]
+    fn keeps_c_types_for_platform_integers() {
+        assert_eq!(map_c_type(&fundamental("int")).to_string(), "c_int");
+        assert_eq!(
+            map_c_type(&fundamental("unsigned long long")).to_string(),
+            "c_ulonglong"
+        );
+    }
+
+    #[test]
+    fn rejects_unbalanced_parentheses() {
+        assert!(evaluate("(1 << 2").is_err());
+        assert!(evaluate("1 2").is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use crate::lib::{is_flags_type, is_value_type};
 use crate::models::{Api, Error, Flag, Flags};
 
 use crate::patching::dictionary::RENAMES;
@@ -18,7 +19,7 @@ fn format_flags_struct_ident(key: &str) -> String {
     format!("{name}")
 }
 
-fn get_shared_prefix(flags: &Vec<Flag>) -> String {
+pub fn get_shared_prefix(flags: &Vec<Flag>) -> String {
     let pattern: Vec<&str> = flags[0].name.split("_").collect();
     let mut words = pattern.len();
     for other in flags {
@@ -40,33 +41,37 @@ fn get_shared_prefix(flags: &Vec<Flag>) -> String {
     words.join("_")
 }
 
+pub fn format_flag_name(prefix: &str, flag: &str) -> String {
+    let mut name = flag.to_string();
+    if name.starts_with("FMOD_CHANNELMASK_5POINT1") {
+        name = name.replace("FMOD_CHANNELMASK_5POINT1", "FMOD_CHANNELMASK_MASK_5POINT1")
+    }
+    if name.starts_with("FMOD_CHANNELMASK_7POINT") {
+        name = name.replace("FMOD_CHANNELMASK_7POINT", "FMOD_CHANNELMASK_MASK_7POINT")
+    }
+
+    name = name.replace(&format!("{prefix}_"), "");
+    if name.starts_with("3D_") {
+        name = name.replace("3D_", "");
+        name += "_3D";
+    }
+    if name == "2D" {
+        name = "FMOD_2D".to_string();
+    }
+    if name == "3D" {
+        name = "FMOD_3D".to_string();
+    }
+    name
+}
+
 pub fn generate_flags(flags: &Flags) -> (String, String) {
     let prefix = get_shared_prefix(&flags.flags);
     let name = format_flags_struct_ident(&prefix);
 
     let mut variants: Vec<String> = vec![];
 
-    let prefix_replace = format!("{prefix}_");
     for flag in &flags.flags {
-        let mut name = flag.name.to_string();
-        if name.starts_with("FMOD_CHANNELMASK_5POINT1") {
-            name = name.replace("FMOD_CHANNELMASK_5POINT1", "FMOD_CHANNELMASK_MASK_5POINT1")
-        }
-        if name.starts_with("FMOD_CHANNELMASK_7POINT") {
-            name = name.replace("FMOD_CHANNELMASK_7POINT", "FMOD_CHANNELMASK_MASK_7POINT")
-        }
-
-        name = name.replace(&prefix_replace, "");
-        if name.starts_with("3D_") {
-            name = name.replace("3D_", "");
-            name += "_3D";
-        }
-        if name == "2D" {
-            name = "FMOD_2D".to_string();
-        }
-        if name == "3D" {
-            name = "FMOD_3D".to_string();
-        }
+        let name = format_flag_name(&prefix, &flag.name);
         let ffi = &flag.name;
         variants.push(format!("        const {name} = ffi::{ffi};"));
     }
@@ -106,6 +111,10 @@ bitflags! {
     let mut into_traits = String::new();
 
     for flag in api.flags.iter() {
+        // first-class types of lib, see generate_flags_type and generate_value_type
+        if is_flags_type(&flag.name) || is_value_type(&flag.name) {
+            continue;
+        }
         let (code, into) = generate_flags(flag);
         source += &code;
         into_traits += &into;
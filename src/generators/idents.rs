@@ -0,0 +1,69 @@
+use convert_case::{Case, Casing};
+use quote::__private::Ident;
+
+use crate::patching::dictionary::KEYWORDS;
+
+/// Keywords which can't be written as raw identifiers.
+const NON_RAW_KEYWORDS: &[&str] = &["crate", "self", "super", "Self"];
+
+/// Makes C name usable as Rust identifier. Names starting with digit get underscore prefix,
+/// keywords get underscore suffix or raw `r#` prefix if raw identifiers are enabled.
+pub fn sanitize_ident(name: &str, raw: bool) -> String {
+    let name = if name.starts_with(|character: char| character.is_ascii_digit()) {
+        format!("_{}", name)
+    } else {
+        name.to_string()
+    };
+    if !KEYWORDS.contains(&&*name.to_lowercase()) {
+        return name;
+    }
+    if raw && !NON_RAW_KEYWORDS.contains(&&*name) {
+        if KEYWORDS.contains(&&*name) {
+            format!("r#{}", name)
+        } else {
+            name
+        }
+    } else {
+        format!("{}_", name)
+    }
+}
+
+pub fn format_safe_ident(name: &str, raw: bool) -> Ident {
+    format_ident!("{}", sanitize_ident(name, raw))
+}
+
+/// Snake case identifier of C name, 3D is kept as single word.
+pub fn format_snake_ident(name: &str, raw: bool) -> Ident {
+    let name = name.replace("3D", "-3d-");
+    let name = name.to_case(Case::Snake);
+    format_safe_ident(&name, raw)
+}
+
+/// Moves leading dimension of camel case name to the end, e.g. `3DAttributes` to `Attributes3d`.
+pub fn move_dimension_prefix(key: &str) -> String {
+    for dimension in ["3D", "2D"] {
+        if let Some(rest) = key.strip_prefix(dimension) {
+            return format!("{}{}", rest, dimension.to_lowercase()).to_case(Case::UpperCamel);
+        }
+    }
+    key.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn suffixes_keywords_by_default() {
+        assert_eq!(sanitize_ident("type", false), "type_");
+        assert_eq!(sanitize_ident("3d", false), "_3d");
+        assert_eq!(sanitize_ident("volume", false), "volume");
+    }
+
+    #[test]
+    fn uses_raw_identifiers_when_enabled() {
+        assert_eq!(sanitize_ident("type", true), "r#type");
+        assert_eq!(sanitize_ident("self", true), "self_");
+        assert_eq!(sanitize_ident("type", false), "type_");
+    }
+}
@@ -0,0 +1,112 @@
+use quote::__private::TokenStream;
+
+use crate::generators::examples::methods;
+use crate::models::{Api, Error};
+
+fn generate_advanced_settings_test(api: &Api) -> Option<TokenStream> {
+    if !api.is_structure("FMOD_ADVANCEDSETTINGS") {
+        return None;
+    }
+    let [create, set_advanced_settings, get_advanced_settings, release] = methods(
+        api,
+        [
+            "FMOD_System_Create",
+            "FMOD_System_SetAdvancedSettings",
+            "FMOD_System_GetAdvancedSettings",
+            "FMOD_System_Release",
+        ],
+    )?;
+    Some(quote! {
+        #[test]
+        fn advanced_settings_round_trip() -> Result<(), Error> {
+            let system = System::#create()?;
+            let mut settings = system.#get_advanced_settings()?;
+            settings.max_mpeg_codecs = 16;
+            settings.max_vorbis_codecs = 24;
+            settings.vol_0_virtualvol = 0.25;
+            settings.default_decode_buffer_size = 800;
+            settings.resampler_method = DspResampler::Cubic;
+            settings.random_seed = 7;
+            settings.asio_channel_list = vec![];
+            settings.asio_speaker_list = vec![];
+            let expected = settings.clone();
+            system.#set_advanced_settings(settings)?;
+            let retrieved = system.#get_advanced_settings()?;
+            assert_eq!(retrieved.max_mpeg_codecs, expected.max_mpeg_codecs);
+            assert_eq!(retrieved.max_vorbis_codecs, expected.max_vorbis_codecs);
+            assert_eq!(retrieved.vol_0_virtualvol, expected.vol_0_virtualvol);
+            assert_eq!(retrieved.default_decode_buffer_size, expected.default_decode_buffer_size);
+            assert_eq!(retrieved.resampler_method, expected.resampler_method);
+            assert_eq!(retrieved.random_seed, expected.random_seed);
+            assert!(retrieved.asio_channel_list.is_empty());
+            system.#release()
+        }
+
+        #[test]
+        fn advanced_settings_speakers_must_match_channels() -> Result<(), Error> {
+            let system = System::#create()?;
+            let mut settings = system.#get_advanced_settings()?;
+            settings.asio_channel_list = vec!["left".to_string(), "right".to_string()];
+            settings.asio_speaker_list = vec![Speaker::FrontLeft];
+            let result = system.#set_advanced_settings(settings);
+            assert!(matches!(result, Err(Error::LengthMismatch { expected: 2, actual: 1, .. })));
+            system.#release()
+        }
+    })
+}
+
+fn generate_studio_advanced_settings_test(api: &Api) -> Option<TokenStream> {
+    if !api.is_structure("FMOD_STUDIO_ADVANCEDSETTINGS") {
+        return None;
+    }
+    let [create, set_advanced_settings, get_advanced_settings, release] = methods(
+        api,
+        [
+            "FMOD_Studio_System_Create",
+            "FMOD_Studio_System_SetAdvancedSettings",
+            "FMOD_Studio_System_GetAdvancedSettings",
+            "FMOD_Studio_System_Release",
+        ],
+    )?;
+    Some(quote! {
+        #[test]
+        fn studio_advanced_settings_round_trip() -> Result<(), Error> {
+            let studio = Studio::#create()?;
+            let mut settings = studio.#get_advanced_settings()?;
+            settings.commandqueuesize = 65536;
+            settings.handleinitialsize = 8192;
+            settings.studioupdateperiod = 25;
+            settings.encryptionkey = "round-trip key".to_string();
+            let expected = settings.clone();
+            studio.#set_advanced_settings(settings)?;
+            let retrieved = studio.#get_advanced_settings()?;
+            assert_eq!(retrieved.commandqueuesize, expected.commandqueuesize);
+            assert_eq!(retrieved.handleinitialsize, expected.handleinitialsize);
+            assert_eq!(retrieved.studioupdateperiod, expected.studioupdateperiod);
+            assert_eq!(retrieved.encryptionkey, expected.encryptionkey);
+            studio.#release()
+        }
+    })
+}
+
+/// Tests of destination crate running against FMOD libraries,
+/// they check conversions which can't be verified by compiler.
+pub fn generate_integration_tests(api: &Api) -> Result<Vec<(String, String)>, Error> {
+    let tests: Vec<TokenStream> = [
+        generate_advanced_settings_test(api),
+        generate_studio_advanced_settings_test(api),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if tests.is_empty() {
+        return Ok(vec![]);
+    }
+    let code = quote! {
+        use libfmod::*;
+
+        #(#tests)*
+    };
+    let code = rustfmt_wrapper::rustfmt(code)?;
+    Ok(vec![("tests/advanced_settings.rs".to_string(), code)])
+}
@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use quote::__private::{Literal, TokenStream};
+
+use crate::models::{Api, Error, LayoutTable};
+
+/// C program printing sizes of all parsed structures, compiled against FMOD headers
+/// so expected sizes don't depend on parser of this generator.
+fn generate_layout_probe(api: &Api) -> String {
+    let mut headers = vec![
+        "fmod.h",
+        "fmod_codec.h",
+        "fmod_output.h",
+        "fmod_dsp.h",
+        "fmod_dsp_effects.h",
+    ];
+    if !api.settings.core_only {
+        headers.push("fmod_studio.h");
+    }
+    let mut probe = String::new();
+    for header in headers {
+        probe.push_str(&format!("#include \"{}\"\n", header));
+    }
+    probe.push_str("#include <stdio.h>\n\nint main(void) {\n");
+    probe.push_str("    printf(\"pointer %u\\n\", (unsigned)(sizeof(void *) * 8));\n");
+    // nested structures lowered from anonymous members don't exist in headers
+    let declared = api
+        .structures
+        .iter()
+        .filter(|structure| !structure.declaration.is_empty());
+    for structure in declared {
+        probe.push_str(&format!(
+            "    printf(\"{0} %u\\n\", (unsigned)sizeof({0}));\n",
+            structure.name
+        ));
+    }
+    probe.push_str("    return 0;\n}\n");
+    probe
+}
+
+/// Compiles layout probe with `CC` compiler (`cc` by default) and returns printed table.
+pub fn capture_layouts(api: &Api, sdk: &Path) -> Result<String, Error> {
+    let directory = env::temp_dir().join("libfmod-gen-layout");
+    fs::create_dir_all(&directory)?;
+    let source = directory.join("probe.c");
+    let binary = directory.join("probe");
+    fs::write(&source, generate_layout_probe(api))?;
+    let compiler = env::var("CC").unwrap_or_else(|_| "cc".to_string());
+    let output = Command::new(&compiler)
+        .arg("-I")
+        .arg(sdk.join("api/core/inc"))
+        .arg("-I")
+        .arg(sdk.join("api/studio/inc"))
+        .arg(&source)
+        .arg("-o")
+        .arg(&binary)
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::Io(format!(
+            "unable to compile layout probe with {}: {}",
+            compiler,
+            String::from_utf8_lossy(&output.stderr)
+        )));
+    }
+    let output = Command::new(&binary).output()?;
+    if !output.status.success() {
+        return Err(Error::Io("layout probe failed".to_string()));
+    }
+    let table = String::from_utf8_lossy(&output.stdout);
+    Ok(format!(
+        "# structure sizes captured from FMOD headers by C compiler\n{}",
+        table
+    ))
+}
+
+/// Parses table of `name size` lines, `pointer` line is target pointer width in bits.
+pub fn parse_layouts(table: &str) -> Result<LayoutTable, Error> {
+    let mut pointer_width = None;
+    let mut sizes = BTreeMap::new();
+    for line in table.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (name, size) = line.split_once(' ').ok_or(Error::FileMalformed)?;
+        let size: usize = size.trim().parse()?;
+        if name == "pointer" {
+            pointer_width = Some(size);
+        } else {
+            sizes.insert(name.to_string(), size);
+        }
+    }
+    Ok(LayoutTable {
+        pointer_width: pointer_width.ok_or(Error::FileMalformed)?,
+        sizes,
+    })
+}
+
+/// Generates compile time assertions of structure sizes captured from C compiler,
+/// so mistakes in parsing or type mapping fail the build of the crate.
+pub fn generate_layout_assertions(api: &Api) -> TokenStream {
+    let table = match &api.layouts {
+        Some(table) => table,
+        None => return quote! {},
+    };
+    let mut assertions = vec![];
+    for structure in &api.structures {
+        if let Some(size) = table.sizes.get(&structure.name) {
+            let name = format_ident!("{}", structure.name);
+            let size = Literal::usize_unsuffixed(*size);
+            let message = format!("{} size differs from C compiler", structure.name);
+            assertions.push(quote! {
+                const _: () = assert!(std::mem::size_of::<#name>() == #size, #message);
+            });
+        }
+    }
+    if assertions.is_empty() {
+        return quote! {};
+    }
+    let width = table.pointer_width.to_string();
+    quote! {
+        #[cfg(target_pointer_width = #width)]
+        mod layout {
+            use super::*;
+
+            #(#assertions)*
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Structure;
+
+    fn structure(name: &str, declaration: &str) -> Structure {
+        Structure {
+            name: name.to_string(),
+            fields: vec![],
+            union: None,
+            is_union: false,
+            declaration: declaration.to_string(),
+        }
+    }
+
+    #[test]
+    fn skips_lowered_structures_in_probe() {
+        let mut api = Api::default();
+        api.structures.push(structure(
+            "FMOD_DSP_PARAMETER_DESC",
+            "typedef struct FMOD_DSP_PARAMETER_DESC { ... } FMOD_DSP_PARAMETER_DESC;",
+        ));
+        api.structures
+            .push(structure("FMOD_DSP_PARAMETER_DESC_UNION", ""));
+        let probe = generate_layout_probe(&api);
+        assert!(probe.contains("sizeof(FMOD_DSP_PARAMETER_DESC))"));
+        assert!(!probe.contains("FMOD_DSP_PARAMETER_DESC_UNION"));
+    }
+}
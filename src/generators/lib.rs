@@ -1,15 +1,22 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::AddAssign;
 use std::str::FromStr;
 
 use convert_case::{Case, Casing};
-use quote::__private::{Ident, TokenStream};
+use quote::__private::{Ident, TokenStream, TokenTree};
 
 use crate::ffi;
 use crate::ffi::describe_pointer;
+use crate::flags::{format_flag_name, get_shared_prefix};
+use crate::generators::idents::format_snake_ident;
 use crate::models::Type::{FundamentalType, UserType};
 use crate::models::{
-    Api, Argument, Enumeration, Error, Field, Function, Modifier, Pointer, Structure, Type,
+    Api, Argument, Callback, Enumeration, Error, Field, FieldDirection, FieldRole, Function,
+    FunctionRole, Modifier, Pointer, SdkVersion, StringConversion, Structure, Type, Unit,
+};
+use crate::patching::dictionary::{
+    FEATURE_GATED_FUNCTIONS, LAYOUT_COMPATIBLE_STRUCTURES, TAGGED_UNIONS,
+    WASM_UNSUPPORTED_FUNCTIONS,
 };
 
 #[derive(Debug, Clone, PartialEq)]
@@ -24,7 +31,7 @@ pub struct Lib {
     pub structs: Vec<Struct>,
 }
 
-fn extract_struct_key(name: &str) -> String {
+pub fn extract_struct_key(name: &str) -> String {
     match name.rfind('_') {
         Some(index) => name[..index].to_uppercase(),
         None => name.to_string(),
@@ -54,7 +61,7 @@ fn format_variant(enumeration: &str, name: &str) -> Ident {
     format_ident!("{}", name)
 }
 
-fn extract_method_name(name: &str) -> String {
+pub fn extract_method_name(name: &str) -> String {
     match name.rfind('_') {
         Some(index) => name[index..]
             .to_string()
@@ -64,16 +71,20 @@ fn extract_method_name(name: &str) -> String {
     }
 }
 
-fn format_struct_ident(key: &str) -> Ident {
+pub fn format_struct_ident(key: &str) -> Ident {
     let name = Api::patch_structure_name(key);
     format_ident!("{}", name)
 }
 
-pub fn format_argument_ident(name: &str) -> Ident {
-    let name = name.replace("3D", "-3d-");
-    let name = name.to_case(Case::Snake);
-    let name = Api::patch_ident(&name);
-    format_ident!("{}", name)
+pub fn format_argument_ident(name: &str, api: &Api) -> Ident {
+    format_snake_ident(name, api.settings.raw_identifiers)
+}
+
+fn unsupported_type(pointer: &str, c_type: &Type) -> Error {
+    let name = match c_type {
+        FundamentalType(name) | UserType(name) => name,
+    };
+    Error::Unsupported(format!("{} {}", pointer, name).trim().to_string())
 }
 
 pub fn format_rust_type(
@@ -82,8 +93,13 @@ pub fn format_rust_type(
     pointer: &Option<Pointer>,
     as_array: &Option<TokenStream>,
     api: &Api,
-) -> TokenStream {
+) -> Result<TokenStream, Error> {
     let ptr = describe_pointer(as_const, pointer);
+    if let (FundamentalType(name), "", Some(_)) = (c_type, ptr, as_array) {
+        if name == "char" {
+            return Ok(quote! { String });
+        }
+    }
     let tokens = match c_type {
         FundamentalType(name) => match (ptr, &name[..]) {
             ("*const", "char") => quote! { String },
@@ -93,6 +109,9 @@ pub fn format_rust_type(
             ("*mut", "int") => quote! { Vec<i32> },
             ("*mut", "float") => quote! { Vec<f32> },
             ("*mut *mut", "float") => quote! { Vec<f32> },
+            ("*mut", "double") => quote! { Vec<f64> },
+            ("*const", "unsigned char") => quote! { Vec<u8> },
+            ("*mut", "unsigned char") => quote! { Vec<u8> },
             ("*mut *mut", "char") => quote! { Vec<String> },
             ("", "unsigned char") => quote! { u8 },
             ("", "char") => quote! { c_char },
@@ -105,10 +124,14 @@ pub fn format_rust_type(
             ("", "unsigned long long") => quote! { u64 },
             ("", "unsigned long") => quote! { u64 },
             ("", "float") => quote! { f32 },
-            _ => {
-                let name = format_ident!("{}", name);
-                quote! { Box<#name> }
-            }
+            ("", "double") => quote! { f64 },
+            _ => match (ptr, ffi::map_sized_type(name)) {
+                ("", Some(rust_type)) => rust_type,
+                _ => {
+                    let name = format_ident!("{}", name);
+                    quote! { Box<#name> }
+                }
+            },
         },
         UserType(name) => match (ptr, api.describe_user_type(name)) {
             ("*mut", UserTypeDesc::OpaqueType) => {
@@ -135,33 +158,68 @@ pub fn format_rust_type(
                 let name = format_struct_ident(name);
                 quote! { #name }
             }
+            ("", UserTypeDesc::Union) => {
+                let name = format_ident!("{}", name);
+                quote! { ffi::#name }
+            }
             ("", UserTypeDesc::Enumeration) => {
                 let name = format_struct_ident(name);
                 quote! { #name }
             }
+            ("", UserTypeDesc::Flags) if is_flags_type(name) => {
+                let name = format_flags_type(name);
+                quote! { #name }
+            }
             ("", _) => {
                 let name = format_ident!("{}", name);
                 quote! { ffi::#name }
             }
-            _ => quote! { err },
+            _ => return Err(unsupported_type(ptr, c_type)),
         },
     };
-    match as_array {
+    let tokens = match as_array {
         None => tokens,
         Some(dimension) => {
             quote! {
                 [#tokens; #dimension as usize]
             }
         }
+    };
+    Ok(tokens)
+}
+
+fn enumerator_values(enumeration: &Enumeration) -> Option<Vec<i32>> {
+    let mut value: i32 = -1;
+    let mut values = vec![];
+    for enumerator in &enumeration.enumerators {
+        value = match &enumerator.value {
+            None => value + 1,
+            Some(repr) => repr.parse().ok()?,
+        };
+        if !enumerator.name.ends_with("FORCEINT") {
+            values.push(value);
+        }
     }
+    Some(values)
 }
 
 pub fn generate_enumeration(enumeration: &Enumeration) -> TokenStream {
     let name = format_struct_ident(&enumeration.name);
 
+    // explicit discriminants are only possible when C values are unique,
+    // some headers declare aliases for the same value
+    let discriminants = match enumerator_values(enumeration) {
+        Some(values) => {
+            let unique: HashSet<&i32> = values.iter().collect();
+            unique.len() == values.len() && !unique.contains(&i32::MIN)
+        }
+        None => false,
+    };
+
     let mut variants = vec![];
     let mut enumerator_arms = vec![];
     let mut variant_arms = vec![];
+    let mut lossy_arms = vec![];
 
     for enumerator in &enumeration.enumerators {
         if enumerator.name.ends_with("FORCEINT") {
@@ -171,22 +229,40 @@ pub fn generate_enumeration(enumeration: &Enumeration) -> TokenStream {
         let enumerator = format_ident!("{}", enumerator.name);
         enumerator_arms.push(quote! {#name::#variant => ffi::#enumerator});
         variant_arms.push(quote! {ffi::#enumerator => Ok(#name::#variant)});
-        variants.push(variant);
+        lossy_arms.push(quote! {ffi::#enumerator => #name::#variant});
+        if discriminants {
+            variants.push(quote! {#variant = ffi::#enumerator});
+        } else {
+            variants.push(quote! {#variant});
+        }
     }
 
     let enumeration_name = &enumeration.name;
     let enumeration = format_ident!("{}", enumeration_name);
+    let (repr, unrecognized) = if discriminants {
+        (
+            quote! { #[repr(i32)] },
+            quote! { Unrecognized(i32) = i32::MIN },
+        )
+    } else {
+        (quote! {}, quote! { Unrecognized(i32) })
+    };
 
     quote! {
         #[derive(Debug, Clone, Copy, PartialEq)]
+        #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+        #repr
         pub enum #name {
-            #(#variants),*
+            #(#variants),*,
+            /// Value not known at generation time, e.g. added by newer FMOD version.
+            #unrecognized
         }
 
         impl From<#name> for ffi::#enumeration {
             fn from(value: #name) -> ffi::#enumeration {
                 match value {
-                    #(#enumerator_arms),*
+                    #(#enumerator_arms),*,
+                    #name::Unrecognized(value) => value
                 }
             }
         }
@@ -198,17 +274,322 @@ pub fn generate_enumeration(enumeration: &Enumeration) -> TokenStream {
                     _ => Err(err_enum!(#enumeration_name, value)),
                 }
             }
+
+            pub fn from_raw_lossy(value: ffi::#enumeration) -> #name {
+                match value {
+                    #(#lossy_arms),*,
+                    _ => #name::Unrecognized(value),
+                }
+            }
+        }
+    }
+}
+
+/// Flags used as first-class types instead of bitflags of optional flags module.
+pub const FLAGS_TYPES: &[(&str, &str)] = &[
+    ("FMOD_INITFLAGS", "InitFlags"),
+    ("FMOD_STUDIO_INITFLAGS", "StudioInitFlags"),
+    ("FMOD_MODE", "Mode"),
+    ("FMOD_TIMEUNIT", "TimeUnit"),
+    ("FMOD_SYSTEM_CALLBACK_TYPE", "SystemCallbackType"),
+    (
+        "FMOD_STUDIO_SYSTEM_CALLBACK_TYPE",
+        "StudioSystemCallbackType",
+    ),
+    ("FMOD_CHANNELMASK", "ChannelMask"),
+    ("FMOD_THREAD_AFFINITY", "ThreadAffinity"),
+    ("FMOD_STUDIO_LOAD_BANK_FLAGS", "LoadBankFlags"),
+    ("FMOD_STUDIO_COMMANDREPLAY_FLAGS", "CommandReplayFlags"),
+];
+
+/// Defines which are named values rather than bits, generated as newtypes with constants.
+pub const VALUE_TYPES: &[(&str, &str)] = &[
+    ("FMOD_THREAD_PRIORITY", "ThreadPriority"),
+    ("FMOD_THREAD_STACK_SIZE", "ThreadStackSize"),
+];
+
+pub fn is_flags_type(key: &str) -> bool {
+    FLAGS_TYPES.iter().any(|(flags, _)| *flags == key)
+}
+
+pub fn is_value_type(key: &str) -> bool {
+    VALUE_TYPES.iter().any(|(values, _)| *values == key)
+}
+
+fn format_flags_type(key: &str) -> Ident {
+    match FLAGS_TYPES.iter().find(|(flags, _)| *flags == key) {
+        Some((_, name)) => format_ident!("{}", name),
+        None => format_ident!("{}", key),
+    }
+}
+
+/// Speaker layout helpers matching speaker mode and speaker names with channel mask flags.
+pub fn generate_speaker_layout(api: &Api) -> TokenStream {
+    let find_enumeration = |name: &str| {
+        api.enumerations
+            .iter()
+            .find(|enumeration| enumeration.name == name)
+    };
+    let (modes, speakers, masks) = match (
+        find_enumeration("FMOD_SPEAKERMODE"),
+        find_enumeration("FMOD_SPEAKER"),
+        api.flags
+            .iter()
+            .find(|flags| flags.name == "FMOD_CHANNELMASK"),
+    ) {
+        (Some(modes), Some(speakers), Some(masks)) => (modes, speakers, masks),
+        _ => return quote! {},
+    };
+    let find_mask = |enumerator: &str, prefix: &str| {
+        let name = enumerator.replacen(prefix, "FMOD_CHANNELMASK_", 1);
+        masks
+            .flags
+            .iter()
+            .find(|flag| flag.name == name)
+            .map(|flag| format_ident!("{}", flag.name))
+    };
+    let mut mode_arms = vec![];
+    for enumerator in &modes.enumerators {
+        if let Some(mask) = find_mask(&enumerator.name, "FMOD_SPEAKERMODE_") {
+            let variant = format_variant(&modes.name, &enumerator.name);
+            mode_arms.push(quote! {
+                SpeakerMode::#variant => Some(ChannelMask::from_bits_retain(ffi::#mask))
+            });
+        }
+    }
+    let mut speaker_masks = vec![];
+    for enumerator in &speakers.enumerators {
+        if let Some(mask) = find_mask(&enumerator.name, "FMOD_SPEAKER_") {
+            let variant = format_variant(&speakers.name, &enumerator.name);
+            speaker_masks.push(quote! { (ffi::#mask, Speaker::#variant) });
+        }
+    }
+    quote! {
+        impl SpeakerMode {
+            /// Speakers of the mode, raw and default modes have no fixed layout.
+            pub fn channel_mask(&self) -> Option<ChannelMask> {
+                match self {
+                    #(#mode_arms,)*
+                    _ => None,
+                }
+            }
+
+            pub fn channel_count(&self) -> Option<u32> {
+                self.channel_mask().map(|mask| mask.channel_count())
+            }
+        }
+
+        impl ChannelMask {
+            const SPEAKERS: &'static [(ffi::FMOD_CHANNELMASK, Speaker)] = &[#(#speaker_masks),*];
+
+            pub fn channel_count(&self) -> u32 {
+                self.bits().count_ones()
+            }
+
+            pub fn speakers(&self) -> Vec<Speaker> {
+                ChannelMask::SPEAKERS
+                    .iter()
+                    .filter(|(mask, _)| self.bits() & mask != 0)
+                    .map(|(_, speaker)| *speaker)
+                    .collect()
+            }
+
+            pub fn from_speakers(speakers: &[Speaker]) -> ChannelMask {
+                let bits = ChannelMask::SPEAKERS
+                    .iter()
+                    .filter(|(_, speaker)| speakers.contains(speaker))
+                    .fold(0, |bits, (mask, _)| bits | mask);
+                ChannelMask::from_bits_retain(bits)
+            }
+        }
+    }
+}
+
+pub fn generate_flags_type(api: &Api, key: &str) -> TokenStream {
+    let flags = match api.flags.iter().find(|flags| flags.name == key) {
+        Some(flags) => flags,
+        None => return quote! {},
+    };
+    let name = format_flags_type(key);
+    let raw = format_ident!("{}", key);
+    let prefix = get_shared_prefix(&flags.flags);
+    let mut constants = vec![];
+    let mut all = vec![];
+    for flag in &flags.flags {
+        let constant = format_ident!("{}", format_flag_name(&prefix, &flag.name));
+        let value = format_ident!("{}", flag.name);
+        constants.push(quote! { pub const #constant: #name = #name(ffi::#value); });
+        all.push(quote! { ffi::#value });
+    }
+    quote! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+        #[repr(transparent)]
+        pub struct #name(ffi::#raw);
+
+        impl #name {
+            #(#constants)*
+
+            pub const fn empty() -> #name {
+                #name(0)
+            }
+
+            pub const fn all() -> #name {
+                #name(#(#all)|*)
+            }
+
+            pub const fn bits(&self) -> ffi::#raw {
+                self.0
+            }
+
+            pub const fn from_bits(bits: ffi::#raw) -> Option<#name> {
+                if bits & !#name::all().0 == 0 {
+                    Some(#name(bits))
+                } else {
+                    None
+                }
+            }
+
+            /// Keeps bits not known at generation time.
+            pub const fn from_bits_retain(bits: ffi::#raw) -> #name {
+                #name(bits)
+            }
+
+            pub const fn contains(&self, other: #name) -> bool {
+                self.0 & other.0 == other.0
+            }
+
+            pub fn insert(&mut self, other: #name) {
+                self.0 |= other.0;
+            }
+
+            pub fn remove(&mut self, other: #name) {
+                self.0 &= !other.0;
+            }
+        }
+
+        impl std::ops::BitOr for #name {
+            type Output = #name;
+            fn bitor(self, other: #name) -> #name {
+                #name(self.0 | other.0)
+            }
+        }
+
+        impl std::ops::BitOrAssign for #name {
+            fn bitor_assign(&mut self, other: #name) {
+                self.0 |= other.0;
+            }
+        }
+
+        impl std::ops::BitAnd for #name {
+            type Output = #name;
+            fn bitand(self, other: #name) -> #name {
+                #name(self.0 & other.0)
+            }
+        }
+
+        impl From<#name> for ffi::#raw {
+            fn from(value: #name) -> ffi::#raw {
+                value.0
+            }
+        }
+
+        impl TryFrom<ffi::#raw> for #name {
+            type Error = Error;
+            fn try_from(value: ffi::#raw) -> Result<Self, Self::Error> {
+                #name::from_bits(value).ok_or_else(|| err_enum!(#key, value))
+            }
+        }
+    }
+}
+
+pub fn generate_value_type(api: &Api, key: &str, name: &str) -> TokenStream {
+    let values = match api.flags.iter().find(|flags| flags.name == key) {
+        Some(values) => values,
+        None => return quote! {},
+    };
+    let name = format_ident!("{}", name);
+    let raw = format_ident!("{}", key);
+    let prefix = get_shared_prefix(&values.flags);
+    let constants = values.flags.iter().map(|value| {
+        let constant = format_ident!("{}", format_flag_name(&prefix, &value.name));
+        let value = format_ident!("{}", value.name);
+        quote! { pub const #constant: #name = #name(ffi::#value); }
+    });
+    quote! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[repr(transparent)]
+        pub struct #name(pub ffi::#raw);
+
+        impl #name {
+            #(#constants)*
+        }
+
+        impl From<#name> for ffi::#raw {
+            fn from(value: #name) -> ffi::#raw {
+                value.0
+            }
+        }
+
+        impl From<ffi::#raw> for #name {
+            fn from(value: ffi::#raw) -> #name {
+                #name(value)
+            }
+        }
+    }
+}
+
+/// Port index with platform specific constants, e.g. `PortIndex::NONE` for default port.
+pub fn generate_port_index(api: &Api) -> TokenStream {
+    if !api
+        .type_aliases
+        .iter()
+        .any(|alias| alias.name == "FMOD_PORT_INDEX")
+    {
+        return quote! {};
+    }
+    let constants = api.constants.iter().filter_map(|constant| {
+        let name = constant.name.strip_prefix("FMOD_PORT_INDEX_")?;
+        let name = format_ident!("{}", name);
+        let value = format_ident!("{}", constant.name);
+        Some(quote! { pub const #name: PortIndex = PortIndex(ffi::#value); })
+    });
+    quote! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[repr(transparent)]
+        pub struct PortIndex(pub ffi::FMOD_PORT_INDEX);
+
+        impl PortIndex {
+            #(#constants)*
+        }
+
+        impl From<PortIndex> for ffi::FMOD_PORT_INDEX {
+            fn from(value: PortIndex) -> ffi::FMOD_PORT_INDEX {
+                value.0
+            }
+        }
+
+        impl From<ffi::FMOD_PORT_INDEX> for PortIndex {
+            fn from(value: ffi::FMOD_PORT_INDEX) -> PortIndex {
+                PortIndex(value)
+            }
         }
     }
 }
 
-pub fn generate_field(structure: &Structure, field: &Field, api: &Api) -> TokenStream {
+pub fn generate_field(
+    structure: &Structure,
+    field: &Field,
+    api: &Api,
+) -> Result<TokenStream, Error> {
     match api.patch_rust_struct_field_definition(&structure.name[..], &field.name[..]) {
-        Some(definition) => return definition,
+        Some(definition) => return Ok(definition),
         _ => {}
     };
+    if api.get_field_role(&structure.name, &field.name).is_some() {
+        return Ok(quote! {});
+    }
 
-    let name = format_argument_ident(&field.name);
+    let name = format_argument_ident(&field.name, api);
     let as_array = match &field.as_array {
         None => None,
         Some(dimension) => {
@@ -229,29 +610,55 @@ pub fn generate_field(structure: &Structure, field: &Field, api: &Api) -> TokenS
         &field.pointer,
         &as_array,
         &api,
-    );
-    quote! {
+    )?;
+    Ok(quote! {
         pub #name: #field_type
-    }
+    })
 }
 
-pub fn generate_field_from(structure: &str, field: &Field, api: &Api) -> TokenStream {
-    let name = format_argument_ident(&field.name);
-    let value_name = ffi::format_rust_ident(&field.name);
+pub fn generate_field_from(
+    structure: &str,
+    field: &Field,
+    api: &Api,
+) -> Result<TokenStream, Error> {
+    let name = format_argument_ident(&field.name, api);
+    let value_name = ffi::format_rust_ident(&field.name, api);
     let ptr = describe_pointer(&field.as_const, &field.pointer);
 
     let getter = match api.patch_field_try_from(structure, &field.name[..]) {
         Some(expression) => {
             if expression.is_empty() {
-                return expression;
+                return Ok(expression);
             } else {
                 expression
             }
         }
+        _ if api.get_field_role(structure, &field.name).is_some() => return Ok(quote! {}),
         _ => match &field.field_type {
             FundamentalType(name) => match (ptr, &name[..]) {
                 ("*const", "char") => quote! { to_string!(value.#value_name)? },
                 ("*mut", "char") => quote! { to_string!(value.#value_name)? },
+                ("", "char") if field.as_array.is_some() => {
+                    quote! { string_from_char_array(&value.#value_name)? }
+                }
+                ("*const" | "*mut", "unsigned char") => {
+                    let length = match api.find_count_field(structure, &field.name) {
+                        Some(length) => ffi::format_rust_ident(length, api),
+                        None => {
+                            return Err(Error::Unsupported(format!(
+                                "{}.{} has no length field",
+                                structure, field.name
+                            )))
+                        }
+                    };
+                    quote! {
+                        if value.#value_name.is_null() {
+                            Vec::new()
+                        } else {
+                            to_vec!(value.#value_name, value.#length)
+                        }
+                    }
+                }
                 _ => quote! { value.#value_name },
             },
             UserType(name) => match (ptr, api.describe_user_type(name)) {
@@ -261,7 +668,7 @@ pub fn generate_field_from(structure: &str, field: &Field, api: &Api) -> TokenSt
                 }
                 ("*mut", UserTypeDesc::Structure) => {
                     let name = format_struct_ident(name);
-                    quote! { #name::try_from(*value.#value_name)? }
+                    quote! { #name::try_from(unsafe { *value.#value_name })? }
                 }
                 ("", UserTypeDesc::Structure) => {
                     let name = format_struct_ident(name);
@@ -269,27 +676,56 @@ pub fn generate_field_from(structure: &str, field: &Field, api: &Api) -> TokenSt
                 }
                 ("", UserTypeDesc::Enumeration) => {
                     let name = format_struct_ident(name);
-                    quote! { #name::from(value.#value_name)? }
+                    quote! { #name::from_raw_lossy(value.#value_name) }
+                }
+                ("", UserTypeDesc::Flags) if is_flags_type(name) => {
+                    let name = format_flags_type(name);
+                    quote! { #name(value.#value_name) }
                 }
                 _ => quote! { value.#value_name },
             },
         },
     };
 
-    quote! {#name: #getter}
+    Ok(quote! {#name: #getter})
 }
 
 pub fn generate_into_field(structure: &str, field: &Field, api: &Api) -> TokenStream {
-    let name = ffi::format_rust_ident(&field.name);
-    let self_name = format_argument_ident(&field.name);
+    let name = ffi::format_rust_ident(&field.name, api);
+    let self_name = format_argument_ident(&field.name, api);
     let ptr = describe_pointer(&field.as_const, &field.pointer);
 
-    let getter = match api.patch_field_into(structure, &field.name[..]) {
+    let derived = match api.get_field_role(structure, &field.name) {
+        Some(FieldRole::SizeOfSelf) => {
+            let structure = format_ident!("{}", structure);
+            Some(quote! { size_of::<ffi::#structure>() as _ })
+        }
+        Some(FieldRole::CountOf(items)) if api.is_byte_buffer(structure, items) => {
+            // buffer is passed only by into_ffi, see generate_byte_buffers_into
+            Some(quote! { 0 })
+        }
+        Some(FieldRole::CountOf(items)) => {
+            let items = format_argument_ident(items, api);
+            Some(quote! { self.#items.len() as _ })
+        }
+        None => None,
+    };
+    let getter = match api.patch_field_into(structure, &field.name[..]).or(derived) {
         Some(expression) => expression,
+        _ if api.get_field_direction(structure, &field.name) == FieldDirection::Output => {
+            quote! { unsafe { std::mem::zeroed() } }
+        }
         _ => match &field.field_type {
             FundamentalType(name) => match (ptr, &name[..]) {
                 ("*const", "char") => quote! { self.#self_name.as_ptr().cast() },
                 ("*mut", "char") => quote! { self.#self_name.as_ptr() as *mut _ },
+                ("", "char") if field.as_array.is_some() => {
+                    quote! { string_into_char_array(&self.#self_name) }
+                }
+                ("*const" | "*mut", "unsigned char") => {
+                    // buffer must outlive the conversion, see generate_byte_buffers_into
+                    quote! { null_mut() }
+                }
                 _ => quote! { self.#self_name },
             },
             UserType(name) => match (ptr, api.describe_user_type(name)) {
@@ -305,6 +741,9 @@ pub fn generate_into_field(structure: &str, field: &Field, api: &Api) -> TokenSt
                 ("", UserTypeDesc::Enumeration) => {
                     quote! { self.#self_name.into() }
                 }
+                ("", UserTypeDesc::Flags) if is_flags_type(name) => {
+                    quote! { self.#self_name.into() }
+                }
                 _ => quote! { self.#self_name },
             },
         },
@@ -333,10 +772,62 @@ pub fn generate_presets(structure: &Structure, api: &Api) -> TokenStream {
     if presets.is_empty() {
         quote! {}
     } else {
+        let enumeration = generate_preset_enumeration(api);
         quote! {
             impl #name {
                 #(#presets)*
             }
+            #enumeration
+        }
+    }
+}
+
+fn generate_preset_enumeration(api: &Api) -> TokenStream {
+    let mut variants = vec![];
+    let mut arms = vec![];
+    for preset in &api.presets {
+        let ident = format_ident!("{}", preset.name);
+        let variant = preset
+            .name
+            .replace("FMOD_PRESET_", "")
+            .to_case(Case::Pascal);
+        let variant = format_ident!("{}", variant);
+        arms.push(quote! { ReverbPreset::#variant => ffi::#ident });
+        variants.push(variant);
+    }
+    quote! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum ReverbPreset {
+            #(#variants),*
+        }
+
+        impl ReverbPreset {
+            pub const ALL: &'static [ReverbPreset] = &[#(ReverbPreset::#variants),*];
+        }
+
+        impl From<ReverbPreset> for ffi::FMOD_REVERB_PROPERTIES {
+            fn from(preset: ReverbPreset) -> ffi::FMOD_REVERB_PROPERTIES {
+                match preset {
+                    #(#arms),*
+                }
+            }
+        }
+
+        impl TryFrom<ReverbPreset> for ReverbProperties {
+            type Error = Error;
+            fn try_from(preset: ReverbPreset) -> Result<Self, Self::Error> {
+                ReverbProperties::try_from(ffi::FMOD_REVERB_PROPERTIES::from(preset))
+            }
+        }
+
+        impl System {
+            pub fn set_reverb_preset(&self, instance: i32, preset: ReverbPreset) -> Result<(), Error> {
+                let properties = ffi::FMOD_REVERB_PROPERTIES::from(preset);
+                match profile_call!("FMOD_System_SetReverbProperties", unsafe { ffi::FMOD_System_SetReverbProperties(self.pointer, instance, &properties) }) {
+                    ffi::FMOD_OK => Ok(()),
+                    error => Err(err_fmod!("FMOD_System_SetReverbProperties", error)),
+                }
+            }
         }
     }
 }
@@ -365,41 +856,159 @@ pub fn generate_structure_into(structure: &Structure, api: &Api) -> TokenStream
     }
 }
 
-pub fn generate_structure_try_from(structure: &Structure, api: &Api) -> TokenStream {
+pub fn generate_structure_try_from(structure: &Structure, api: &Api) -> Result<TokenStream, Error> {
     let ident = format_ident!("{}", structure.name);
     let name = format_struct_ident(&structure.name);
-    let conversion = structure
-        .fields
-        .iter()
-        .map(|field| generate_field_from(&structure.name, field, api))
-        .filter(|definition| !definition.is_empty());
+    let mut conversion = vec![];
+    for field in &structure.fields {
+        let definition = generate_field_from(&structure.name, field, api)?;
+        if !definition.is_empty() {
+            conversion.push(definition);
+        }
+    }
     let union = if structure.union.is_some() {
         Some(quote! { ,union: value.union })
     } else {
         None
     };
-    quote! {
+    Ok(quote! {
         impl TryFrom<ffi::#ident> for #name {
             type Error = Error;
 
             fn try_from(value: ffi::#ident) -> Result<Self, Self::Error> {
-                unsafe {
-                    Ok(#name {
-                        #(#conversion),*
-                        #union
-                    })
-                }
+                Ok(#name {
+                    #(#conversion),*
+                    #union
+                })
+            }
+        }
+    })
+}
+
+/// Byte buffers can't outlive plain conversion, so structures having them
+/// get `into_ffi` keeping buffers alive by guard.
+pub fn generate_byte_buffers_into(structure: &Structure, api: &Api) -> TokenStream {
+    let buffers: Vec<&Field> = structure
+        .fields
+        .iter()
+        .filter(|field| api.is_byte_buffer(&structure.name, &field.name))
+        .collect();
+    if buffers.is_empty() || api.structure_patches.contains_key(&structure.name) {
+        return quote! {};
+    }
+    let name = format_struct_ident(&structure.name);
+    let raw = format_ident!("{}", structure.name);
+    let mut takes = vec![];
+    let mut assignments = vec![];
+    for field in buffers {
+        let buffer = format_argument_ident(&field.name, api);
+        let raw_buffer = ffi::format_rust_ident(&field.name, api);
+        takes.push(quote! { let #buffer = std::mem::take(&mut self.#buffer); });
+        if let Some(length) = api.find_count_field(&structure.name, &field.name) {
+            let length = ffi::format_rust_ident(length, api);
+            assignments.push(quote! { value.#length = #buffer.len() as _; });
+        }
+        assignments.push(quote! { value.#raw_buffer = guard.keep(#buffer) as _; });
+    }
+    quote! {
+        impl #name {
+            pub fn into_ffi(mut self, guard: &mut Guard) -> Result<ffi::#raw, Error> {
+                #(#takes)*
+                let mut value: ffi::#raw = self.into();
+                #(#assignments)*
+                Ok(value)
             }
         }
     }
 }
 
-pub fn generate_structure(structure: &Structure, api: &Api) -> TokenStream {
+pub fn generate_union_accessors(structure: &Structure, api: &Api) -> Result<TokenStream, Error> {
+    let (union, tag, selectors) = match (
+        &structure.union,
+        TAGGED_UNIONS
+            .iter()
+            .find(|(key, _, _)| *key == structure.name),
+    ) {
+        (Some(union), Some((_, tag, selectors))) => (union, tag, selectors),
+        _ => return Ok(quote! {}),
+    };
+    let enumeration = match structure.fields.iter().find(|field| &field.name == tag) {
+        Some(Field {
+            field_type: UserType(enumeration),
+            ..
+        }) => enumeration,
+        _ => return Ok(quote! {}),
+    };
+    let name = format_struct_ident(&structure.name);
+    let value_name = format_ident!("{}Value", name);
+    let tag_name = format_struct_ident(enumeration);
+    let doc = format!("Reads union member selected by `{}` field.", tag);
+    let tag = format_argument_ident(tag, api);
+    let mut variants = vec![];
+    let mut arms = vec![];
+    for (enumerator, field_name) in selectors.iter() {
+        let field = match union.fields.iter().find(|field| &field.name == field_name) {
+            Some(field) => field,
+            None => continue,
+        };
+        let variant = format_variant(enumeration, enumerator);
+        let field_name = ffi::format_rust_ident(&field.name, api);
+        let ptr = describe_pointer(&field.as_const, &field.pointer);
+        let (value_type, getter) = match (&field.field_type, ptr) {
+            (FundamentalType(type_name), "*const") if type_name == "char" => (
+                quote! { String },
+                quote! { to_string!(self.union.#field_name)? },
+            ),
+            (UserType(type_name), "") if type_name == "FMOD_BOOL" => {
+                (quote! { bool }, quote! { to_bool!(self.union.#field_name) })
+            }
+            (UserType(type_name), "")
+                if matches!(api.describe_user_type(type_name), UserTypeDesc::Structure) =>
+            {
+                let type_name = format_struct_ident(type_name);
+                (
+                    quote! { #type_name },
+                    quote! { #type_name::try_from(self.union.#field_name)? },
+                )
+            }
+            (field_type, _) => (
+                format_rust_type(field_type, &field.as_const, &field.pointer, &None, api)?,
+                quote! { self.union.#field_name },
+            ),
+        };
+        variants.push(quote! { #variant(#value_type) });
+        arms.push(quote! { #tag_name::#variant => #value_name::#variant(#getter) });
+    }
+    let enumeration = format_ident!("{}", enumeration);
+    Ok(quote! {
+        #[derive(Debug, Clone)]
+        pub enum #value_name {
+            #(#variants),*
+        }
+
+        impl #name {
+            #[doc = #doc]
+            pub fn value(&self) -> Result<#value_name, Error> {
+                let value = unsafe {
+                    match self.#tag {
+                        #(#arms),*,
+                        tag => return Err(err_enum!(stringify!(#enumeration), ffi::#enumeration::from(tag))),
+                    }
+                };
+                Ok(value)
+            }
+        }
+    })
+}
+
+pub fn generate_structure(structure: &Structure, api: &Api) -> Result<TokenStream, Error> {
     let name = format_struct_ident(&structure.name);
     let mut fields: Vec<TokenStream> = structure
         .fields
         .iter()
         .map(|field| generate_field(structure, field, api))
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
         .filter(|definition| !definition.is_empty())
         .collect();
 
@@ -407,6 +1016,16 @@ pub fn generate_structure(structure: &Structure, api: &Api) -> TokenStream {
         None => quote! { Debug, Clone },
         Some(drive) => drive.clone(),
     };
+    let has_union_fields = structure
+        .fields
+        .iter()
+        .any(|field| match &field.field_type {
+            UserType(name) => api.is_union(name),
+            FundamentalType(_) => false,
+        });
+    if has_union_fields {
+        derive = quote! { Clone };
+    }
     if structure.union.is_some() {
         let name = format_ident!("{}_UNION", structure.name);
         fields.push(quote! {
@@ -415,24 +1034,90 @@ pub fn generate_structure(structure: &Structure, api: &Api) -> TokenStream {
         derive = quote! { Clone };
     }
     let presets = generate_presets(structure, api);
+    let accessors = generate_union_accessors(structure, api)?;
     let into = generate_structure_into(structure, api);
-    let try_from = generate_structure_try_from(structure, api);
+    let try_from = generate_structure_try_from(structure, api)?;
+    let buffers = generate_byte_buffers_into(structure, api);
     let conversions = api.structure_patches.get(&structure.name);
-    quote! {
-        #[derive(#derive)]
-        pub struct #name {
-            #(#fields),*
+    let raw = format_ident!("{}", structure.name);
+    let layout = if is_layout_compatible(&structure.name) {
+        quote! {
+            #[repr(C)]
         }
-        #presets
-        #try_from
-        #conversions
-        #into
-    }
-}
-
-struct OutArgument {
-    pub target: TokenStream,
-    pub source: TokenStream,
+    } else {
+        quote! {}
+    };
+    let layout_conversions = if is_layout_compatible(&structure.name) {
+        quote! {
+            const _: () = assert!(
+                std::mem::size_of::<#name>() == std::mem::size_of::<ffi::#raw>()
+                    && std::mem::align_of::<#name>() == std::mem::align_of::<ffi::#raw>()
+            );
+            impl #name {
+                /// Borrows as FFI structure without copying, layouts are identical.
+                #[inline]
+                pub fn as_ffi(&self) -> &ffi::#raw {
+                    unsafe { &*(self as *const Self as *const ffi::#raw) }
+                }
+                #[inline]
+                pub fn as_ffi_mut(&mut self) -> &mut ffi::#raw {
+                    unsafe { &mut *(self as *mut Self as *mut ffi::#raw) }
+                }
+                #[inline]
+                pub fn from_ffi_ref(value: &ffi::#raw) -> &Self {
+                    unsafe { &*(value as *const ffi::#raw as *const Self) }
+                }
+            }
+            impl AsRef<ffi::#raw> for #name {
+                fn as_ref(&self) -> &ffi::#raw {
+                    self.as_ffi()
+                }
+            }
+            impl AsMut<ffi::#raw> for #name {
+                fn as_mut(&mut self) -> &mut ffi::#raw {
+                    self.as_ffi_mut()
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+    Ok(quote! {
+        #[derive(#derive)]
+        #layout
+        pub struct #name {
+            #(#fields),*
+        }
+        #presets
+        #accessors
+        #try_from
+        #conversions
+        #buffers
+        #into
+        impl #name {
+            /// Converts into FFI structure to pass to functions not covered by wrapper.
+            #[inline]
+            pub fn to_ffi(self) -> ffi::#raw {
+                self.into()
+            }
+            /// Converts from FFI structure returned by functions not covered by wrapper.
+            #[inline]
+            pub fn from_ffi(value: ffi::#raw) -> Result<Self, Error> {
+                Self::try_from(value)
+            }
+        }
+        #layout_conversions
+    })
+}
+
+pub fn is_layout_compatible(key: &str) -> bool {
+    LAYOUT_COMPATIBLE_STRUCTURES.contains(&key)
+}
+
+struct OutArgument {
+    pub name: Ident,
+    pub target: TokenStream,
+    pub source: TokenStream,
     pub output: TokenStream,
     pub retype: TokenStream,
 }
@@ -453,10 +1138,10 @@ pub fn quote_tuple(items: &Vec<TokenStream>) -> TokenStream {
     }
 }
 
-fn map_optional(argument: &Argument, api: &Api) -> InArgument {
+fn map_optional(argument: &Argument, api: &Api) -> Result<InArgument, Error> {
     let pointer = ffi::describe_pointer(&argument.as_const, &argument.pointer);
-    let name = format_argument_ident(&argument.name);
-    match &argument.argument_type {
+    let name = format_argument_ident(&argument.name, api);
+    let input = match &argument.argument_type {
         FundamentalType(type_name) => match &format!("{}:{}", pointer, type_name)[..] {
             ":int" => InArgument {
                 param: quote! { #name: Option<i32> },
@@ -466,6 +1151,10 @@ fn map_optional(argument: &Argument, api: &Api) -> InArgument {
                 param: quote! { #name: Option<f32> },
                 input: quote! { #name.unwrap_or(0.0) },
             },
+            ":double" => InArgument {
+                param: quote! { #name: Option<f64> },
+                input: quote! { #name.unwrap_or(0.0) },
+            },
             ":unsigned long long" => InArgument {
                 param: quote! { #name: Option<u64> },
                 input: quote! { #name.unwrap_or(0) },
@@ -486,14 +1175,24 @@ fn map_optional(argument: &Argument, api: &Api) -> InArgument {
                 param: quote! { #name: Option<*mut c_void> },
                 input: quote! { #name.unwrap_or(null_mut()) },
             },
-            argument_type => {
-                unimplemented!("opt {}", argument_type)
-            }
+            _ => match (pointer, ffi::map_sized_type(type_name)) {
+                ("", Some(rust_type)) => InArgument {
+                    param: quote! { #name: Option<#rust_type> },
+                    input: quote! { #name.unwrap_or(0) },
+                },
+                _ => return Err(unsupported_type(pointer, &argument.argument_type)),
+            },
         },
         UserType(user_type) => {
             let tp = format_struct_ident(&user_type);
             let ident = format_ident!("{}", user_type);
             match (pointer, api.describe_user_type(&user_type)) {
+                ("*mut", UserTypeDesc::Structure) if is_layout_compatible(user_type) => {
+                    InArgument {
+                        param: quote! { #name: Option<#tp> },
+                        input: quote! { #name.as_ref().map(|value| value.as_ffi() as *const ffi::#ident as *mut ffi::#ident).unwrap_or(null_mut()) },
+                    }
+                }
                 ("*mut", UserTypeDesc::Structure) => InArgument {
                     param: quote! { #name: Option<#tp> },
                     input: quote! { #name.map(|value| &mut value.into() as *mut _).unwrap_or(null_mut()) },
@@ -502,6 +1201,12 @@ fn map_optional(argument: &Argument, api: &Api) -> InArgument {
                     param: quote! { #name: Option<#tp> },
                     input: quote! { #name.map(|value| value.as_mut_ptr()).unwrap_or(null_mut()) },
                 },
+                ("*const", UserTypeDesc::Structure) if is_layout_compatible(user_type) => {
+                    InArgument {
+                        param: quote! { #name: Option<#tp> },
+                        input: quote! { #name.as_ref().map(#tp::as_ffi).map(from_ref).unwrap_or_else(null) },
+                    }
+                }
                 ("*const", UserTypeDesc::Structure) => InArgument {
                     param: quote! { #name: Option<#tp> },
                     input: quote! { #name.map(#tp::into).as_ref().map(from_ref).unwrap_or_else(null) },
@@ -514,22 +1219,27 @@ fn map_optional(argument: &Argument, api: &Api) -> InArgument {
                     param: quote! { #name: ffi::#ident },
                     input: quote! { #name },
                 },
-                user_type => unimplemented!("opt {:?}", user_type),
+                _ => return Err(unsupported_type(pointer, &argument.argument_type)),
             }
         }
-    }
+    };
+    Ok(input)
 }
 
-fn map_input(argument: &Argument, api: &Api) -> InArgument {
+fn map_input(argument: &Argument, api: &Api) -> Result<InArgument, Error> {
     let pointer = ffi::describe_pointer(&argument.as_const, &argument.pointer);
     let argument_type = &argument.argument_type;
-    let argument = format_argument_ident(&argument.name);
-    match argument_type {
+    let argument = format_argument_ident(&argument.name, api);
+    let input = match argument_type {
         FundamentalType(type_name) => match &format!("{}:{}", pointer, type_name)[..] {
             ":float" => InArgument {
                 param: quote! { #argument: f32 },
                 input: quote! { #argument },
             },
+            ":double" => InArgument {
+                param: quote! { #argument: f64 },
+                input: quote! { #argument },
+            },
             ":int" => InArgument {
                 param: quote! { #argument: i32 },
                 input: quote! { #argument },
@@ -558,7 +1268,25 @@ fn map_input(argument: &Argument, api: &Api) -> InArgument {
                 param: quote! { #argument: *mut f32 },
                 input: quote! { #argument },
             },
-            _ => unimplemented!(),
+            "*mut:double" => InArgument {
+                param: quote! { #argument: *mut f64 },
+                input: quote! { #argument },
+            },
+            "*const:unsigned char" => InArgument {
+                param: quote! { #argument: &[u8] },
+                input: quote! { #argument.as_ptr() },
+            },
+            "*mut:unsigned char" => InArgument {
+                param: quote! { #argument: &mut [u8] },
+                input: quote! { #argument.as_mut_ptr() },
+            },
+            _ => match (pointer, ffi::map_sized_type(type_name)) {
+                ("", Some(rust_type)) => InArgument {
+                    param: quote! { #argument: #rust_type },
+                    input: quote! { #argument },
+                },
+                _ => return Err(unsupported_type(pointer, argument_type)),
+            },
         },
         UserType(type_name) => {
             let rust_type = format_struct_ident(&type_name);
@@ -568,10 +1296,23 @@ fn map_input(argument: &Argument, api: &Api) -> InArgument {
                     param: quote! { #argument: #rust_type },
                     input: quote! { #argument.as_mut_ptr() },
                 },
+                ("*const", UserTypeDesc::Structure) if is_layout_compatible(type_name) => {
+                    InArgument {
+                        param: quote! { #argument: &#rust_type },
+                        input: quote! { #argument.as_ffi() },
+                    }
+                }
                 ("*const", UserTypeDesc::Structure) => InArgument {
                     param: quote! { #argument: #rust_type },
                     input: quote! { &#argument.into() },
                 },
+                // Input structures passed by mutable pointer are missing const in C API only.
+                ("*mut", UserTypeDesc::Structure) if is_layout_compatible(type_name) => {
+                    InArgument {
+                        param: quote! { #argument: &#rust_type },
+                        input: quote! { #argument.as_ffi() as *const ffi::#ident as *mut ffi::#ident },
+                    }
+                }
                 ("*mut", UserTypeDesc::Structure) => InArgument {
                     param: quote! { #argument: #rust_type },
                     input: quote! { &mut #argument.into() },
@@ -580,6 +1321,13 @@ fn map_input(argument: &Argument, api: &Api) -> InArgument {
                     param: quote! { #argument: #rust_type },
                     input: quote! { #argument.into() },
                 },
+                ("", UserTypeDesc::Flags) if is_flags_type(type_name) => {
+                    let flags_type = format_flags_type(type_name);
+                    InArgument {
+                        param: quote! { #argument: #flags_type },
+                        input: quote! { #argument.into() },
+                    }
+                }
                 ("", UserTypeDesc::Flags) => InArgument {
                     param: quote! { #argument: impl Into<ffi::#ident> },
                     input: quote! { #argument.into() },
@@ -598,72 +1346,305 @@ fn map_input(argument: &Argument, api: &Api) -> InArgument {
                         input: quote! { from_bool!(#argument) },
                     },
                     "FMOD_PORT_INDEX" => InArgument {
-                        param: quote! { #argument: u64 },
-                        input: quote! { #argument },
+                        param: quote! { #argument: PortIndex },
+                        input: quote! { #argument.into() },
                     },
-                    _ => unimplemented!(),
+                    _ => return Err(unsupported_type(pointer, argument_type)),
                 },
-                _ => unimplemented!(),
+                _ => return Err(unsupported_type(pointer, argument_type)),
+            }
+        }
+    };
+    Ok(input)
+}
+
+/// Maps int documented as boolean to bool, FMOD_BOOL is handled as type alias.
+fn map_bool_input(argument: &Argument, api: &Api) -> InArgument {
+    let name = format_argument_ident(&argument.name, api);
+    InArgument {
+        param: quote! { #name: bool },
+        input: quote! { from_bool!(#name) },
+    }
+}
+
+fn map_bool_output(argument: &Argument, api: &Api) -> OutArgument {
+    let arg = format_argument_ident(&argument.name, api);
+    OutArgument {
+        name: arg.clone(),
+        target: quote! { let mut #arg = 0; },
+        source: quote! { &mut #arg },
+        output: quote! { to_bool!(#arg) },
+        retype: quote! { bool },
+    }
+}
+
+/// Maps optional int documented as boolean, missing value is passed as false.
+fn map_optional_bool(argument: &Argument, api: &Api) -> InArgument {
+    let name = format_argument_ident(&argument.name, api);
+    InArgument {
+        param: quote! { #name: Option<bool> },
+        input: quote! { #name.map(|value| from_bool!(value)).unwrap_or(0) },
+    }
+}
+
+fn map_unit_input(argument: &Argument, unit: Ident, api: &Api) -> Result<InArgument, Error> {
+    let name = format_argument_ident(&argument.name, api);
+    let raw = format_rust_type(&argument.argument_type, &None, &None, &None, api)?;
+    Ok(InArgument {
+        param: quote! { #name: #unit<#raw> },
+        input: quote! { #name.0 },
+    })
+}
+
+fn map_unit_output(argument: OutArgument, unit: Ident) -> OutArgument {
+    let output = &argument.output;
+    let retype = &argument.retype;
+    OutArgument {
+        output: quote! { #unit(#output) },
+        retype: quote! { #unit<#retype> },
+        ..argument
+    }
+}
+
+fn map_format(argument: &Argument, api: &Api) -> InArgument {
+    let argument = format_argument_ident(&argument.name, api);
+    InArgument {
+        param: quote! { #argument: &str },
+        input: quote! { b"%s\0".as_ptr() as *const c_char, CString::new(#argument)?.as_ptr() },
+    }
+}
+
+pub fn generate_variadic_callback(callback: &Callback, api: &Api) -> Option<TokenStream> {
+    callback.varargs.as_ref()?;
+    if callback.returns().is_some() {
+        return None;
+    }
+    let (format, arguments) = callback.arguments.split_last()?;
+    let name = format_ident!("{}", callback.name);
+    let method = callback.name.trim_start_matches("FMOD_");
+    let method = method
+        .trim_end_matches("_FUNC")
+        .trim_end_matches("_CALLBACK");
+    let method = format_ident!("{}", method.to_lowercase());
+    let mut params = vec![];
+    let mut inputs = vec![];
+    for argument in arguments {
+        let argument = map_input(argument, api).ok()?;
+        params.push(argument.param);
+        inputs.push(argument.input);
+    }
+    let format = map_format(format, api);
+    let format_param = format.param;
+    let format_input = format.input;
+    Some(quote! {
+        /// Calls printf-style FMOD callback with message formatted on Rust side.
+        ///
+        /// # Safety
+        ///
+        /// Callback must be function provided by FMOD, e.g. by plugin state,
+        /// and raw pointer arguments must be valid for the duration of call.
+        pub unsafe fn #method(callback: ffi::#name, #(#params,)* #format_param) -> Result<(), Error> {
+            if let Some(callback) = callback {
+                unsafe { callback(#(#inputs,)* #format_input) };
             }
+            Ok(())
+        }
+    })
+}
+
+fn find_buffer_length(buffer: &Argument, function: &Function, api: &Api) -> Option<Ident> {
+    let index = function
+        .arguments
+        .iter()
+        .position(|argument| argument.name == buffer.name)?;
+    let length = function.arguments.get(index + 1)?;
+    match (&length.argument_type, &length.pointer) {
+        (FundamentalType(name), None) if name == "int" => {
+            Some(format_argument_ident(&length.name, api))
         }
+        _ => None,
     }
 }
 
-fn map_output(argument: &Argument, _function: &Function, api: &Api) -> OutArgument {
+/// Char output buffer preceding length argument, the length is taken from buffer
+/// allocated by generated code instead of parameter.
+fn find_length_buffer<'a>(
+    length: &Argument,
+    function: &'a Function,
+    api: &Api,
+) -> Option<&'a Argument> {
+    let index = function
+        .arguments
+        .iter()
+        .position(|argument| argument.name == length.name)?;
+    let buffer = function.arguments.get(index.checked_sub(1)?)?;
+    let is_string = buffer.argument_type == FundamentalType("char".into())
+        && describe_pointer(&buffer.as_const, &buffer.pointer) == "*mut";
+    let is_length =
+        length.argument_type == FundamentalType("int".into()) && length.pointer.is_none();
+    let is_output = api.get_modifier(&function.name, &buffer.name) == Modifier::Out;
+    (is_string && is_length && is_output).then_some(buffer)
+}
+
+/// Maps array input paired with length argument to slice.
+fn map_array(argument: &Argument, api: &Api) -> Result<InArgument, Error> {
+    let pointer = ffi::describe_pointer(&argument.as_const, &argument.pointer);
+    let name = format_argument_ident(&argument.name, api);
+    let cast = match pointer {
+        "*const" => quote! {},
+        _ => quote! { as *mut _ },
+    };
+    let argument = match &argument.argument_type {
+        FundamentalType(type_name) if type_name == "unsigned char" && pointer == "*mut" => {
+            InArgument {
+                param: quote! { #name: &mut [u8] },
+                input: quote! { #name.as_mut_ptr() },
+            }
+        }
+        FundamentalType(_) => {
+            let element = format_rust_type(&argument.argument_type, &None, &None, &None, api)?;
+            InArgument {
+                param: quote! { #name: &[#element] },
+                input: quote! { #name.as_ptr() #cast },
+            }
+        }
+        UserType(type_name) => {
+            let element = format_struct_ident(type_name);
+            let raw = format_ident!("{}", type_name);
+            InArgument {
+                param: quote! { #name: &[#element] },
+                input: quote! {
+                    #name.iter().cloned().map(Into::into).collect::<Vec<ffi::#raw>>().as_ptr() #cast
+                },
+            }
+        }
+    };
+    Ok(argument)
+}
+
+fn map_output(argument: &Argument, function: &Function, api: &Api) -> Result<OutArgument, Error> {
     let pointer = ffi::describe_pointer(&argument.as_const, &argument.pointer);
-    let arg = format_argument_ident(&argument.name);
+    let arg = format_argument_ident(&argument.name, api);
 
-    match &argument.argument_type {
+    let output = match &argument.argument_type {
         FundamentalType(type_name) => match &format!("{}:{}", pointer, type_name)[..] {
-            "*mut:char" => OutArgument {
-                target: quote! { let #arg = CString::from_vec_unchecked(b"".to_vec()).into_raw(); },
-                source: quote! { #arg },
-                output: quote! { CString::from_raw(#arg).into_string().map_err(Error::String)? },
+            "*mut:char" => {
+                // buffer with length argument is grown on truncation, see `generate_call`,
+                // FMOD writes names of fixed size up to 32 characters to buffers without length
+                let capacity = match find_buffer_length(argument, function, api) {
+                    Some(_) => quote! { 256 },
+                    None => quote! { 512 },
+                };
+                OutArgument {
+                    name: arg.clone(),
+                    target: quote! { let mut #arg = vec![0u8; #capacity]; },
+                    source: quote! { #arg.as_mut_ptr() as *mut c_char },
+                    output: quote! {{
+                        let length = #arg.iter().position(|&byte| byte == 0).unwrap_or(#arg.len());
+                        #arg.truncate(length);
+                        into_string!(unsafe { CString::from_vec_unchecked(#arg) })?
+                    }},
+                    retype: quote! { String },
+                }
+            }
+            "*mut:unsigned char" => {
+                let capacity = match find_buffer_length(argument, function, api) {
+                    Some(length) => quote! { #length.max(0) as usize },
+                    None => {
+                        return Err(Error::Unsupported(format!(
+                            "{} buffer has no length",
+                            function.name
+                        )))
+                    }
+                };
+                OutArgument {
+                    name: arg.clone(),
+                    target: quote! { let mut #arg = vec![0u8; #capacity]; },
+                    source: quote! { #arg.as_mut_ptr() },
+                    output: quote! { #arg },
+                    retype: quote! { Vec<u8> },
+                }
+            }
+            "*mut *mut:char" => OutArgument {
+                name: arg.clone(),
+                target: quote! { let mut #arg = null_mut(); },
+                source: quote! { &mut #arg },
+                output: quote! { to_string!(#arg)? },
+                retype: quote! { String },
+            },
+            "*const *const:char" => OutArgument {
+                name: arg.clone(),
+                target: quote! { let mut #arg = null(); },
+                source: quote! { &mut #arg },
+                output: quote! { to_string!(#arg)? },
                 retype: quote! { String },
             },
             "*mut:float" => OutArgument {
+                name: arg.clone(),
                 target: quote! { let mut #arg = f32::default(); },
                 source: quote! { &mut #arg },
                 output: quote! { #arg },
                 retype: quote! { f32 },
             },
+            "*mut:double" => OutArgument {
+                name: arg.clone(),
+                target: quote! { let mut #arg = f64::default(); },
+                source: quote! { &mut #arg },
+                output: quote! { #arg },
+                retype: quote! { f64 },
+            },
             "*mut:unsigned long long" => OutArgument {
+                name: arg.clone(),
                 target: quote! { let mut #arg = u64::default(); },
                 source: quote! { &mut #arg },
                 output: quote! { #arg },
                 retype: quote! { u64 },
             },
             "*mut:long long" => OutArgument {
+                name: arg.clone(),
                 target: quote! { let mut #arg = i64::default(); },
                 source: quote! { &mut #arg },
                 output: quote! { #arg },
                 retype: quote! { i64 },
             },
             "*mut:unsigned int" => OutArgument {
+                name: arg.clone(),
                 target: quote! { let mut #arg = u32::default(); },
                 source: quote! { &mut #arg },
                 output: quote! { #arg },
                 retype: quote! { u32 },
             },
             "*mut:int" => OutArgument {
+                name: arg.clone(),
                 target: quote! { let mut #arg = i32::default(); },
                 source: quote! { &mut #arg },
                 output: quote! { #arg },
                 retype: quote! { i32 },
             },
             "*mut *mut:void" => OutArgument {
+                name: arg.clone(),
                 target: quote! { let mut #arg = null_mut(); },
                 source: quote! { &mut #arg },
                 output: quote! { #arg },
                 retype: quote! { *mut c_void },
             },
             "*mut:void" => OutArgument {
+                name: arg.clone(),
                 target: quote! { let #arg = null_mut(); },
                 source: quote! { #arg },
                 output: quote! { #arg },
                 retype: quote! { *mut c_void },
             },
-            _ => unimplemented!(),
+            _ => match (pointer, ffi::map_sized_type(type_name)) {
+                ("*mut", Some(rust_type)) => OutArgument {
+                    name: arg.clone(),
+                    target: quote! { let mut #arg = #rust_type::default(); },
+                    source: quote! { &mut #arg },
+                    output: quote! { #arg },
+                    retype: quote! { #rust_type },
+                },
+                _ => return Err(unsupported_type(pointer, &argument.argument_type)),
+            },
         },
         UserType(user_type) => {
             let type_name = format_struct_ident(&user_type);
@@ -672,59 +1653,78 @@ fn map_output(argument: &Argument, _function: &Function, api: &Api) -> OutArgume
             match (pointer, api.describe_user_type(&user_type)) {
                 ("*mut", UserTypeDesc::TypeAlias) => match &user_type[..] {
                     "FMOD_BOOL" => OutArgument {
+                        name: arg.clone(),
                         target: quote! { let mut #arg = ffi::FMOD_BOOL::default(); },
                         source: quote! { &mut #arg },
                         output: quote! { to_bool!(#arg) },
                         retype: quote! { bool },
                     },
                     "FMOD_PORT_INDEX" => OutArgument {
-                        target: quote! { let mut #arg = u64::default(); },
+                        name: arg.clone(),
+                        target: quote! { let mut #arg = ffi::FMOD_PORT_INDEX::default(); },
                         source: quote! { &mut #arg },
-                        output: quote! { #arg },
-                        retype: quote! { u64 },
+                        output: quote! { PortIndex(#arg) },
+                        retype: quote! { PortIndex },
                     },
-                    _ => unimplemented!(),
+                    _ => return Err(unsupported_type(pointer, &argument.argument_type)),
                 },
                 ("*mut *mut", UserTypeDesc::OpaqueType) => OutArgument {
+                    name: arg.clone(),
                     target: quote! { let mut #arg = null_mut(); },
                     source: quote! { &mut #arg },
                     output: quote! { #type_name::from(#arg) },
                     retype: quote! { #type_name },
                 },
+                ("*mut", UserTypeDesc::Flags) if is_flags_type(user_type) => {
+                    let flags_type = format_flags_type(user_type);
+                    OutArgument {
+                        name: arg.clone(),
+                        target: quote! { let mut #arg = ffi::#ident::default(); },
+                        source: quote! { &mut #arg },
+                        output: quote! { #flags_type(#arg) },
+                        retype: quote! { #flags_type },
+                    }
+                }
                 ("*mut", UserTypeDesc::Flags) => OutArgument {
+                    name: arg.clone(),
                     target: quote! { let mut #arg = ffi::#ident::default(); },
                     source: quote! { &mut #arg },
                     output: quote! { #arg },
                     retype: quote! { ffi::#ident },
                 },
                 ("*mut", UserTypeDesc::Structure) => OutArgument {
+                    name: arg.clone(),
                     target: quote! { let mut #arg = ffi::#ident::default(); },
                     source: quote! { &mut #arg },
                     output: quote! { #type_name::try_from(#arg)? },
                     retype: quote! { #type_name },
                 },
                 ("*mut *mut", UserTypeDesc::Structure) => OutArgument {
+                    name: arg.clone(),
                     target: quote! { let mut #arg = null_mut(); },
                     source: quote! { &mut #arg },
-                    output: quote! { #type_name::try_from(*#arg)? },
+                    output: quote! { #type_name::try_from(unsafe { *#arg })? },
                     retype: quote! { #type_name },
                 },
                 ("*const *const", UserTypeDesc::Structure) => OutArgument {
+                    name: arg.clone(),
                     target: quote! { let mut #arg = null(); },
                     source: quote! { &mut #arg },
-                    output: quote! { #type_name::try_from(*#arg)? },
+                    output: quote! { #type_name::try_from(unsafe { *#arg })? },
                     retype: quote! { #type_name },
                 },
                 ("*mut", UserTypeDesc::Enumeration) => OutArgument {
+                    name: arg.clone(),
                     target: quote! { let mut #arg = ffi::#ident::default(); },
                     source: quote! { &mut #arg },
-                    output: quote! { #type_name::from(#arg)? },
+                    output: quote! { #type_name::from_raw_lossy(#arg) },
                     retype: quote! { #type_name },
                 },
-                _ => unimplemented!(),
+                _ => return Err(unsupported_type(pointer, &argument.argument_type)),
             }
         }
-    }
+    };
+    Ok(output)
 }
 
 pub struct Signature {
@@ -733,6 +1733,9 @@ pub struct Signature {
     pub targets: Vec<TokenStream>,
     pub outputs: Vec<TokenStream>,
     pub return_types: Vec<TokenStream>,
+    pub output_names: Vec<Ident>,
+    /// String buffers grown and passed again while FMOD truncates them.
+    pub buffers: Vec<Ident>,
 }
 
 impl Signature {
@@ -743,7 +1746,56 @@ impl Signature {
             targets: vec![],
             outputs: vec![],
             return_types: vec![],
+            output_names: vec![],
+            buffers: vec![],
+        }
+    }
+
+    pub fn describe_output_fields(&self) -> Option<Vec<(Ident, TokenStream)>> {
+        if self.outputs.len() < 2 || self.output_names.len() != self.outputs.len() {
+            return None;
         }
+        let types: Vec<String> = self.return_types.iter().map(|t| t.to_string()).collect();
+        let shared = types
+            .iter()
+            .enumerate()
+            .any(|(index, return_type)| types[index + 1..].contains(return_type));
+        if !shared {
+            return None;
+        }
+        let fields = self
+            .output_names
+            .iter()
+            .cloned()
+            .zip(self.return_types.iter().cloned())
+            .collect();
+        Some(fields)
+    }
+
+    fn add_optional_output(&mut self, argument: OutArgument) {
+        let OutArgument {
+            name,
+            target,
+            source,
+            output,
+            retype,
+        } = argument;
+        let flag = format_ident!("with_{}", name);
+        self.arguments.push(quote! { #flag: bool });
+        self.targets.push(target);
+        self.inputs
+            .push(quote! { if #flag { #source } else { null_mut() } });
+        self.outputs
+            .push(quote! { if #flag { Some(#output) } else { None } });
+        self.return_types.push(quote! { Option<#retype> });
+        self.output_names.push(name);
+    }
+
+    pub fn name_outputs(&mut self, structure: &Ident) {
+        let names = &self.output_names;
+        let outputs = &self.outputs;
+        self.outputs = vec![quote! { #structure { #(#names: #outputs),* } }];
+        self.return_types = vec![quote! { #structure }];
     }
 
     pub fn define(
@@ -778,97 +1830,909 @@ impl AddAssign<OutArgument> for Signature {
         self.inputs.push(argument.source);
         self.outputs.push(argument.output);
         self.return_types.push(argument.retype);
+        self.output_names.push(argument.name);
     }
 }
 
-pub fn generate_method(owner: &str, function: &Function, api: &Api) -> TokenStream {
-    let mut signature = Signature::new();
+fn build_signature(owner: &str, function: &Function, api: &Api) -> Result<Signature, Error> {
+    build_signature_with(owner, function, api, false)
+}
 
-    if let Some(overriding) = api.function_patches.get(&function.name) {
-        return overriding.clone();
+/// Partial signature takes flags to skip optional outputs,
+/// available only for functions with several outputs.
+fn build_partial_signature(
+    owner: &str,
+    function: &Function,
+    api: &Api,
+) -> Result<Option<Signature>, Error> {
+    let optional = function
+        .arguments
+        .iter()
+        .any(|argument| api.is_optional_output(&function.name, &argument.name));
+    if !optional {
+        return Ok(None);
     }
+    let signature = build_signature_with(owner, function, api, true)?;
+    if signature.outputs.len() < 2 {
+        return Ok(None);
+    }
+    Ok(Some(signature))
+}
 
+fn build_signature_with(
+    owner: &str,
+    function: &Function,
+    api: &Api,
+    partial: bool,
+) -> Result<Signature, Error> {
+    let mut signature = Signature::new();
+    let format = match function.varargs {
+        Some(_) => function.arguments.last(),
+        None => None,
+    };
     for argument in &function.arguments {
-        if !signature.patch_function_signature(owner, function, argument) {
+        if Some(argument) == format {
+            signature += map_format(argument, api);
+        } else if signature.patch_function_signature(owner, function, argument) {
+            continue;
+        } else if let Some(buffer) = find_length_buffer(argument, function, api) {
+            let buffer = format_argument_ident(&buffer.name, api);
+            signature.inputs.push(quote! { #buffer.len() as i32 });
+            signature.buffers.push(buffer);
+        } else if api
+            .get_array_length(&function.name, &argument.name)
+            .is_some()
+        {
+            signature += map_array(argument, api)?;
+        } else if let Some((length, checks)) = api.get_length_arrays(function, &argument.name) {
+            signature.inputs.push(length);
+            signature.targets.extend(checks);
+        } else {
+            let unit = api.get_unit(&function.name, argument);
+            let boolean = api.is_boolean(&function.name, argument);
             match api.get_modifier(&function.name, &argument.name) {
-                Modifier::None => signature += map_input(argument, api),
-                Modifier::Opt => signature += map_optional(argument, api),
-                Modifier::Out => signature += map_output(argument, function, api),
+                Modifier::None if boolean => signature += map_bool_input(argument, api),
+                Modifier::Out if boolean => signature += map_bool_output(argument, api),
+                Modifier::None => match unit {
+                    Some(unit) => signature += map_unit_input(argument, unit, api)?,
+                    None => signature += map_input(argument, api)?,
+                },
+                Modifier::Opt if boolean && argument.pointer.is_none() => {
+                    signature += map_optional_bool(argument, api)
+                }
+                Modifier::Opt => signature += map_optional(argument, api)?,
+                Modifier::Out => {
+                    let output = map_output(argument, function, api)?;
+                    let output = match unit {
+                        Some(unit) => map_unit_output(output, unit),
+                        None => output,
+                    };
+                    if partial && api.is_optional_output(&function.name, &argument.name) {
+                        signature.add_optional_output(output);
+                    } else {
+                        signature += output;
+                    }
+                }
+                Modifier::OptOut => unreachable!(),
             }
         }
     }
+    Ok(signature)
+}
 
-    let (arguments, inputs, out, output, returns) = signature.define();
-    let method_name = extract_method_name(&function.name);
-    let method = format_ident!("{}", method_name);
-    let function_name = &function.name;
-    let function = format_ident!("{}", function_name);
+fn format_output_structure_name(method: &str) -> String {
+    let name = method.strip_prefix("get_").unwrap_or(method);
+    let name = name.to_case(Case::UpperCamel);
+    let name = match name.get(..2) {
+        Some(prefix @ ("3d" | "2d")) => format!("{}{}", &name[2..], prefix),
+        _ => name,
+    };
+    format!("{}Result", name)
+}
 
-    quote! {
-        pub fn #method( #(#arguments),* ) -> Result<#returns, Error> {
-            unsafe {
-                #(#out)*
-                match ffi::#function( #(#inputs),* ) {
-                    ffi::FMOD_OK => Ok(#output),
-                    error => Err(err_fmod!(#function_name, error)),
+/// Output fields of methods grouped by name of result structure.
+type OutputCandidates<'a> =
+    BTreeMap<String, Vec<(&'a String, &'a Function, Vec<(Ident, TokenStream)>)>>;
+
+#[derive(Default)]
+pub struct OutputStructures {
+    pub names: HashMap<String, Ident>,
+    pub definitions: Vec<TokenStream>,
+}
+
+impl OutputStructures {
+    pub fn collect(types: &BTreeMap<String, Vec<&Function>>, api: &Api) -> Result<Self, Error> {
+        let mut candidates = OutputCandidates::new();
+        for (key, methods) in types {
+            for function in methods {
+                if api.function_patches.contains_key(&function.name) {
+                    continue;
+                }
+                let signature = build_signature(key, function, api)?;
+                if let Some(fields) = signature.describe_output_fields() {
+                    let name = format_output_structure_name(&extract_method_name(&function.name));
+                    candidates
+                        .entry(name)
+                        .or_default()
+                        .push((key, function, fields));
+                }
+            }
+        }
+
+        let mut structures = OutputStructures::default();
+        for (name, candidates) in candidates {
+            let describe = |fields: &Vec<(Ident, TokenStream)>| {
+                fields
+                    .iter()
+                    .map(|(name, field_type)| format!("{}:{}", name, field_type))
+                    .collect::<Vec<String>>()
+            };
+            let shared = candidates
+                .iter()
+                .all(|(_, _, fields)| describe(fields) == describe(&candidates[0].2));
+            for (index, (key, function, fields)) in candidates.iter().enumerate() {
+                let ident = if shared {
+                    format_ident!("{}", name)
+                } else {
+                    format_ident!("{}{}", format_struct_ident(key), name)
+                };
+                let taken = api
+                    .structures
+                    .iter()
+                    .any(|structure| format_struct_ident(&structure.name) == ident);
+                if taken {
+                    return Err(Error::Unsupported(format!(
+                        "{} result structure collides with existing structure",
+                        ident
+                    )));
+                }
+                structures
+                    .names
+                    .insert(function.name.clone(), ident.clone());
+                if shared && index > 0 {
+                    continue;
                 }
+                let fields = fields
+                    .iter()
+                    .map(|(name, field_type)| quote! { pub #name: #field_type });
+                structures.definitions.push(quote! {
+                    #[derive(Debug, Clone)]
+                    pub struct #ident {
+                        #(#fields),*
+                    }
+                });
             }
         }
+        Ok(structures)
     }
 }
 
-pub fn generate_opaque_type(key: &String, methods: &Vec<&Function>, api: &Api) -> TokenStream {
-    let name = format_struct_ident(key);
-    let opaque_type = format_ident!("{}", key);
-
-    let methods: Vec<TokenStream> = methods
+/// Attributes hiding methods behind cargo feature or on platforms where function is missing.
+fn format_function_gate(function: &str) -> TokenStream {
+    let feature = FEATURE_GATED_FUNCTIONS
         .iter()
-        .map(|method| generate_method(key, method, api))
-        .collect();
+        .find(|(name, _)| *name == function)
+        .map(|(_, feature)| quote! { #[cfg(feature = #feature)] });
+    let platform = WASM_UNSUPPORTED_FUNCTIONS
+        .contains(&function)
+        .then(|| quote! { #[cfg(not(target_family = "wasm"))] });
+    quote! { #feature #platform }
+}
 
+/// Match arm telling released handle apart from other invalid handle errors,
+/// generated for methods of types having `IsValid` function.
+fn generate_release_check(owner: &str, function: &Function, api: &Api) -> TokenStream {
+    let is_owner = |argument: Option<&Argument>| {
+        argument
+            .map(|argument| {
+                argument.argument_type.is_user_type(owner)
+                    && describe_pointer(&argument.as_const, &argument.pointer) == "*mut"
+            })
+            .unwrap_or(false)
+    };
+    let validated = api
+        .functions
+        .iter()
+        .flat_map(|(_, functions)| functions)
+        .any(|other| other.name.ends_with("_IsValid") && is_owner(other.arguments.first()));
+    if !validated || !is_owner(function.arguments.first()) {
+        return quote! {};
+    }
+    let function_name = &function.name;
+    let function = if api.settings.static_errors {
+        quote! { #function_name }
+    } else {
+        quote! { #function_name.to_string() }
+    };
     quote! {
-        #[derive(Debug, Clone, Copy)]
-        pub struct #name {
-            pointer: *mut ffi::#opaque_type,
+        #[cfg(feature = "debug-valid")]
+        ffi::FMOD_ERR_INVALID_HANDLE if !self.is_valid() => Err(Error::UseAfterRelease { function: #function }),
+    }
+}
+
+pub fn generate_method(
+    owner: &str,
+    function: &Function,
+    api: &Api,
+    outputs: &OutputStructures,
+) -> Result<TokenStream, Error> {
+    if let Some(overriding) = api.function_patches.get(&function.name) {
+        return Ok(overriding.clone());
+    }
+
+    let mut signature = build_signature(owner, function, api)?;
+    if let Some(structure) = outputs.names.get(&function.name) {
+        signature.name_outputs(structure);
+    }
+    let partial = build_partial_signature(owner, function, api)?;
+    let released = generate_release_check(owner, function, api);
+    let callback_doc = if function.role == FunctionRole::CallbackSetter {
+        quote! {
+            /// Takes raw FMOD callback, it is called from FMOD threads
+            /// and must not unwind across FFI boundary.
         }
+    } else {
+        quote! {}
+    };
 
-        unsafe impl Send for #name {}
+    let call = generate_call(&function.name, &signature.inputs, &signature.buffers);
+    let (arguments, _, out, output, returns) = signature.define();
+    let method_name = api.method_name(&function.name);
+    let method = format_ident!("{}", method_name);
+    let function_name = &function.name;
+    let gate = format_function_gate(function_name);
 
-        unsafe impl Sync for #name {}
+    let partial = partial.map(|signature| {
+        let call = generate_call(function_name, &signature.inputs, &signature.buffers);
+        let (arguments, _, out, output, returns) = signature.define();
+        let method = format_ident!("{}_partial", method_name);
+        quote! {
+            #gate
+            pub fn #method( #(#arguments),* ) -> Result<#returns, Error> {
+                #(#out)*
+                match #call {
+                    ffi::FMOD_OK => Ok(#output),
+                    #released
+                    error => Err(err_fmod!(#function_name, error)),
+                }
+            }
+        }
+    });
 
-        impl #name {
-            #[inline]
-            pub fn from(pointer: *mut ffi::#opaque_type) -> Self {
-                Self { pointer }
+    Ok(quote! {
+        #gate
+        #callback_doc
+        pub fn #method( #(#arguments),* ) -> Result<#returns, Error> {
+            #(#out)*
+            match #call {
+                ffi::FMOD_OK => Ok(#output),
+                #released
+                error => Err(err_fmod!(#function_name, error)),
             }
-            #[inline]
-            pub fn as_mut_ptr(&self) -> *mut ffi::#opaque_type {
-                self.pointer
+        }
+        #partial
+    })
+}
+
+/// FMOD call expression, string buffers are doubled and call is repeated
+/// while FMOD fills them up to the end, because it truncates strings silently.
+fn generate_call(function_name: &str, inputs: &[TokenStream], buffers: &[Ident]) -> TokenStream {
+    let function = format_ident!("{}", function_name);
+    let call = quote! { profile_call!(#function_name, unsafe { ffi::#function( #(#inputs),* ) }) };
+    if buffers.is_empty() {
+        return call;
+    }
+    quote! {
+        loop {
+            let result = #call;
+            let mut grown = false;
+            #(
+                if matches!(result, ffi::FMOD_OK | ffi::FMOD_ERR_TRUNCATED)
+                    && #buffers.len() < 4096
+                    && !#buffers[..#buffers.len() - 1].contains(&0)
+                {
+                    #buffers = vec![0u8; #buffers.len() * 2];
+                    grown = true;
+                }
+            )*
+            if !grown {
+                break result;
             }
-            #(#methods)*
         }
     }
 }
 
-#[derive(Debug)]
-enum UserTypeDesc {
-    OpaqueType,
-    Structure,
-    Enumeration,
-    Flags,
-    Constant,
+/// Name of parameter declared as `name: Type`, receiver has no name.
+fn format_param_name(param: &TokenStream) -> Option<Ident> {
+    match param.clone().into_iter().next() {
+        Some(TokenTree::Ident(name)) if name != "self" => Some(name),
+        _ => None,
+    }
+}
+
+/// Convenience methods for functions taking time unit as last argument,
+/// e.g. `position_ms()` for `get_position(unit)`.
+pub fn generate_time_unit_methods(
+    owner: &str,
+    function: &Function,
+    api: &Api,
+) -> Result<Option<TokenStream>, Error> {
+    if api.function_patches.contains_key(&function.name) {
+        return Ok(None);
+    }
+    let is_unit = |argument: &Argument| {
+        argument.argument_type == UserType("FMOD_TIMEUNIT".into()) && argument.pointer.is_none()
+    };
+    let units = function
+        .arguments
+        .iter()
+        .filter(|argument| is_unit(argument))
+        .count();
+    if units != 1 || !function.arguments.last().map(is_unit).unwrap_or(false) {
+        return Ok(None);
+    }
+    let signature = build_signature(owner, function, api)?;
+    let method_name = extract_method_name(&function.name);
+    let method = format_ident!("{}", api.method_name(&function.name));
+    let gate = format_function_gate(&function.name);
+    let mut methods = vec![];
+    for (suffix, constant) in [("ms", "MS"), ("pcm", "PCM")] {
+        let constant = format_ident!("{}", constant);
+        match (signature.arguments.len(), signature.outputs.len()) {
+            (2, 1) if function.role == FunctionRole::Getter => {
+                let name = format_ident!("{}_{}", &method_name["get_".len()..], suffix);
+                let returns = quote_tuple(&signature.return_types);
+                methods.push(quote! {
+                    #gate
+                    pub fn #name(&self) -> Result<#returns, Error> {
+                        self.#method(TimeUnit::#constant)
+                    }
+                });
+            }
+            (3, 0) if function.role == FunctionRole::Setter => {
+                let name = format_ident!("{}_{}", method_name, suffix);
+                let param = &signature.arguments[1];
+                let value = format_argument_ident(&function.arguments[1].name, api);
+                methods.push(quote! {
+                    #gate
+                    pub fn #name(&self, #param) -> Result<(), Error> {
+                        self.#method(#value, TimeUnit::#constant)
+                    }
+                });
+            }
+            _ => return Ok(None),
+        }
+    }
+    Ok(Some(quote! { #(#methods)* }))
+}
+
+/// Type of parameter declared as `name: Type`.
+fn format_param_type(param: &TokenStream) -> Option<TokenStream> {
+    let mut tokens = param.clone().into_iter();
+    match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Ident(_)), Some(TokenTree::Punct(colon))) if colon.as_char() == ':' => {
+            Some(tokens.collect())
+        }
+        _ => None,
+    }
+}
+
+/// Pairs getters with setters of the same property, e.g. `GetVolume` with `SetVolume`,
+/// when setter takes exactly the type getter returns.
+pub fn detect_property_pairs<'a>(
+    methods: &[&'a Function],
+    api: &Api,
+) -> Vec<(&'a Function, &'a Function)> {
+    let mut pairs = vec![];
+    for getter in methods {
+        if getter.role != FunctionRole::Getter || getter.arguments.len() != 2 {
+            continue;
+        }
+        let setter_suffix = match extract_function_suffix(&getter.name).strip_prefix("Get") {
+            Some(property) => format!("Set{}", property),
+            None => continue,
+        };
+        let setter = methods.iter().find(|setter| {
+            extract_function_suffix(&setter.name) == setter_suffix
+                && setter.role == FunctionRole::Setter
+                && setter.arguments.len() == 2
+        });
+        if let Some(setter) = setter {
+            if !api.function_patches.contains_key(&getter.name)
+                && !api.function_patches.contains_key(&setter.name)
+            {
+                pairs.push((*getter, *setter));
+            }
+        }
+    }
+    pairs
+}
+
+/// Read-modify-write method of property, e.g. `update_volume(|volume| volume * 0.5)`.
+pub fn generate_property_update(
+    owner: &str,
+    getter: &Function,
+    setter: &Function,
+    api: &Api,
+) -> Result<Option<TokenStream>, Error> {
+    let getter_signature = build_signature(owner, getter, api)?;
+    let setter_signature = build_signature(owner, setter, api)?;
+    if getter_signature.arguments.len() != 1
+        || getter_signature.return_types.len() != 1
+        || setter_signature.arguments.len() != 2
+        || !setter_signature.return_types.is_empty()
+    {
+        return Ok(None);
+    }
+    let value = &getter_signature.return_types[0];
+    let accepted = match format_param_type(&setter_signature.arguments[1]) {
+        Some(accepted) => accepted,
+        None => return Ok(None),
+    };
+    // raw pointers like user data have nothing to update
+    if value.to_string() != accepted.to_string() || value.to_string().contains('*') {
+        return Ok(None);
+    }
+    let property = &extract_method_name(&getter.name)["get_".len()..];
+    let name = format_ident!("update_{}", property);
+    let getter_method = format_ident!("{}", api.method_name(&getter.name));
+    let setter_method = format_ident!("{}", api.method_name(&setter.name));
+    let getter_gate = format_function_gate(&getter.name);
+    let setter_gate = format_function_gate(&setter.name);
+    Ok(Some(quote! {
+        #getter_gate
+        #setter_gate
+        /// Gets property value, passes it through `update` and sets the result.
+        pub fn #name(&self, update: impl FnOnce(#value) -> #value) -> Result<(), Error> {
+            let value = self.#getter_method()?;
+            self.#setter_method(update(value))
+        }
+    }))
+}
+
+/// Handles having destructor are released by [`Owned`] wrapper when dropped.
+pub fn generate_release_impl(key: &str, methods: &[&Function], api: &Api) -> TokenStream {
+    let destructor = match methods
+        .iter()
+        .find(|method| method.role == FunctionRole::Destructor)
+    {
+        Some(destructor) => destructor,
+        None => return quote! {},
+    };
+    let name = format_struct_ident(key);
+    let method = format_ident!("{}", api.method_name(&destructor.name));
+    quote! {
+        impl Release for #name {
+            fn release(&self) -> Result<(), Error> {
+                #name::#method(self)
+            }
+        }
+    }
+}
+
+/// Constructor returning handle released when dropped, e.g. `System::create_owned()`.
+pub fn generate_owned_constructor(
+    owner: &str,
+    function: &Function,
+    methods: &[&Function],
+    api: &Api,
+) -> Result<Option<TokenStream>, Error> {
+    if function.role != FunctionRole::Constructor
+        || !methods
+            .iter()
+            .any(|method| method.role == FunctionRole::Destructor)
+    {
+        return Ok(None);
+    }
+    let signature = build_signature(owner, function, api)?;
+    let method_name = api.method_name(&function.name);
+    let method = format_ident!("{}", method_name);
+    let name = format_ident!("{}_owned", method_name);
+    let arguments = &signature.arguments;
+    let values = arguments.iter().filter_map(format_param_name);
+    let gate = format_function_gate(&function.name);
+    Ok(Some(quote! {
+        #gate
+        pub fn #name( #(#arguments),* ) -> Result<Owned<Self>, Error> {
+            Self::#method( #(#values),* ).map(Owned::new)
+        }
+    }))
+}
+
+/// Two-phase string functions end with buffer, its size and retrieved length
+/// arguments, buffer of caller can be passed to them directly.
+pub fn generate_string_into_method(
+    owner: &str,
+    function: &Function,
+    api: &Api,
+) -> Result<Option<TokenStream>, Error> {
+    let count = function.arguments.len();
+    if count < 4 || function.varargs.is_some() {
+        return Ok(None);
+    }
+    let int = FundamentalType("int".into());
+    let (buffer, size, retrieved) = (
+        &function.arguments[count - 3],
+        &function.arguments[count - 2],
+        &function.arguments[count - 1],
+    );
+    if buffer.argument_type != FundamentalType("char".into())
+        || describe_pointer(&buffer.as_const, &buffer.pointer) != "*mut"
+        || size.argument_type != int
+        || size.pointer.is_some()
+        || retrieved.argument_type != int
+        || describe_pointer(&retrieved.as_const, &retrieved.pointer) != "*mut"
+    {
+        return Ok(None);
+    }
+    let head = Function {
+        arguments: function.arguments[..count - 3].to_vec(),
+        ..function.clone()
+    };
+    let signature = build_signature(owner, &head, api)?;
+    if !signature.outputs.is_empty() {
+        return Ok(None);
+    }
+    let released = generate_release_check(owner, function, api);
+    let (arguments, inputs, targets, _, _) = signature.define();
+    let method = format_ident!("{}_into", api.method_name(&function.name));
+    let function_name = &function.name;
+    let function = format_ident!("{}", function_name);
+    let gate = format_function_gate(function_name);
+    Ok(Some(quote! {
+        #gate
+        pub fn #method(#(#arguments,)* buffer: &mut [u8]) -> Result<usize, Error> {
+            #(#targets)*
+            let mut retrieved = 0;
+            match profile_call!(#function_name, unsafe {
+                ffi::#function(
+                    #(#inputs,)*
+                    buffer.as_mut_ptr() as *mut c_char,
+                    buffer.len() as i32,
+                    &mut retrieved
+                )
+            }) {
+                ffi::FMOD_OK => Ok((retrieved as usize).saturating_sub(1)),
+                #released
+                error => Err(err_fmod!(#function_name, error)),
+            }
+        }
+    }))
+}
+
+fn extract_function_suffix(name: &str) -> &str {
+    match name.rfind('_') {
+        Some(index) => &name[index + 1..],
+        None => name,
+    }
+}
+
+fn format_collection_name(getter: &str) -> Ident {
+    let name = getter
+        .trim_start_matches("get_")
+        .trim_end_matches("_by_index");
+    let name = match name.strip_suffix('y') {
+        Some(stem) => format!("{}ies", stem),
+        None => format!("{}s", name),
+    };
+    format_ident!("{}", name)
+}
+
+pub fn detect_indexed_collections<'a>(
+    methods: &[&'a Function],
+    api: &Api,
+) -> Vec<(&'a Function, &'a Function)> {
+    let mut collections = vec![];
+    for count in methods {
+        if api.function_patches.contains_key(&count.name) || count.role != FunctionRole::Count {
+            continue;
+        }
+        let suffix = extract_function_suffix(&count.name);
+        let getter_suffix = if let Some(items) = suffix.strip_prefix("GetNum") {
+            match items.strip_suffix('s') {
+                Some(item) => format!("Get{}", item),
+                None => continue,
+            }
+        } else if let Some(item) = suffix.strip_suffix("Count") {
+            format!("{}ByIndex", item)
+        } else {
+            continue;
+        };
+        let getter = methods.iter().find(|getter| {
+            extract_function_suffix(&getter.name) == getter_suffix
+                && !api.function_patches.contains_key(&getter.name)
+                && getter.role == FunctionRole::IndexedGetter
+        });
+        if let Some(getter) = getter {
+            collections.push((*count, *getter));
+        }
+    }
+    collections
+}
+
+pub fn generate_indexed_collection(
+    owner: &str,
+    count: &Function,
+    getter: &Function,
+    api: &Api,
+) -> Result<TokenStream, Error> {
+    let item = quote_tuple(&build_signature(owner, getter, api)?.return_types);
+    let name = format_collection_name(&extract_method_name(&getter.name));
+    let getter_gate = format_function_gate(&getter.name);
+    let count_gate = format_function_gate(&count.name);
+    let getter = format_ident!("{}", api.method_name(&getter.name));
+    let count = format_ident!("{}", api.method_name(&count.name));
+    Ok(quote! {
+        #getter_gate
+        #count_gate
+        pub fn #name(&self) -> Result<impl Iterator<Item = Result<#item, Error>>, Error> {
+            let this = *self;
+            let count = this.#count()?;
+            Ok((0..count).map(move |index| this.#getter(index)))
+        }
+    })
+}
+
+/// Pairs list functions with functions counting their items, e.g. `GetEventList` with `GetEventCount`.
+pub fn detect_counted_lists<'a>(
+    methods: &[&'a Function],
+    api: &Api,
+) -> Vec<(&'a Function, &'a Function)> {
+    let mut lists = vec![];
+    for list in methods {
+        if list.role != FunctionRole::List {
+            continue;
+        }
+        let count_suffix = match extract_function_suffix(&list.name).strip_suffix("List") {
+            Some(items) => format!("{}Count", items),
+            None => continue,
+        };
+        let count = methods.iter().find(|count| {
+            extract_function_suffix(&count.name) == count_suffix
+                && !api.function_patches.contains_key(&count.name)
+                && count.role == FunctionRole::Count
+        });
+        if let Some(count) = count {
+            lists.push((*list, *count));
+        }
+    }
+    lists
+}
+
+/// List getter sized by count function, raw items are fetched by one FMOD call
+/// into array allocated for all of them, only conversion is deferred to iteration.
+pub fn generate_list_iterator(
+    owner: &str,
+    list: &Function,
+    count: &Function,
+    api: &Api,
+) -> Option<TokenStream> {
+    let array = list.arguments.get(1)?;
+    let item_type = match &array.argument_type {
+        UserType(name) => name,
+        FundamentalType(_) => return None,
+    };
+    let item = format_struct_ident(item_type);
+    let raw = format_ident!("{}", item_type);
+    let pointer = describe_pointer(&array.as_const, &array.pointer);
+    let (target, convert) = match (pointer, api.describe_user_type(item_type)) {
+        ("*mut *mut", UserTypeDesc::OpaqueType) => (
+            quote! { vec![null_mut(); capacity.max(0) as usize] },
+            quote! { |pointer| #item::try_from_ptr(pointer).map(Ok) },
+        ),
+        ("*mut", UserTypeDesc::Structure) => (
+            quote! { vec![ffi::#raw::default(); capacity.max(0) as usize] },
+            quote! { |item| Some(#item::try_from(item)) },
+        ),
+        _ => return None,
+    };
+    let released = generate_release_check(owner, list, api);
+    let method = format_ident!("{}", api.method_name(&list.name));
+    let count_gate = format_function_gate(&count.name);
+    let count = format_ident!("{}", api.method_name(&count.name));
+    let function_name = &list.name;
+    let function = format_ident!("{}", function_name);
+    let gate = format_function_gate(function_name);
+    Some(quote! {
+        #gate
+        #count_gate
+        /// Raw items are fetched at once into array allocated for all of them,
+        /// each item is converted when iterator reaches it, null handles are skipped.
+        pub fn #method(&self) -> Result<impl Iterator<Item = Result<#item, Error>>, Error> {
+            let capacity = self.#count()?;
+            let mut array = #target;
+            let mut count = 0;
+            match profile_call!(#function_name, unsafe {
+                ffi::#function(self.pointer, array.as_mut_ptr(), capacity, &mut count)
+            }) {
+                ffi::FMOD_OK => Ok(array.into_iter().take(count.max(0) as usize).filter_map(#convert)),
+                #released
+                error => Err(err_fmod!(#function_name, error)),
+            }
+        }
+    })
+}
+
+pub fn generate_opaque_type(
+    key: &String,
+    methods: &Vec<&Function>,
+    api: &Api,
+    outputs: &OutputStructures,
+) -> Result<TokenStream, Error> {
+    let name = format_struct_ident(key);
+    let opaque_type = format_ident!("{}", key);
+
+    let label = name.to_string();
+    let debug = if methods
+        .iter()
+        .any(|method| method.name.ends_with("_IsValid"))
+    {
+        quote! {
+            if cfg!(feature = "debug-valid") {
+                write!(f, "{}({:p}, valid: {})", #label, self.pointer, self.is_valid())
+            } else {
+                write!(f, "{}({:p})", #label, self.pointer)
+            }
+        }
+    } else {
+        quote! {
+            write!(f, "{}({:p})", #label, self.pointer)
+        }
+    };
+
+    let collections = detect_indexed_collections(methods, api)
+        .into_iter()
+        .map(|(count, getter)| generate_indexed_collection(key, count, getter, api))
+        .collect::<Result<Vec<TokenStream>, Error>>()?;
+
+    let mut buffers = vec![];
+    let mut time_units = vec![];
+    let mut constructors = vec![];
+    for method in methods {
+        buffers.extend(generate_string_into_method(key, method, api)?);
+        time_units.extend(generate_time_unit_methods(key, method, api)?);
+        constructors.extend(generate_owned_constructor(key, method, methods, api)?);
+    }
+    let mut updates = vec![];
+    for (getter, setter) in detect_property_pairs(methods, api) {
+        updates.extend(generate_property_update(key, getter, setter, api)?);
+    }
+    let release = generate_release_impl(key, methods, api);
+
+    let mut lists = vec![];
+    let mut iterated = HashSet::new();
+    if api.settings.list_iterators {
+        for (list, count) in detect_counted_lists(methods, api) {
+            if let Some(iterator) = generate_list_iterator(key, list, count, api) {
+                lists.push(iterator);
+                iterated.insert(&list.name);
+            }
+        }
+    }
+
+    let methods = methods
+        .iter()
+        .filter(|method| !iterated.contains(&method.name))
+        .map(|method| generate_method(key, method, api, outputs))
+        .collect::<Result<Vec<TokenStream>, Error>>()?;
+    let helpers = api.opaque_type_patches.get(key);
+
+    let resource = if api.settings.bevy && (key == "FMOD_SYSTEM" || key == "FMOD_STUDIO_SYSTEM") {
+        quote! { #[cfg_attr(feature = "bevy", derive(bevy_ecs::prelude::Resource))] }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        #[derive(Clone, Copy)]
+        #resource
+        pub struct #name {
+            pointer: *mut ffi::#opaque_type,
+        }
+
+        impl Debug for #name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                #debug
+            }
+        }
+
+        #[cfg(feature = "send-sync")]
+        unsafe impl Send for #name {}
+
+        #[cfg(feature = "send-sync")]
+        unsafe impl Sync for #name {}
+
+        impl #name {
+            #[inline]
+            pub fn from(pointer: *mut ffi::#opaque_type) -> Self {
+                Self { pointer }
+            }
+            #[inline]
+            pub fn try_from_ptr(pointer: *mut ffi::#opaque_type) -> Option<Self> {
+                if pointer.is_null() {
+                    None
+                } else {
+                    Some(Self { pointer })
+                }
+            }
+            /// Wraps raw handle obtained from FFI.
+            ///
+            /// # Safety
+            ///
+            /// Pointer must be valid handle of this type or null.
+            #[inline]
+            pub unsafe fn from_raw(pointer: *mut ffi::#opaque_type) -> Self {
+                Self { pointer }
+            }
+            #[inline]
+            pub fn as_mut_ptr(&self) -> *mut ffi::#opaque_type {
+                self.pointer
+            }
+            /// Returns raw handle to pass to FFI functions not covered by wrapper.
+            #[inline]
+            pub fn into_raw(self) -> *mut ffi::#opaque_type {
+                self.pointer
+            }
+            #(#methods)*
+            #(#buffers)*
+            #(#time_units)*
+            #(#collections)*
+            #(#lists)*
+            #(#updates)*
+            #(#constructors)*
+        }
+
+        #release
+
+        #helpers
+    })
+}
+
+#[derive(Debug)]
+enum UserTypeDesc {
+    OpaqueType,
+    Structure,
+    Enumeration,
+    Flags,
+    Constant,
     TypeAlias,
     Callback,
+    Union,
     Unknown,
 }
 
 impl Api {
+    /// Method name of function according to naming setting.
+    pub fn method_name(&self, function: &str) -> String {
+        match self.method_names.get(function) {
+            Some(name) => name.clone(),
+            None => extract_method_name(function),
+        }
+    }
+
+    /// Generated method of function, hand-written code calls generated methods through it.
+    pub fn method_ident(&self, function: &str) -> Ident {
+        format_ident!("{}", self.method_name(function))
+    }
+
+    pub fn version(&self) -> Option<SdkVersion> {
+        self.defines
+            .get("FMOD_VERSION")
+            .map(|value| SdkVersion::from_define(*value))
+    }
+
     pub fn is_structure(&self, key: &str) -> bool {
         self.structures
             .iter()
             .any(|structure| &structure.name == key)
     }
 
+    pub fn is_union(&self, key: &str) -> bool {
+        self.structures
+            .iter()
+            .any(|structure| structure.is_union && structure.name == key)
+    }
+
     pub fn is_opaque_type(&self, key: &str) -> bool {
         self.opaque_types
             .iter()
@@ -900,7 +2764,9 @@ impl Api {
     }
 
     fn describe_user_type(&self, key: &str) -> UserTypeDesc {
-        if self.is_structure(key) {
+        if self.is_union(key) {
+            UserTypeDesc::Union
+        } else if self.is_structure(key) {
             UserTypeDesc::Structure
         } else if self.is_enumeration(key) {
             UserTypeDesc::Enumeration
@@ -919,13 +2785,126 @@ impl Api {
         }
     }
 
+    pub fn get_field_role(&self, structure: &str, field: &str) -> Option<&FieldRole> {
+        self.field_roles.get(&format!("{}+{}", structure, field))
+    }
+
+    pub fn get_array_length(&self, function: &str, array: &str) -> Option<&String> {
+        self.array_lengths.get(&format!("{}+{}", function, array))
+    }
+
+    /// Builds length argument input from slices of arrays paired with it
+    /// and checks rejecting slices of different lengths.
+    pub fn get_length_arrays(
+        &self,
+        function: &Function,
+        length: &str,
+    ) -> Option<(TokenStream, Vec<TokenStream>)> {
+        let arrays: Vec<Ident> = function
+            .arguments
+            .iter()
+            .filter(|argument| {
+                self.get_array_length(&function.name, &argument.name)
+                    .is_some_and(|name| name == length)
+            })
+            .map(|argument| format_argument_ident(&argument.name, self))
+            .collect();
+        let (first, rest) = arrays.split_first()?;
+        let checks = rest
+            .iter()
+            .map(|array| {
+                let name = array.to_string();
+                quote! {
+                    if #array.len() != #first.len() {
+                        return Err(Error::LengthMismatch {
+                            name: #name,
+                            expected: #first.len(),
+                            actual: #array.len(),
+                        });
+                    }
+                }
+            })
+            .collect();
+        Some((quote! { #first.len() as _ }, checks))
+    }
+
+    /// Finds field holding number of elements in given field of structure.
+    pub fn is_byte_buffer(&self, structure: &str, field: &str) -> bool {
+        self.structures
+            .iter()
+            .filter(|candidate| candidate.name == structure)
+            .flat_map(|candidate| &candidate.fields)
+            .any(|candidate| {
+                candidate.name == field
+                    && candidate.field_type == FundamentalType("unsigned char".into())
+                    && matches!(
+                        describe_pointer(&candidate.as_const, &candidate.pointer),
+                        "*const" | "*mut"
+                    )
+            })
+    }
+
+    pub fn find_count_field(&self, structure: &str, items: &str) -> Option<&str> {
+        let prefix = format!("{}+", structure);
+        self.field_roles.iter().find_map(|(key, role)| match role {
+            FieldRole::CountOf(name) if name == items => key.strip_prefix(&prefix),
+            _ => None,
+        })
+    }
+
+    pub fn get_field_direction(&self, structure: &str, field: &str) -> FieldDirection {
+        let key = format!("{}+{}", structure, field);
+        match self.field_directions.get(&key) {
+            None => FieldDirection::Both,
+            Some(direction) => *direction,
+        }
+    }
+
+    pub fn get_unit(&self, function: &str, argument: &Argument) -> Option<Ident> {
+        if !self.settings.unit_types {
+            return None;
+        }
+        let numeric = match &argument.argument_type {
+            FundamentalType(name) => {
+                ["int", "unsigned int", "float", "double"].contains(&&name[..])
+            }
+            UserType(_) => false,
+        };
+        let pointer = describe_pointer(&argument.as_const, &argument.pointer);
+        if !numeric || !(pointer.is_empty() || pointer == "*mut") {
+            return None;
+        }
+        let key = format!("{}+{}", function, argument.name);
+        let unit = match self.units.get(&key)? {
+            Unit::Milliseconds => "Millis",
+            Unit::Samples => "PcmSamples",
+            Unit::Hertz => "Hertz",
+        };
+        Some(format_ident!("{}", unit))
+    }
+
+    pub fn is_boolean(&self, function: &str, argument: &Argument) -> bool {
+        let pointer = describe_pointer(&argument.as_const, &argument.pointer);
+        argument.argument_type == FundamentalType("int".into())
+            && (pointer.is_empty() || pointer == "*mut")
+            && self
+                .booleans
+                .contains(&format!("{}+{}", function, argument.name))
+    }
+
     pub fn get_modifier(&self, function: &str, argument: &str) -> Modifier {
         let key = format!("{}+{}", function, argument);
         match self.modifiers.get(&key) {
             None => Modifier::None,
+            Some(Modifier::OptOut) => Modifier::Out,
             Some(modifier) => modifier.clone(),
         }
     }
+
+    pub fn is_optional_output(&self, function: &str, argument: &str) -> bool {
+        let key = format!("{}+{}", function, argument);
+        self.modifiers.get(&key) == Some(&Modifier::OptOut)
+    }
 }
 
 impl Type {
@@ -968,24 +2947,75 @@ pub fn generate_lib_code(api: &Api) -> Result<TokenStream, Error> {
         }
     }
 
-    let types: Vec<TokenStream> = types
+    let outputs = if api.settings.named_outputs {
+        OutputStructures::collect(&types, api)?
+    } else {
+        OutputStructures::default()
+    };
+    let output_structures = &outputs.definitions;
+    let variadic_callbacks: Vec<TokenStream> = api
+        .callbacks
         .iter()
-        .map(|(key, methods)| generate_opaque_type(key, methods, api))
+        .filter_map(|callback| generate_variadic_callback(callback, api))
         .collect();
 
+    let types = types
+        .iter()
+        .map(|(key, methods)| generate_opaque_type(key, methods, api, &outputs))
+        .collect::<Result<Vec<TokenStream>, Error>>()?;
+
     let enumerations: Vec<TokenStream> =
         api.enumerations.iter().map(generate_enumeration).collect();
 
     let mut structures: Vec<TokenStream> = vec![];
     for structure in &api.structures {
-        structures.push(generate_structure(structure, api));
+        if structure.is_union {
+            continue;
+        }
+        structures.push(generate_structure(structure, api)?);
     }
 
+    let speaker_layout = generate_speaker_layout(api);
+
+    let flags_types: Vec<TokenStream> = FLAGS_TYPES
+        .iter()
+        .map(|(key, _)| generate_flags_type(api, key))
+        .collect();
+    let value_types: Vec<TokenStream> = VALUE_TYPES
+        .iter()
+        .map(|(key, name)| generate_value_type(api, key, name))
+        .collect();
+    let port_index = generate_port_index(api);
+
+    let units = if api.settings.unit_types {
+        quote! {
+            #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+            pub struct Millis<T = u32>(pub T);
+
+            #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+            pub struct PcmSamples<T = u32>(pub T);
+
+            #[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+            pub struct Hertz<T = f32>(pub T);
+        }
+    } else {
+        quote! {}
+    };
+
+    let into_string = match api.settings.string_conversion {
+        StringConversion::Strict => quote! {
+            $value.into_string().map_err(Error::String)
+        },
+        StringConversion::Lossy => quote! {
+            Ok::<String, Error>($value.to_string_lossy().into_owned())
+        },
+    };
+
     Ok(quote! {
-        #![allow(unused_unsafe)]
+        #![deny(unsafe_op_in_unsafe_fn)]
         use std::os::raw::{c_char};
-        use std::ffi::{c_void, CStr, CString, IntoStringError, NulError};
-        use std::fmt::{Display, Formatter};
+        use std::ffi::{c_void, CStr, CString};
+        use std::fmt::{Debug, Formatter};
         use std::mem::size_of;
         use std::ptr::{null, null_mut};
         use std::slice;
@@ -994,73 +3024,60 @@ pub fn generate_lib_code(api: &Api) -> Result<TokenStream, Error> {
         mod flags;
         #[cfg(feature = "flags")]
         pub use flags::*;
-
-        #[derive(Debug)]
-        pub enum Error {
-            Fmod {
-                function: String,
-                code: i32,
-                message: String,
-            },
-            EnumBindgen {
-                enumeration: String,
-                value: String
-            },
-            String(IntoStringError),
-            StringNul(NulError),
-            NotDspFft
+        #[macro_use]
+        mod error;
+        pub use error::*;
+
+        macro_rules! profile_call {
+            ($ function : expr , $ call : expr) => {{
+                #[cfg(feature = "profiling")]
+                let start = std::time::Instant::now();
+                let result = $call;
+                #[cfg(feature = "profiling")]
+                profiling::record($function, start.elapsed(), result);
+                result
+            }};
         }
 
-        impl Display for Error {
-            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-                match self {
-                    Error::Fmod {
-                        function,
-                        code,
-                        message,
-                    } => {
-                        write!(f, "{}: {} ({})", function, message, code)
-                    }
-                    Error::EnumBindgen { enumeration, value } => {
-                        write!(f, "FMOD returns unexpected value {} for {} enum", value, enumeration)
-                    }
-                    Error::String(_) => {
-                        write!(f, "invalid UTF-8 when converting C string")
-                    }
-                    Error::StringNul(_) => {
-                        write!(f, "nul byte was found in the middle, C strings can't contain it")
-                    }
-                    Error::NotDspFft => {
-                        write!(f, "trying get FFT from DSP which not FFT")
-                    }
+        #[cfg(feature = "profiling")]
+        pub mod profiling {
+            use crate::ffi;
+            use std::sync::atomic::{AtomicU64, Ordering};
+            use std::sync::RwLock;
+            use std::time::Duration;
+
+            pub type Sink = fn(function: &'static str, elapsed: Duration, result: ffi::FMOD_RESULT);
+
+            static SINK: RwLock<Option<Sink>> = RwLock::new(None);
+            static CALLS: AtomicU64 = AtomicU64::new(0);
+            static ERRORS: AtomicU64 = AtomicU64::new(0);
+
+            pub fn set_sink(sink: Option<Sink>) {
+                if let Ok(mut current) = SINK.write() {
+                    *current = sink;
                 }
             }
-        }
-
-        impl std::error::Error for Error {}
 
-        impl From<NulError> for Error {
-            fn from(error: NulError) -> Self {
-                Error::StringNul(error)
+            pub fn counters() -> (u64, u64) {
+                (CALLS.load(Ordering::Relaxed), ERRORS.load(Ordering::Relaxed))
             }
-        }
 
-        macro_rules! err_fmod {
-            ($ function : expr , $ code : expr) => {
-                Error::Fmod {
-                    function: $function.to_string(),
-                    code: $code,
-                    message: ffi::map_fmod_error($code).to_string(),
+            pub fn record(function: &'static str, elapsed: Duration, result: ffi::FMOD_RESULT) {
+                CALLS.fetch_add(1, Ordering::Relaxed);
+                if result != ffi::FMOD_OK {
+                    ERRORS.fetch_add(1, Ordering::Relaxed);
                 }
-            };
+                if let Ok(sink) = SINK.read() {
+                    if let Some(sink) = *sink {
+                        sink(function, elapsed, result);
+                    }
+                }
+            }
         }
 
-        macro_rules! err_enum {
-            ($ enumeration : expr , $ value : expr) => {
-                Error::EnumBindgen {
-                    enumeration: $enumeration.to_string(),
-                    value: $value.to_string(),
-                }
+        macro_rules! into_string {
+            ($ value : expr) => {
+                #into_string
             };
         }
 
@@ -1069,9 +3086,7 @@ pub fn generate_lib_code(api: &Api) -> Result<TokenStream, Error> {
                 if $ptr.is_null() {
                     Ok(String::new())
                 } else {
-                    CString::from(CStr::from_ptr($ptr))
-                        .into_string()
-                        .map_err(Error::String)
+                    into_string!(CString::from(unsafe { CStr::from_ptr($ptr) }))
                 }
             };
         }
@@ -1092,12 +3107,24 @@ pub fn generate_lib_code(api: &Api) -> Result<TokenStream, Error> {
             };
         }
 
+        /// Converts elements straight from FMOD memory into vector allocated once.
+        fn map_slice<T: Copy, R>(
+            items: &[T],
+            mut map: impl FnMut(T) -> Result<R, Error>,
+        ) -> Result<Vec<R>, Error> {
+            let mut values = Vec::with_capacity(items.len());
+            for item in items {
+                values.push(map(*item)?);
+            }
+            Ok(values)
+        }
+
         macro_rules! to_vec {
             ($ ptr : expr , $ length : expr, $ closure : expr) => {
-                slice::from_raw_parts($ptr, $length as usize).to_vec().into_iter().map($closure).collect::<Result<Vec<_>, Error>>()
+                map_slice(unsafe { slice::from_raw_parts($ptr, $length as usize) }, $closure)
             };
             ($ ptr : expr , $ length : expr) => {
-                slice::from_raw_parts($ptr, $length as usize).to_vec()
+                unsafe { slice::from_raw_parts($ptr, $length as usize) }.to_vec()
             };
         }
 
@@ -1118,10 +3145,84 @@ pub fn generate_lib_code(api: &Api) -> Result<TokenStream, Error> {
             }
         }
 
+        /// Handle which can be released, see [`Owned`].
+        pub trait Release: Copy {
+            fn release(&self) -> Result<(), Error>;
+        }
+
+        /// Handle released when dropped, release errors are ignored.
+        #[derive(Debug)]
+        pub struct Owned<T: Release>(T);
+
+        impl<T: Release> Owned<T> {
+            pub fn new(handle: T) -> Self {
+                Self(handle)
+            }
+
+            /// Returns handle without releasing it.
+            pub fn into_inner(self) -> T {
+                let handle = self.0;
+                std::mem::forget(self);
+                handle
+            }
+        }
+
+        impl<T: Release> std::ops::Deref for Owned<T> {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                &self.0
+            }
+        }
+
+        impl<T: Release> Drop for Owned<T> {
+            fn drop(&mut self) {
+                let _ = self.0.release();
+            }
+        }
+
         pub fn attr3d_array8(values: Vec<Attributes3d>) -> [Attributes3d; ffi::FMOD_MAX_LISTENERS as usize] {
             values.try_into().expect("slice with incorrect length")
         }
 
+        pub fn string_from_char_array(array: &[c_char]) -> Result<String, Error> {
+            let bytes: Vec<u8> = array.iter().take_while(|c| **c != 0).map(|c| *c as u8).collect();
+            into_string!(CString::new(bytes)?)
+        }
+
+        pub fn string_into_char_array<const N: usize>(value: &str) -> [c_char; N] {
+            let mut array = [0 as c_char; N];
+            let mut length = 0;
+            for character in value.chars() {
+                let size = character.len_utf8();
+                if length + size >= N {
+                    break;
+                }
+                length += size;
+            }
+            for (index, byte) in value.bytes().take(length).enumerate() {
+                array[index] = byte as c_char;
+            }
+            array
+        }
+
+        /// FMOD truncates strings silently, so call filling buffer is repeated
+        /// with doubled buffer until the string fits.
+        fn read_string_growing<F>(capacity: usize, mut fill: F) -> Result<String, Error>
+            where F: FnMut(&mut [u8]) -> Result<(), Error>
+        {
+            let mut buf = vec![0u8; capacity];
+            loop {
+                fill(&mut buf)?;
+                let length = buf.iter().position(|&byte| byte == 0).unwrap_or(buf.len());
+                if length + 1 < buf.len() || buf.len() >= 4096 {
+                    buf.truncate(length);
+                    return Ok(into_string!(unsafe { CString::from_vec_unchecked(buf) })?);
+                }
+                buf = vec![0u8; buf.len() * 2];
+            }
+        }
+
         pub fn vec_as_mut_ptr<T, O, F>(values: Vec<T>, map: F) -> *mut O
             where F: FnMut(T) -> O
         {
@@ -1138,9 +3239,37 @@ pub fn generate_lib_code(api: &Api) -> Result<TokenStream, Error> {
             value
         }
 
+        #[derive(Debug, Default)]
+        pub struct Guard {
+            strings: Vec<CString>,
+            buffers: Vec<Box<dyn std::any::Any>>,
+        }
+
+        impl Guard {
+            pub fn string(&mut self, value: &str) -> Result<*const c_char, Error> {
+                let value = CString::new(value)?;
+                let pointer = value.as_ptr();
+                self.strings.push(value);
+                Ok(pointer)
+            }
+
+            pub fn keep<T: 'static>(&mut self, mut values: Vec<T>) -> *mut T {
+                let pointer = values.as_mut_ptr();
+                self.buffers.push(Box::new(values));
+                pointer
+            }
+        }
+
+        #units
+        #(#flags_types)*
+        #(#value_types)*
+        #port_index
+        #speaker_layout
         #(#enumerations)*
         #(#structures)*
+        #(#output_structures)*
         #(#types)*
+        #(#variadic_callbacks)*
     })
 }
 
@@ -1148,3 +3277,278 @@ pub fn generate(api: &Api) -> Result<String, Error> {
     let code = generate_lib_code(api)?;
     rustfmt_wrapper::rustfmt(code).map_err(Error::from)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::OpaqueType;
+
+    fn format(name: &str, pointer: Option<Pointer>) -> String {
+        let c_type = FundamentalType(name.to_string());
+        format_rust_type(&c_type, &None, &pointer, &None, &Api::default())
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn maps_sized_types_to_primitives() {
+        let types = [
+            ("size_t", "usize"),
+            ("uintptr_t", "usize"),
+            ("intptr_t", "isize"),
+            ("ptrdiff_t", "isize"),
+            ("int8_t", "i8"),
+            ("uint8_t", "u8"),
+            ("int16_t", "i16"),
+            ("uint16_t", "u16"),
+            ("int32_t", "i32"),
+            ("uint32_t", "u32"),
+            ("int64_t", "i64"),
+            ("uint64_t", "u64"),
+        ];
+        for (c_type, rust_type) in types {
+            assert_eq!(format(c_type, None), rust_type);
+        }
+    }
+
+    #[test]
+    fn maps_double_values_and_buffers() {
+        assert_eq!(format("double", None), "f64");
+        let pointer = Some(Pointer::NormalPointer("*".into()));
+        assert_eq!(format("double", pointer), "Vec < f64 >");
+    }
+
+    #[test]
+    fn reports_unsupported_types() {
+        let c_type = UserType("FMOD_UNKNOWN".to_string());
+        let pointer = Some(Pointer::NormalPointer("*".into()));
+        let result = format_rust_type(&c_type, &None, &pointer, &None, &Api::default());
+        assert!(matches!(result, Err(Error::Unsupported(name)) if name == "*mut FMOD_UNKNOWN"));
+    }
+
+    #[test]
+    fn maps_sized_type_arrays() {
+        let c_type = FundamentalType("uint8_t".to_string());
+        let dimension = Some(quote! { 16 });
+        let rust_type =
+            format_rust_type(&c_type, &None, &None, &dimension, &Api::default()).unwrap();
+        assert_eq!(rust_type.to_string(), "[u8 ; 16 as usize]");
+    }
+
+    fn argument(name: &str, argument_type: &str, pointer: Option<Pointer>) -> Argument {
+        let argument_type = if argument_type.starts_with("FMOD_") {
+            UserType(argument_type.to_string())
+        } else {
+            FundamentalType(argument_type.to_string())
+        };
+        Argument {
+            as_const: None,
+            argument_type,
+            pointer,
+            name: name.to_string(),
+        }
+    }
+
+    fn function(name: &str, arguments: Vec<Argument>) -> Function {
+        Function {
+            return_type: UserType("FMOD_RESULT".into()),
+            name: name.to_string(),
+            arguments,
+            varargs: None,
+            role: FunctionRole::Method,
+            declaration: String::new(),
+        }
+    }
+
+    fn classify(api: &Api, mut functions: Vec<Function>) -> Vec<Function> {
+        for function in functions.iter_mut() {
+            function.role = api.classify_function(function);
+        }
+        functions
+    }
+
+    fn handle() -> Option<Pointer> {
+        Some(Pointer::NormalPointer("*".into()))
+    }
+
+    #[test]
+    fn generates_property_update_for_matching_pair() {
+        let mut api = Api::default();
+        api.modifiers
+            .insert("FMOD_Channel_GetVolume+volume".into(), Modifier::Out);
+        let functions = classify(
+            &api,
+            vec![
+                function(
+                    "FMOD_Channel_GetVolume",
+                    vec![
+                        argument("channel", "FMOD_CHANNEL", handle()),
+                        argument("volume", "float", handle()),
+                    ],
+                ),
+                function(
+                    "FMOD_Channel_SetVolume",
+                    vec![
+                        argument("channel", "FMOD_CHANNEL", handle()),
+                        argument("volume", "float", None),
+                    ],
+                ),
+            ],
+        );
+        let methods: Vec<&Function> = functions.iter().collect();
+        let pairs = detect_property_pairs(&methods, &api);
+        assert_eq!(pairs.len(), 1);
+        let update = generate_property_update("FMOD_CHANNEL", pairs[0].0, pairs[0].1, &api)
+            .unwrap()
+            .unwrap()
+            .to_string();
+        assert!(
+            update.contains("pub fn update_volume (& self , update : impl FnOnce (f32) -> f32)")
+        );
+    }
+
+    #[test]
+    fn skips_property_update_for_different_types() {
+        let mut api = Api::default();
+        api.modifiers
+            .insert("FMOD_Channel_GetPriority+priority".into(), Modifier::Out);
+        let functions = classify(
+            &api,
+            vec![
+                function(
+                    "FMOD_Channel_GetPriority",
+                    vec![
+                        argument("channel", "FMOD_CHANNEL", handle()),
+                        argument("priority", "int", handle()),
+                    ],
+                ),
+                function(
+                    "FMOD_Channel_SetPriority",
+                    vec![
+                        argument("channel", "FMOD_CHANNEL", handle()),
+                        argument("priority", "float", None),
+                    ],
+                ),
+            ],
+        );
+        let methods: Vec<&Function> = functions.iter().collect();
+        let pairs = detect_property_pairs(&methods, &api);
+        let update =
+            generate_property_update("FMOD_CHANNEL", pairs[0].0, pairs[0].1, &api).unwrap();
+        assert!(update.is_none());
+    }
+
+    #[test]
+    fn generates_release_and_owned_constructor() {
+        let mut api = Api::default();
+        api.opaque_types.push(OpaqueType {
+            name: "FMOD_SYSTEM".into(),
+        });
+        let double = Some(Pointer::DoublePointer("**".into()));
+        api.modifiers
+            .insert("FMOD_System_Create+system".into(), Modifier::Out);
+        let functions = classify(
+            &api,
+            vec![
+                function(
+                    "FMOD_System_Create",
+                    vec![argument("system", "FMOD_SYSTEM", double)],
+                ),
+                function(
+                    "FMOD_System_Release",
+                    vec![argument("system", "FMOD_SYSTEM", handle())],
+                ),
+            ],
+        );
+        assert_eq!(functions[0].role, FunctionRole::Constructor);
+        assert_eq!(functions[1].role, FunctionRole::Destructor);
+        let methods: Vec<&Function> = functions.iter().collect();
+        let release = generate_release_impl("FMOD_SYSTEM", &methods, &api).to_string();
+        assert!(release.contains("impl Release for System"));
+        let constructor = generate_owned_constructor("FMOD_SYSTEM", &functions[0], &methods, &api)
+            .unwrap()
+            .unwrap()
+            .to_string();
+        assert!(constructor.contains("pub fn create_owned () -> Result < Owned < Self > , Error >"));
+    }
+    #[test]
+    fn grows_string_buffer_on_truncation() {
+        let mut api = Api::default();
+        api.opaque_types.push(OpaqueType {
+            name: "FMOD_SOUND".into(),
+        });
+        api.modifiers
+            .insert("FMOD_Sound_GetName+name".into(), Modifier::Out);
+        let get_name = function(
+            "FMOD_Sound_GetName",
+            vec![
+                argument("sound", "FMOD_SOUND", handle()),
+                argument("name", "char", handle()),
+                argument("namelen", "int", None),
+            ],
+        );
+        let method = generate_method("FMOD_SOUND", &get_name, &api, &OutputStructures::default())
+            .unwrap()
+            .to_string();
+        assert!(method.contains("pub fn get_name (& self) -> Result < String , Error >"));
+        assert!(method.contains("name . len () as i32"));
+        assert!(method.contains("name = vec ! [0u8 ; name . len () * 2]"));
+    }
+
+    #[test]
+    fn maps_optional_int_boolean() {
+        let mut api = Api::default();
+        api.opaque_types.push(OpaqueType {
+            name: "FMOD_CHANNEL".into(),
+        });
+        api.modifiers
+            .insert("FMOD_Channel_SetMute+mute".into(), Modifier::Opt);
+        api.booleans.insert("FMOD_Channel_SetMute+mute".into());
+        let set_mute = function(
+            "FMOD_Channel_SetMute",
+            vec![
+                argument("channel", "FMOD_CHANNEL", handle()),
+                argument("mute", "int", None),
+            ],
+        );
+        let method = generate_method(
+            "FMOD_CHANNEL",
+            &set_mute,
+            &api,
+            &OutputStructures::default(),
+        )
+        .unwrap()
+        .to_string();
+        assert!(method.contains("mute : Option < bool >"));
+        assert!(method.contains("mute . map (| value | from_bool ! (value)) . unwrap_or (0)"));
+    }
+
+    #[test]
+    fn checks_released_handle_in_buffer_methods() {
+        let mut api = Api::default();
+        api.opaque_types.push(OpaqueType {
+            name: "FMOD_SOUND".into(),
+        });
+        let get_name = function(
+            "FMOD_Sound_GetName",
+            vec![
+                argument("sound", "FMOD_SOUND", handle()),
+                argument("name", "char", handle()),
+                argument("namelen", "int", None),
+                argument("retrieved", "int", handle()),
+            ],
+        );
+        let is_valid = function(
+            "FMOD_Sound_IsValid",
+            vec![argument("sound", "FMOD_SOUND", handle())],
+        );
+        api.functions
+            .push(("fmod.h".into(), vec![get_name.clone(), is_valid]));
+        let method = generate_string_into_method("FMOD_SOUND", &get_name, &api)
+            .unwrap()
+            .unwrap()
+            .to_string();
+        assert!(method.contains("pub fn get_name_into"));
+        assert!(method.contains("Error :: UseAfterRelease"));
+    }
+}
@@ -1,3 +1,11 @@
+pub mod cargo;
+pub mod coverage;
+pub mod errors;
+pub mod examples;
 pub mod ffi;
 pub mod flags;
+pub mod idents;
+pub mod integration;
+pub mod layout;
 pub mod lib;
+pub mod paths;
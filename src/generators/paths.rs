@@ -0,0 +1,101 @@
+use std::collections::{BTreeMap, HashSet};
+
+use quote::__private::{Literal, TokenStream};
+
+use crate::models::Error;
+use crate::parsers::guids::StudioPath;
+
+fn format_module_name(kind: &str) -> String {
+    match kind {
+        "bus" => "buses".to_string(),
+        "parameter" => "parameters".to_string(),
+        kind => format!("{}s", kind),
+    }
+}
+
+/// Constant name from path without kind prefix, e.g. `MUSIC_LEVEL_01` for `event:/Music/Level 01`.
+fn format_constant_name(path: &str) -> String {
+    let path = path.split_once(":/").map(|(_, path)| path).unwrap_or(path);
+    let mut name = String::new();
+    for character in path.chars() {
+        if character.is_ascii_alphanumeric() {
+            name.push(character.to_ascii_uppercase());
+        } else if !name.is_empty() && !name.ends_with('_') {
+            name.push('_');
+        }
+    }
+    let name = name.trim_end_matches('_').to_string();
+    match name.chars().next() {
+        None => "MASTER".to_string(),
+        Some(first) if first.is_ascii_digit() => format!("_{}", name),
+        Some(_) => name,
+    }
+}
+
+fn generate_constant(path: &StudioPath, name: &str) -> TokenStream {
+    let name = format_ident!("{}", name);
+    let value = &path.path;
+    let data_1 = Literal::u32_unsuffixed(path.data_1);
+    let data_2 = Literal::u16_unsuffixed(path.data_2);
+    let data_3 = Literal::u16_unsuffixed(path.data_3);
+    let data_4 = path.data_4.iter().map(|byte| Literal::u8_unsuffixed(*byte));
+    quote! {
+        #[doc = #value]
+        pub const #name: StudioPath = StudioPath {
+            path: #value,
+            id: Guid {
+                data_1: #data_1,
+                data_2: #data_2,
+                data_3: #data_3,
+                data_4: [#(#data_4),*],
+            },
+        };
+    }
+}
+
+/// Generates module of Studio project paths grouped by kind, so references to events,
+/// buses and VCAs are checked by compiler instead of failing on lookup at runtime.
+pub fn generate_paths(paths: &[StudioPath]) -> Result<String, Error> {
+    let mut kinds: BTreeMap<&str, Vec<&StudioPath>> = BTreeMap::new();
+    for path in paths {
+        kinds.entry(&path.kind).or_default().push(path);
+    }
+    let mut modules = vec![];
+    for (kind, mut paths) in kinds {
+        paths.sort_by(|a, b| a.path.cmp(&b.path));
+        let mut names = HashSet::new();
+        let mut constants = vec![];
+        for path in paths {
+            let base = format_constant_name(&path.path);
+            let mut name = base.clone();
+            let mut index = 2;
+            while !names.insert(name.clone()) {
+                name = format!("{}_{}", base, index);
+                index += 1;
+            }
+            constants.push(generate_constant(path, &name));
+        }
+        let module = format_ident!("{}", format_module_name(kind));
+        modules.push(quote! {
+            pub mod #module {
+                use super::*;
+
+                #(#constants)*
+            }
+        });
+    }
+    let code = quote! {
+        #![allow(dead_code)]
+        use libfmod::Guid;
+
+        /// Path of FMOD Studio project item with its identifier.
+        #[derive(Debug, Clone, Copy)]
+        pub struct StudioPath {
+            pub path: &'static str,
+            pub id: Guid,
+        }
+
+        #(#modules)*
+    };
+    Ok(rustfmt_wrapper::rustfmt(code)?)
+}
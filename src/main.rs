@@ -9,43 +9,54 @@ extern crate proc_macro;
 #[macro_use]
 extern crate pest_derive;
 
-use crate::generators::{ffi, flags, lib};
-use crate::models::{Api, Error};
+use crate::generators::{
+    cargo, coverage, errors, examples, ffi, flags, integration, layout, lib, paths,
+};
+use crate::models::{Api, Error, Settings};
 use crate::parsers::{
-    fmod, fmod_codec, fmod_common, fmod_docs, fmod_dsp, fmod_dsp_effects, fmod_errors, fmod_output,
-    fmod_studio, fmod_studio_common,
+    defines, fmod, fmod_codec, fmod_common, fmod_docs, fmod_dsp, fmod_dsp_effects, fmod_errors,
+    fmod_output, fmod_studio, fmod_studio_common, guids, modifiers,
 };
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::{env, fs};
 
+mod analysis;
 mod generators;
 mod models;
 mod parsers;
 mod patching;
+mod regions;
 mod repr;
+mod sdk;
+mod validation;
 
-fn generate_lib_fmod(source: &str, destination: &str) -> Result<(), Error> {
-    let source = Path::new(source);
-    if !source.join("api/studio/inc/fmod_studio.h").exists() {
-        return Err(Error::Io(
-            "FMOD headers not found, make sure input is FMOD SDK \
-            directory with api, doc, plugin folders"
-                .to_string(),
-        ));
+fn generate_lib_fmod(
+    source: Option<&str>,
+    destination: &str,
+    settings: Settings,
+) -> Result<(), Error> {
+    let source = sdk::locate(source)?;
+    println!("source {} {}", source.display(), destination);
+    let mut api = Api {
+        settings,
+        ..Api::default()
+    };
+    if !api.settings.core_only {
+        let data = fs::read_to_string(source.join("api/studio/inc/fmod_studio.h"))?;
+        let header = fmod_studio::parse(&data)?;
+        let link = "fmodstudio".into();
+        api.functions.push((link, header.functions.clone()));
+        let data = fs::read_to_string(source.join("api/studio/inc/fmod_studio_common.h"))?;
+        let header = fmod_studio_common::parse(&data)?;
+        api.defines.extend(defines::parse(&data));
+        api.opaque_types.extend(header.opaque_types);
+        api.constants.extend(header.constants);
+        api.enumerations.extend(header.enumerations);
+        api.callbacks.extend(header.callbacks);
+        api.flags.extend(header.flags);
+        api.structures.extend(header.structures);
     }
-    let mut api = Api::default();
-    let data = fs::read_to_string(source.join("api/studio/inc/fmod_studio.h"))?;
-    let header = fmod_studio::parse(&data)?;
-    let link = "fmodstudio".into();
-    api.functions.push((link, header.functions.clone()));
-    let data = fs::read_to_string(source.join("api/studio/inc/fmod_studio_common.h"))?;
-    let header = fmod_studio_common::parse(&data)?;
-    api.opaque_types.extend(header.opaque_types);
-    api.constants.extend(header.constants);
-    api.enumerations.extend(header.enumerations);
-    api.callbacks.extend(header.callbacks);
-    api.flags.extend(header.flags);
-    api.structures.extend(header.structures);
 
     let data = fs::read_to_string(source.join("api/core/inc/fmod.h"))?;
     let header = fmod::parse(&data)?;
@@ -54,6 +65,7 @@ fn generate_lib_fmod(source: &str, destination: &str) -> Result<(), Error> {
 
     let data = fs::read_to_string(source.join("api/core/inc/fmod_common.h"))?;
     let header = fmod_common::parse(&data)?;
+    api.defines.extend(defines::parse(&data));
     api.opaque_types.extend(header.opaque_types);
     api.type_aliases.extend(header.type_aliases);
     api.constants.extend(header.constants);
@@ -65,6 +77,7 @@ fn generate_lib_fmod(source: &str, destination: &str) -> Result<(), Error> {
 
     let data = fs::read_to_string(source.join("api/core/inc/fmod_codec.h"))?;
     let header = fmod_codec::parse(&data)?;
+    api.defines.extend(defines::parse(&data));
     api.opaque_types.extend(header.opaque_types);
     api.constants.extend(header.constants);
     api.callbacks.extend(header.callbacks);
@@ -73,6 +86,7 @@ fn generate_lib_fmod(source: &str, destination: &str) -> Result<(), Error> {
 
     let data = fs::read_to_string(source.join("api/core/inc/fmod_output.h"))?;
     let header = fmod_output::parse(&data)?;
+    api.defines.extend(defines::parse(&data));
     api.opaque_types.extend(header.opaque_types);
     api.constants.extend(header.constants);
     api.callbacks.extend(header.callbacks);
@@ -81,6 +95,7 @@ fn generate_lib_fmod(source: &str, destination: &str) -> Result<(), Error> {
 
     let data = fs::read_to_string(source.join("api/core/inc/fmod_dsp.h"))?;
     let header = fmod_dsp::parse(&data)?;
+    api.defines.extend(defines::parse(&data));
     api.opaque_types.extend(header.opaque_types);
     api.constants.extend(header.constants);
     api.enumerations.extend(header.enumerations);
@@ -90,6 +105,7 @@ fn generate_lib_fmod(source: &str, destination: &str) -> Result<(), Error> {
 
     let data = fs::read_to_string(source.join("api/core/inc/fmod_dsp_effects.h"))?;
     let header = fmod_dsp_effects::parse(&data)?;
+    api.defines.extend(defines::parse(&data));
     api.constants.extend(header.constants);
     api.enumerations.extend(header.enumerations);
     api.structures.extend(header.structures);
@@ -98,7 +114,7 @@ fn generate_lib_fmod(source: &str, destination: &str) -> Result<(), Error> {
     let header = fmod_errors::parse(&data)?;
     api.errors = header.mapping.clone();
 
-    api.modifiers = fmod_docs::parse_parameter_modifiers(&[
+    let manual = [
         source.join("doc/FMOD API User Manual/core-api-system.html"),
         source.join("doc/FMOD API User Manual/core-api-soundgroup.html"),
         source.join("doc/FMOD API User Manual/core-api-sound.html"),
@@ -121,9 +137,20 @@ fn generate_lib_fmod(source: &str, destination: &str) -> Result<(), Error> {
         source.join("doc/FMOD API User Manual/studio-api-eventinstance.html"),
         source.join("doc/FMOD API User Manual/studio-api-system.html"),
         source.join("doc/FMOD API User Manual/studio-api-vca.html"),
-    ])?;
+    ];
+    let manual: Vec<PathBuf> = manual
+        .into_iter()
+        .filter(|page| !api.settings.core_only || !page.to_string_lossy().contains("studio-api"))
+        .collect();
+    api.modifiers = fmod_docs::parse_parameter_modifiers(&manual)?;
+    let units = fmod_docs::parse_parameter_units(&manual)?;
+    api.units = fmod_docs::select_units(&units);
+    api.booleans = fmod_docs::select_booleans(&units);
 
     println!("FMOD API");
+    if let Some(version) = api.version() {
+        println!("Version: {}", version);
+    }
     println!("Opaque Types: {}", api.opaque_types.len());
     println!("Type Aliases: {}", api.type_aliases.len());
     println!(
@@ -162,41 +189,188 @@ fn generate_lib_fmod(source: &str, destination: &str) -> Result<(), Error> {
             .count()
     );
     println!("Parameter Modifiers: {}", api.modifiers.len());
+    println!("Parameter Units: {}", api.units.len());
+    println!("Boolean Parameters: {}", api.booleans.len());
     println!("Errors: {}", api.errors.errors.len());
 
     api.patch_all();
 
+    if let Some(path) = api.settings.import_modifiers.clone() {
+        let curated = modifiers::import(&fs::read_to_string(path)?)?;
+        api.apply_curated_modifiers(curated);
+    }
+    api.validate()?;
+    api.classify_functions();
+    let collisions = api.assign_method_names();
+    if !collisions.is_empty() {
+        println!("Methods keeping get_ prefix because of collision:");
+        for function in collisions {
+            println!("  {}", function);
+        }
+    }
+    api.repatch_code();
+    api.pair_array_lengths();
+    api.sort_declarations();
+
+    let mut outputs = vec![];
+    if let Some(path) = api.settings.layouts.clone() {
+        let table = match fs::read_to_string(&path) {
+            Ok(table) => Some(table),
+            // capturing compiles probe and writes table, dry run only reports it
+            Err(_) if api.settings.dry_run => {
+                println!("Structure layouts would be captured to {}", path);
+                None
+            }
+            Err(_) => {
+                let table = layout::capture_layouts(&api, &source)?;
+                println!("Structure layouts captured to {}", path);
+                fs::write(&path, &table)?;
+                Some(table)
+            }
+        };
+        if let Some(table) = table {
+            api.layouts = Some(layout::parse_layouts(&table)?);
+        }
+    }
+    if let Some(path) = &api.settings.export_modifiers {
+        outputs.push((PathBuf::from(path), modifiers::export(&api.modifiers)?));
+    }
+
     let destination = Path::new(destination);
-    if !destination.join("src/ffi.rs").exists() {
+    if !api.settings.dry_run && !destination.join("src/ffi.rs").exists() {
         return Err(Error::Io(
             "src not found, make sure output is libfmod project directory".to_string(),
         ));
     }
-    let code = ffi::generate(&api)?;
-    fs::write(destination.join("src/ffi.rs"), code)?;
-    let code = lib::generate(&api)?;
-    fs::write(destination.join("src/lib.rs"), code)?;
-    let code = flags::generate_to_file(&api)?;
-    fs::write(destination.join("src/flags.rs"), code)?;
+    outputs.push((destination.join("src/ffi.rs"), ffi::generate(&api)?));
+    outputs.push((destination.join("src/lib.rs"), lib::generate(&api)?));
+    outputs.push((destination.join("src/error.rs"), errors::generate(&api)?));
+    outputs.push((
+        destination.join("src/flags.rs"),
+        flags::generate_to_file(&api)?,
+    ));
+    for (path, code) in examples::generate_examples(&api)? {
+        outputs.push((destination.join(path), code));
+    }
+    for (path, code) in integration::generate_integration_tests(&api)? {
+        outputs.push((destination.join(path), code));
+    }
+    if api.settings.bevy && !api.settings.core_only {
+        let code = examples::generate_bevy_example()?;
+        outputs.push((destination.join("examples/bevy.rs"), code));
+    }
+    outputs.push((
+        destination.join("COVERAGE.md"),
+        coverage::generate_coverage(&api),
+    ));
+    let manifest = destination.join("Cargo.toml");
+    if let Ok(existing) = fs::read_to_string(&manifest) {
+        let features = cargo::collect_features(outputs.iter().map(|(_, code)| code.as_str()));
+        outputs.push((manifest, cargo::update_manifest(&existing, &features)?));
+    }
+
+    let outputs: Vec<(PathBuf, String)> = outputs
+        .into_iter()
+        .map(|(path, code)| match fs::read_to_string(&path) {
+            Ok(existing) => {
+                let code = regions::preserve_regions(&existing, code);
+                (path, code)
+            }
+            Err(_) => (path, code),
+        })
+        .collect();
+
+    if api.settings.dry_run {
+        println!("Dry run, nothing is written:");
+        for (path, code) in &outputs {
+            println!("  {}", describe_change(path, code));
+        }
+        return Ok(());
+    }
+    for (path, code) in outputs {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, code)?;
+    }
+    if api.settings.check_examples {
+        examples::check_examples(destination)?;
+    }
+
+    Ok(())
+}
 
+fn generate_studio_paths(source: &str, destination: &str) -> Result<(), Error> {
+    let studio_paths = guids::parse(&fs::read_to_string(source)?)?;
+    println!("Studio Paths: {}", studio_paths.len());
+    fs::write(destination, paths::generate_paths(&studio_paths)?)?;
     Ok(())
 }
 
-const FMOD_SDK_PATH: &str = "C:\\Program Files (x86)\\FMOD SoundSystem\\FMOD Studio API Windows";
+fn describe_change(path: &Path, code: &str) -> String {
+    let existing = match fs::read_to_string(path) {
+        Ok(existing) => existing,
+        Err(_) => return format!("{} new ({} lines)", path.display(), code.lines().count()),
+    };
+    if existing == code {
+        return format!("{} unchanged", path.display());
+    }
+    let mut lines: HashMap<&str, i32> = HashMap::new();
+    for line in existing.lines() {
+        *lines.entry(line).or_default() += 1;
+    }
+    for line in code.lines() {
+        *lines.entry(line).or_default() -= 1;
+    }
+    let removed: i32 = lines.values().filter(|count| **count > 0).sum();
+    let added: i32 = -lines.values().filter(|count| **count < 0).sum::<i32>();
+    format!(
+        "{} modified (+{} -{} lines)",
+        path.display(),
+        added,
+        removed
+    )
+}
+
 const OUTPUT_DIR: &str = "../libfmod";
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    let source = match args.get(1) {
-        None => FMOD_SDK_PATH,
-        Some(source) => source,
+    let (flags, args): (Vec<&str>, Vec<&str>) = args
+        .iter()
+        .map(String::as_str)
+        .partition(|arg| arg.starts_with("--"));
+    if args.get(1) == Some(&"paths") {
+        match (args.get(2), args.get(3)) {
+            (Some(source), Some(destination)) => {
+                if let Err(error) = generate_studio_paths(source, destination) {
+                    println!("Unable to generate Studio paths, {:?}", error);
+                }
+            }
+            _ => println!("usage: paths [GUIDs.txt] [output .rs file]"),
+        }
+        return;
+    }
+    let settings = match Settings::from_flags(&flags) {
+        Ok(settings) => settings,
+        Err(error) => {
+            println!("Unable to generate libfmod, {:?}", error);
+            return;
+        }
     };
+    let source = args.get(1).copied();
     let destination = match args.get(2) {
         None => OUTPUT_DIR,
-        Some(destination) => destination,
+        Some(&destination) => destination,
     };
-    println!("source {} {}", source, destination);
-    if let Err(error) = generate_lib_fmod(&source, &destination) {
-        println!("Unable to generate libfmod, {:?}", error);
+    match generate_lib_fmod(source, destination, settings) {
+        Err(Error::Validation(violations)) => {
+            println!("Unable to generate libfmod, API is inconsistent:");
+            for violation in violations {
+                println!("  {}", violation);
+            }
+        }
+        Err(error) => println!("Unable to generate libfmod, {:?}", error),
+        Ok(_) => {}
     }
 }
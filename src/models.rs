@@ -1,5 +1,5 @@
 use quote::__private::TokenStream;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 use serde::{Deserialize, Serialize};
 
@@ -13,6 +13,8 @@ pub enum Error {
     ParseFloat(String),
     LexError(String),
     Io(String),
+    Validation(Vec<String>),
+    Unsupported(String),
 }
 
 impl From<serde_json::Error> for Error {
@@ -47,11 +49,32 @@ pub struct Argument {
     pub name: String,
 }
 
+/// Role of function in its class, drives generation of idiomatic patterns.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+pub enum FunctionRole {
+    Constructor,
+    Destructor,
+    Getter,
+    Setter,
+    Count,
+    IndexedGetter,
+    List,
+    CallbackSetter,
+    #[default]
+    Method,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Function {
     pub return_type: Type,
     pub name: String,
     pub arguments: Vec<Argument>,
+    pub varargs: Option<String>,
+    #[serde(default)]
+    pub role: FunctionRole,
+    /// Original C prototype.
+    #[serde(default)]
+    pub declaration: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -63,6 +86,8 @@ pub struct OpaqueType {
 pub struct Constant {
     pub name: String,
     pub value: String,
+    #[serde(default)]
+    pub declaration: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -109,6 +134,118 @@ pub struct Structure {
     pub name: String,
     pub fields: Vec<Field>,
     pub union: Option<Union>,
+    #[serde(default)]
+    pub is_union: bool,
+    /// Original C declaration, empty for nested structures lowered from it.
+    #[serde(default)]
+    pub declaration: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum Member {
+    Field(Field),
+    Union {
+        union_keyword: String,
+        fields: Vec<Member>,
+        name: Option<String>,
+    },
+    Nested {
+        nested_keyword: String,
+        fields: Vec<Member>,
+        name: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StructureDeclaration {
+    pub name: String,
+    pub fields: Vec<Member>,
+    #[serde(default)]
+    pub declaration: String,
+}
+
+impl StructureDeclaration {
+    /// Nested anonymous unions and structures become separate named types,
+    /// except single trailing union which is kept as structure union.
+    pub fn into_structures(self) -> Vec<Structure> {
+        let mut structures = vec![];
+        let mut structure = lower_members(self.name, self.fields, false, &mut structures);
+        structure.declaration = self.declaration;
+        structures.insert(0, structure);
+        structures
+    }
+}
+
+fn lower_members(
+    name: String,
+    mut members: Vec<Member>,
+    is_union: bool,
+    structures: &mut Vec<Structure>,
+) -> Structure {
+    let nested = members
+        .iter()
+        .filter(|member| !matches!(member, Member::Field(_)))
+        .count();
+    let mut union = None;
+    if nested == 1 {
+        if let Some(Member::Union {
+            fields, name: None, ..
+        }) = members.last()
+        {
+            let fields: Option<Vec<Field>> = fields
+                .iter()
+                .map(|member| match member {
+                    Member::Field(field) => Some(field.clone()),
+                    _ => None,
+                })
+                .collect();
+            if let Some(fields) = fields {
+                union = Some(Union { fields });
+                members.pop();
+            }
+        }
+    }
+    let mut fields = vec![];
+    let mut unions = 0;
+    let mut structs = 0;
+    for member in members {
+        let (suffix, counter, member_name, member_fields, member_is_union) = match member {
+            Member::Field(field) => {
+                fields.push(field);
+                continue;
+            }
+            Member::Union { fields, name, .. } => ("union", &mut unions, name, fields, true),
+            Member::Nested { fields, name, .. } => ("struct", &mut structs, name, fields, false),
+        };
+        *counter += 1;
+        let suffix = match *counter {
+            1 => suffix.to_string(),
+            index => format!("{}{}", suffix, index),
+        };
+        let type_name = format!("{}_{}", name, suffix.to_uppercase());
+        let structure = lower_members(
+            type_name.clone(),
+            member_fields,
+            member_is_union,
+            structures,
+        );
+        structures.push(structure);
+        fields.push(Field {
+            as_const: None,
+            as_array: None,
+            field_type: Type::UserType(type_name),
+            pointer: None,
+            name: member_name.unwrap_or(suffix),
+        });
+    }
+    Structure {
+        name,
+        fields,
+        union,
+        is_union,
+        declaration: String::new(),
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -148,6 +285,146 @@ pub enum Modifier {
     None,
     Out,
     Opt,
+    /// Output which can be skipped by passing null.
+    OptOut,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldDirection {
+    Both,
+    /// Written by FMOD only, conversion to FFI structure leaves it zeroed.
+    Output,
+}
+
+/// Role of field derived from the rest of structure.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldRole {
+    /// Size of structure in bytes, FMOD uses it to check ABI version.
+    SizeOfSelf,
+    /// Number of elements in named sibling field.
+    CountOf(String),
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum Unit {
+    Milliseconds,
+    Samples,
+    Hertz,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ErrorLogging {
+    #[default]
+    None,
+    Log,
+    Tracing,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum StringConversion {
+    #[default]
+    Strict,
+    Lossy,
+}
+
+/// Naming of generated methods, FMOD mirrors function names, idiomatic strips `get_` prefix.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum MethodNaming {
+    #[default]
+    Fmod,
+    Idiomatic,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    pub named_outputs: bool,
+    pub error_logging: ErrorLogging,
+    pub bevy: bool,
+    pub string_conversion: StringConversion,
+    pub unit_types: bool,
+    pub import_modifiers: Option<String>,
+    pub export_modifiers: Option<String>,
+    pub dry_run: bool,
+    pub raw_identifiers: bool,
+    pub core_only: bool,
+    pub method_naming: MethodNaming,
+    pub list_iterators: bool,
+    pub static_errors: bool,
+    pub layouts: Option<String>,
+    pub check_examples: bool,
+}
+
+/// Command line options of generator, values are given after `=`.
+const OPTIONS: &[&str] = &[
+    "--named-outputs",
+    "--log-errors",
+    "--trace-errors",
+    "--bevy",
+    "--lossy-strings",
+    "--unit-types",
+    "--dry-run",
+    "--raw-identifiers",
+    "--core-only",
+    "--idiomatic-names",
+    "--list-iterators",
+    "--static-errors",
+    "--check-examples",
+];
+const VALUE_OPTIONS: &[&str] = &["--import-modifiers=", "--export-modifiers=", "--layouts="];
+
+impl Settings {
+    pub fn from_flags(flags: &[&str]) -> Result<Self, Error> {
+        for flag in flags {
+            let known = OPTIONS.contains(flag)
+                || VALUE_OPTIONS.iter().any(|option| flag.starts_with(option));
+            if !known {
+                return Err(Error::Unsupported(format!("unknown option {}", flag)));
+            }
+        }
+        if flags.contains(&"--trace-errors") && flags.contains(&"--log-errors") {
+            return Err(Error::Unsupported(
+                "--log-errors and --trace-errors can't be combined".to_string(),
+            ));
+        }
+        let error_logging = if flags.contains(&"--trace-errors") {
+            ErrorLogging::Tracing
+        } else if flags.contains(&"--log-errors") {
+            ErrorLogging::Log
+        } else {
+            ErrorLogging::None
+        };
+        let value = |name: &str| {
+            flags
+                .iter()
+                .find_map(|flag| flag.strip_prefix(name))
+                .map(ToString::to_string)
+        };
+        Ok(Settings {
+            named_outputs: flags.contains(&"--named-outputs"),
+            error_logging,
+            bevy: flags.contains(&"--bevy"),
+            unit_types: flags.contains(&"--unit-types"),
+            string_conversion: if flags.contains(&"--lossy-strings") {
+                StringConversion::Lossy
+            } else {
+                StringConversion::Strict
+            },
+            import_modifiers: value("--import-modifiers="),
+            export_modifiers: value("--export-modifiers="),
+            dry_run: flags.contains(&"--dry-run"),
+            raw_identifiers: flags.contains(&"--raw-identifiers"),
+            core_only: flags.contains(&"--core-only"),
+            list_iterators: flags.contains(&"--list-iterators"),
+            static_errors: flags.contains(&"--static-errors"),
+            layouts: value("--layouts="),
+            check_examples: flags.contains(&"--check-examples"),
+            method_naming: if flags.contains(&"--idiomatic-names") {
+                MethodNaming::Idiomatic
+            } else {
+                MethodNaming::Fmod
+            },
+        })
+    }
 }
 
 #[derive(Debug, Default)]
@@ -162,8 +439,148 @@ pub struct Api {
     pub functions: Vec<(String, Vec<Function>)>,
     pub presets: Vec<Preset>,
     pub errors: ErrorStringMapping,
+    pub defines: BTreeMap<String, u64>,
     pub modifiers: HashMap<String, Modifier>,
+    pub units: HashMap<String, Unit>,
+    pub booleans: HashSet<String>,
+    pub field_directions: HashMap<String, FieldDirection>,
+    pub field_roles: HashMap<String, FieldRole>,
+    pub array_lengths: HashMap<String, String>,
+    pub method_names: HashMap<String, String>,
     pub structure_patches: HashMap<String, TokenStream>,
     pub structure_derives: HashMap<String, TokenStream>,
     pub function_patches: HashMap<String, TokenStream>,
+    pub opaque_type_patches: HashMap<String, TokenStream>,
+    pub layouts: Option<LayoutTable>,
+    pub settings: Settings,
+}
+
+/// Structure sizes captured from C compiler for target pointer width in bits.
+#[derive(Debug, Clone)]
+pub struct LayoutTable {
+    pub pointer_width: usize,
+    pub sizes: BTreeMap<String, usize>,
+}
+
+/// Version of FMOD SDK encoded by `FMOD_VERSION` as `0xaaaabbcc`,
+/// product, major and minor numbers are printed in hex like SDK does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SdkVersion {
+    pub product: u64,
+    pub major: u64,
+    pub minor: u64,
+}
+
+impl SdkVersion {
+    pub fn from_define(value: u64) -> Self {
+        Self {
+            product: value >> 16,
+            major: (value >> 8) & 0xff,
+            minor: value & 0xff,
+        }
+    }
+}
+
+impl std::fmt::Display for SdkVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:x}.{:02x}.{:02x}",
+            self.product, self.major, self.minor
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str) -> Member {
+        Member::Field(Field {
+            as_const: None,
+            as_array: None,
+            field_type: Type::FundamentalType("int".into()),
+            pointer: None,
+            name: name.to_string(),
+        })
+    }
+
+    fn union(fields: Vec<Member>) -> Member {
+        Member::Union {
+            union_keyword: "union".into(),
+            fields,
+            name: None,
+        }
+    }
+
+    fn declaration(fields: Vec<Member>) -> StructureDeclaration {
+        StructureDeclaration {
+            name: "FMOD_TEST".into(),
+            fields,
+            declaration: "typedef struct FMOD_TEST { ... } FMOD_TEST;".into(),
+        }
+    }
+
+    #[test]
+    fn lowers_two_unions_to_numbered_types() {
+        let structures = declaration(vec![
+            field("kind"),
+            union(vec![field("a"), field("b")]),
+            union(vec![field("c"), field("d")]),
+        ])
+        .into_structures();
+        let names: Vec<&str> = structures.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["FMOD_TEST", "FMOD_TEST_UNION", "FMOD_TEST_UNION2"]);
+        let parent = &structures[0];
+        assert!(parent.union.is_none());
+        let fields: Vec<(&str, &Type)> = parent
+            .fields
+            .iter()
+            .map(|field| (field.name.as_str(), &field.field_type))
+            .collect();
+        assert_eq!(
+            fields,
+            [
+                ("kind", &Type::FundamentalType("int".into())),
+                ("union", &Type::UserType("FMOD_TEST_UNION".into())),
+                ("union2", &Type::UserType("FMOD_TEST_UNION2".into())),
+            ]
+        );
+        assert!(structures[1].is_union && structures[2].is_union);
+    }
+
+    #[test]
+    fn lowers_structure_nested_in_union() {
+        let nested = Member::Nested {
+            nested_keyword: "struct".into(),
+            fields: vec![field("x"), field("y")],
+            name: Some("point".into()),
+        };
+        let structures =
+            declaration(vec![field("kind"), union(vec![field("value"), nested])]).into_structures();
+        let names: Vec<&str> = structures.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["FMOD_TEST", "FMOD_TEST_UNION_STRUCT", "FMOD_TEST_UNION"]
+        );
+        let union = &structures[2];
+        assert!(union.is_union);
+        assert_eq!(union.fields[1].name, "point");
+        assert_eq!(
+            union.fields[1].field_type,
+            Type::UserType("FMOD_TEST_UNION_STRUCT".into())
+        );
+        assert!(!structures[1].is_union);
+        assert_eq!(structures[1].fields.len(), 2);
+    }
+
+    #[test]
+    fn keeps_declaration_only_on_declared_structure() {
+        let structures =
+            declaration(vec![union(vec![field("a")]), union(vec![field("b")])]).into_structures();
+        assert!(!structures[0].declaration.is_empty());
+        assert!(structures[1..]
+            .iter()
+            .all(|structure| structure.declaration.is_empty()));
+    }
 }
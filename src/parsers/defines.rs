@@ -0,0 +1,24 @@
+use std::collections::BTreeMap;
+
+use regex::Regex;
+
+/// Numeric literals of `#define` directives, extracted without grammar
+/// to cross-check values produced by header parsers.
+pub fn parse(source: &str) -> BTreeMap<String, u64> {
+    let pattern =
+        Regex::new("(?m)^\\s*#define\\s+(\\w+)\\s+\\(?(0x[0-9A-Fa-f]+|\\d+)\\)?\\s*(?:$|/)")
+            .unwrap();
+    let mut defines = BTreeMap::new();
+    for captures in pattern.captures_iter(source) {
+        let name = captures.get(1).unwrap().as_str();
+        let literal = captures.get(2).unwrap().as_str();
+        let value = match literal.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => literal.parse(),
+        };
+        if let Ok(value) = value {
+            defines.insert(name.to_string(), value);
+        }
+    }
+    defines
+}
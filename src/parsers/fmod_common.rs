@@ -1,5 +1,6 @@
 use crate::models::{
-    Callback, Constant, Enumeration, Error, Flags, OpaqueType, Preset, Structure, TypeAlias,
+    Callback, Constant, Enumeration, Error, Flags, OpaqueType, Preset, Structure,
+    StructureDeclaration, TypeAlias,
 };
 use crate::repr::JsonConverter;
 use pest::{error, Parser};
@@ -42,15 +43,15 @@ pub fn parse(source: &str) -> Result<Header, Error> {
             Rule::Flags => header.flags.push(converter.convert(declaration)?),
             Rule::Enumeration => header.enumerations.push(converter.convert(declaration)?),
             Rule::Structure => {
-                let structure: Structure = converter.convert(declaration)?;
+                let declaration: StructureDeclaration = converter.convert(declaration)?;
                 if let Some(index) = header
                     .opaque_types
                     .iter()
-                    .position(|opaque_type| opaque_type.name == structure.name)
+                    .position(|opaque_type| opaque_type.name == declaration.name)
                 {
                     header.opaque_types.remove(index);
                 }
-                header.structures.push(structure);
+                header.structures.extend(declaration.into_structures());
             }
             Rule::Callback => header.callbacks.push(converter.convert(declaration)?),
             Rule::TypeAlias => header.type_aliases.push(converter.convert(declaration)?),
@@ -67,3 +68,80 @@ impl From<error::Error<Rule>> for Error {
         Self::Pest(error.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Api, SdkVersion};
+    use crate::parsers::defines;
+
+    const FMOD_2_03: &str = include_str!("../../fixtures/fmod-2.03/fmod_common.h");
+
+    #[test]
+    fn parses_version_and_build_number_as_constants() {
+        let header = parse(FMOD_2_03).unwrap();
+        let constants: Vec<&str> = header
+            .constants
+            .iter()
+            .map(|constant| constant.name.as_str())
+            .collect();
+        assert!(constants.contains(&"FMOD_VERSION"));
+        assert!(constants.contains(&"FMOD_BUILDNUMBER"));
+        assert!(header
+            .type_aliases
+            .iter()
+            .any(|alias| alias.name == "FMOD_PORT_INDEX"));
+        let version = defines::parse(FMOD_2_03)["FMOD_VERSION"];
+        assert_eq!(SdkVersion::from_define(version).to_string(), "2.03.09");
+    }
+
+    #[test]
+    fn parses_new_system_callbacks() {
+        let header = parse(FMOD_2_03).unwrap();
+        let callbacks = header
+            .flags
+            .iter()
+            .find(|flags| flags.name == "FMOD_SYSTEM_CALLBACK_TYPE")
+            .unwrap();
+        let flag = callbacks
+            .flags
+            .iter()
+            .find(|flag| flag.name == "FMOD_SYSTEM_CALLBACK_OUTPUTUNDERRUN")
+            .unwrap();
+        assert_eq!(flag.value, "0x00004000");
+        assert_eq!(callbacks.flags.len(), 17);
+    }
+
+    #[test]
+    fn parses_new_output_type() {
+        let header = parse(FMOD_2_03).unwrap();
+        let output = header
+            .enumerations
+            .iter()
+            .find(|enumeration| enumeration.name == "FMOD_OUTPUTTYPE")
+            .unwrap();
+        assert!(output
+            .enumerators
+            .iter()
+            .any(|enumerator| enumerator.name == "FMOD_OUTPUTTYPE_OHAUDIO"));
+        assert_eq!(Api::patch_variant_name("Ohaudio"), "OhAudio");
+    }
+
+    #[test]
+    fn parses_advanced_settings() {
+        let header = parse(FMOD_2_03).unwrap();
+        let settings = header
+            .structures
+            .iter()
+            .find(|structure| structure.name == "FMOD_ADVANCEDSETTINGS")
+            .unwrap();
+        let names: Vec<&str> = settings
+            .fields
+            .iter()
+            .map(|field| field.name.as_str())
+            .collect();
+        assert_eq!(names.first(), Some(&"cbSize"));
+        assert!(names.contains(&"maxOpusCodecs"));
+        assert!(names.contains(&"maxSpatialObjects"));
+    }
+}
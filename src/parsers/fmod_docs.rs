@@ -1,10 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 
 use regex::Regex;
 
-use crate::models::{Error, Modifier};
+use crate::models::{Error, Modifier, Unit};
 
 pub fn parse_fragment(content: &str) -> Result<HashMap<String, Modifier>, Error> {
     let mut modifiers = HashMap::new();
@@ -31,9 +31,14 @@ pub fn parse_fragment(content: &str) -> Result<HashMap<String, Modifier>, Error>
         } else if let Some(captures) = output_pattern.captures(line) {
             let argument = captures.get(1).unwrap().as_str();
             // println!("-------> {}", argument);
+            let modifier = if optional_pattern.is_match(line) {
+                Modifier::OptOut
+            } else {
+                Modifier::Out
+            };
             for function in &functions {
                 let key = format!("{}+{}", function, argument);
-                modifiers.insert(key, Modifier::Out);
+                modifiers.insert(key, modifier.clone());
             }
         } else if let Some(captures) = optional_pattern.captures(line) {
             let argument = captures.get(1).unwrap().as_str();
@@ -49,8 +54,83 @@ pub fn parse_fragment(content: &str) -> Result<HashMap<String, Modifier>, Error>
 pub fn parse_parameter_modifiers(paths: &[PathBuf]) -> Result<HashMap<String, Modifier>, Error> {
     let mut output = HashMap::new();
     for path in paths {
+        // manual pages differ between SDK versions
+        if !path.exists() {
+            println!("Manual page not found {:?}", path);
+            continue;
+        }
         let html = fs::read_to_string(path)?;
         output.extend(parse_fragment(&html)?)
     }
     Ok(output)
 }
+
+/// Collects unit labels of parameters, e.g. `Milliseconds` or `Boolean`.
+pub fn parse_units_fragment(content: &str) -> HashMap<String, String> {
+    let mut units = HashMap::new();
+
+    let function_pattern = Regex::new("<span class=\"nf\">(\\w+)</span>").unwrap();
+    let argument_pattern = Regex::new("<dt>(\\w+)").unwrap();
+    let unit_pattern = Regex::new("Units:</span> <a[^>]*>(\\w+)</a>").unwrap();
+
+    let mut functions = vec![];
+    let mut argument = None;
+    for line in content.lines() {
+        if line.contains("<div class=\"language-selector\">") {
+            functions = vec![];
+            argument = None;
+        }
+        if let Some(captures) = function_pattern.captures(line) {
+            functions.push(captures.get(1).unwrap().as_str());
+        } else if let Some(captures) = argument_pattern.captures(line) {
+            argument = Some(captures.get(1).unwrap().as_str());
+        } else if let (Some(captures), Some(argument)) = (unit_pattern.captures(line), argument) {
+            let unit = captures.get(1).unwrap().as_str();
+            for function in &functions {
+                let key = format!("{}+{}", function, argument);
+                units.insert(key, unit.to_string());
+            }
+        }
+    }
+    units
+}
+
+pub fn parse_parameter_units(paths: &[PathBuf]) -> Result<HashMap<String, String>, Error> {
+    let mut output = HashMap::new();
+    for path in paths {
+        // manual pages differ between SDK versions
+        if !path.exists() {
+            println!("Manual page not found {:?}", path);
+            continue;
+        }
+        let html = fs::read_to_string(path)?;
+        output.extend(parse_units_fragment(&html))
+    }
+    Ok(output)
+}
+
+/// Parameters measured in units having Rust wrapper types.
+pub fn select_units(labels: &HashMap<String, String>) -> HashMap<String, Unit> {
+    labels
+        .iter()
+        .filter_map(|(key, label)| {
+            let unit = match label.as_str() {
+                "Milliseconds" => Unit::Milliseconds,
+                "Hertz" => Unit::Hertz,
+                "Samples" => Unit::Samples,
+                _ => return None,
+            };
+            Some((key.clone(), unit))
+        })
+        .collect()
+}
+
+/// Parameters documented as booleans, most of them are FMOD_BOOL
+/// but some are declared as plain int.
+pub fn select_booleans(labels: &HashMap<String, String>) -> HashSet<String> {
+    labels
+        .iter()
+        .filter(|(_, label)| *label == "Boolean")
+        .map(|(key, _)| key.clone())
+        .collect()
+}
@@ -1,4 +1,6 @@
-use crate::models::{Callback, Constant, Enumeration, Error, Flags, OpaqueType, Structure};
+use crate::models::{
+    Callback, Constant, Enumeration, Error, Flags, OpaqueType, Structure, StructureDeclaration,
+};
 use crate::repr::JsonConverter;
 use pest::{error, Parser};
 
@@ -37,15 +39,15 @@ pub fn parse(source: &str) -> Result<Header, Error> {
             Rule::Flags => header.flags.push(converter.convert(declaration)?),
             Rule::Enumeration => header.enumerations.push(converter.convert(declaration)?),
             Rule::Structure => {
-                let structure: Structure = converter.convert(declaration)?;
+                let declaration: StructureDeclaration = converter.convert(declaration)?;
                 if let Some(index) = header
                     .opaque_types
                     .iter()
-                    .position(|opaque_type| opaque_type.name == structure.name)
+                    .position(|opaque_type| opaque_type.name == declaration.name)
                 {
                     header.opaque_types.remove(index);
                 }
-                header.structures.push(structure);
+                header.structures.extend(declaration.into_structures());
             }
             Rule::Callback => header.callbacks.push(converter.convert(declaration)?),
             _ => continue,
@@ -60,3 +62,44 @@ impl From<error::Error<Rule>> for Error {
         Self::Pest(error.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Type::FundamentalType;
+
+    const FMOD_2_03: &str = include_str!("../../fixtures/fmod-2.03/fmod_dsp.h");
+
+    #[test]
+    fn parses_dynamic_response_data_type() {
+        let header = parse(FMOD_2_03).unwrap();
+        let data_type = header
+            .enumerations
+            .iter()
+            .find(|enumeration| enumeration.name == "FMOD_DSP_PARAMETER_DATA_TYPE")
+            .unwrap();
+        let enumerator = data_type
+            .enumerators
+            .iter()
+            .find(|enumerator| enumerator.name == "FMOD_DSP_PARAMETER_DATA_TYPE_DYNAMIC_RESPONSE")
+            .unwrap();
+        assert_eq!(enumerator.value.as_deref(), Some("-7"));
+    }
+
+    #[test]
+    fn parses_dynamic_response_structure() {
+        let header = parse(FMOD_2_03).unwrap();
+        let response = header
+            .structures
+            .iter()
+            .find(|structure| structure.name == "FMOD_DSP_PARAMETER_DYNAMIC_RESPONSE")
+            .unwrap();
+        assert_eq!(response.fields.len(), 2);
+        assert_eq!(response.fields[1].name, "rms");
+        assert_eq!(
+            response.fields[1].field_type,
+            FundamentalType("float".into())
+        );
+        assert_eq!(response.fields[1].as_array.as_deref(), Some("[32]"));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::models::{Constant, Enumeration, Error, Structure};
+use crate::models::{Constant, Enumeration, Error, Structure, StructureDeclaration};
 use crate::repr::JsonConverter;
 use pest::{error, Parser};
 
@@ -26,7 +26,10 @@ pub fn parse(source: &str) -> Result<Header, Error> {
         match declaration.as_rule() {
             Rule::Constant => header.constants.push(converter.convert(declaration)?),
             Rule::Enumeration => header.enumerations.push(converter.convert(declaration)?),
-            Rule::Structure => header.structures.push(converter.convert(declaration)?),
+            Rule::Structure => {
+                let declaration: StructureDeclaration = converter.convert(declaration)?;
+                header.structures.extend(declaration.into_structures());
+            }
             _ => continue,
         }
     }
@@ -39,3 +42,51 @@ impl From<error::Error<Rule>> for Error {
         Self::Pest(error.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Api;
+
+    const FMOD_2_03: &str = include_str!("../../fixtures/fmod-2.03/fmod_dsp_effects.h");
+
+    fn enumerators<'a>(header: &'a Header, name: &str) -> Vec<&'a str> {
+        header
+            .enumerations
+            .iter()
+            .find(|enumeration| enumeration.name == name)
+            .map(|enumeration| {
+                enumeration
+                    .enumerators
+                    .iter()
+                    .map(|enumerator| enumerator.name.as_str())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn parses_multiband_dynamics() {
+        let header = parse(FMOD_2_03).unwrap();
+        assert!(enumerators(&header, "FMOD_DSP_TYPE").contains(&"FMOD_DSP_TYPE_MULTIBAND_DYNAMICS"));
+        assert_eq!(
+            enumerators(&header, "FMOD_DSP_MULTIBAND_DYNAMICS").len(),
+            12
+        );
+        assert_eq!(
+            enumerators(&header, "FMOD_DSP_MULTIBAND_DYNAMICS_MODE_TYPE").len(),
+            5
+        );
+    }
+
+    #[test]
+    fn parses_fft_window_type() {
+        let header = parse(FMOD_2_03).unwrap();
+        assert_eq!(enumerators(&header, "FMOD_DSP_FFT_WINDOW_TYPE").len(), 6);
+        assert!(enumerators(&header, "FMOD_DSP_FFT").contains(&"FMOD_DSP_FFT_SPECTRAL_CENTROID"));
+        assert_eq!(
+            Api::patch_structure_name("FMOD_DSP_FFT_WINDOW_TYPE"),
+            "DspFftWindow"
+        );
+    }
+}
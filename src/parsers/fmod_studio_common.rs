@@ -1,4 +1,6 @@
-use crate::models::{Callback, Constant, Enumeration, Error, Flags, OpaqueType, Structure};
+use crate::models::{
+    Callback, Constant, Enumeration, Error, Flags, OpaqueType, Structure, StructureDeclaration,
+};
 use crate::repr::JsonConverter;
 use pest::{error, Parser};
 
@@ -37,15 +39,15 @@ pub fn parse(source: &str) -> Result<Header, Error> {
             Rule::Flags => header.flags.push(converter.convert(declaration)?),
             Rule::Enumeration => header.enumerations.push(converter.convert(declaration)?),
             Rule::Structure => {
-                let structure: Structure = converter.convert(declaration)?;
+                let declaration: StructureDeclaration = converter.convert(declaration)?;
                 if let Some(index) = header
                     .opaque_types
                     .iter()
-                    .position(|opaque_type| opaque_type.name == structure.name)
+                    .position(|opaque_type| opaque_type.name == declaration.name)
                 {
                     header.opaque_types.remove(index);
                 }
-                header.structures.push(structure);
+                header.structures.extend(declaration.into_structures());
             }
             Rule::Callback => header.callbacks.push(converter.convert(declaration)?),
             _ => continue,
@@ -0,0 +1,43 @@
+use regex::Regex;
+
+use crate::models::Error;
+
+/// Entry of GUIDs.txt exported by FMOD Studio, e.g. `{...} event:/Music/Level 01`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StudioPath {
+    pub kind: String,
+    pub path: String,
+    pub data_1: u32,
+    pub data_2: u16,
+    pub data_3: u16,
+    pub data_4: [u8; 8],
+}
+
+pub fn parse(source: &str) -> Result<Vec<StudioPath>, Error> {
+    let pattern = Regex::new(
+        "^\\{([0-9a-fA-F]{8})-([0-9a-fA-F]{4})-([0-9a-fA-F]{4})-([0-9a-fA-F]{4})-([0-9a-fA-F]{12})\\}\\s+(\\w+):/(.*)$",
+    )
+    .unwrap();
+    let mut paths = vec![];
+    for line in source.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let captures = pattern.captures(line).ok_or(Error::FileMalformed)?;
+        let tail = format!("{}{}", &captures[4], &captures[5]);
+        let mut data_4 = [0u8; 8];
+        for (index, byte) in data_4.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&tail[index * 2..index * 2 + 2], 16)?;
+        }
+        paths.push(StudioPath {
+            kind: captures[6].to_string(),
+            path: format!("{}:/{}", &captures[6], &captures[7]),
+            data_1: u32::from_str_radix(&captures[1], 16)?,
+            data_2: u16::from_str_radix(&captures[2], 16)?,
+            data_3: u16::from_str_radix(&captures[3], 16)?,
+            data_4,
+        });
+    }
+    Ok(paths)
+}
@@ -1,3 +1,4 @@
+pub mod defines;
 pub mod fmod;
 pub mod fmod_codec;
 pub mod fmod_common;
@@ -8,3 +9,5 @@ pub mod fmod_errors;
 pub mod fmod_output;
 pub mod fmod_studio;
 pub mod fmod_studio_common;
+pub mod guids;
+pub mod modifiers;
@@ -0,0 +1,43 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::models::{Error, Modifier};
+
+impl From<toml::de::Error> for Error {
+    fn from(error: toml::de::Error) -> Self {
+        Self::Serde(error.to_string())
+    }
+}
+
+impl From<toml::ser::Error> for Error {
+    fn from(error: toml::ser::Error) -> Self {
+        Self::Serde(error.to_string())
+    }
+}
+
+/// Modifiers grouped by function, sorted to keep curated file diff-able.
+type ModifierTable = BTreeMap<String, BTreeMap<String, Modifier>>;
+
+pub fn export(modifiers: &HashMap<String, Modifier>) -> Result<String, Error> {
+    let mut table = ModifierTable::new();
+    for (key, modifier) in modifiers {
+        if let Some((function, argument)) = key.split_once('+') {
+            table
+                .entry(function.to_string())
+                .or_default()
+                .insert(argument.to_string(), modifier.clone());
+        }
+    }
+    Ok(toml::to_string(&table)?)
+}
+
+/// Curated modifiers, `None` marks argument which is not an output despite documentation.
+pub fn import(source: &str) -> Result<HashMap<String, Modifier>, Error> {
+    let table: ModifierTable = toml::from_str(source)?;
+    let mut modifiers = HashMap::new();
+    for (function, arguments) in table {
+        for (argument, modifier) in arguments {
+            modifiers.insert(format!("{}+{}", function, argument), modifier);
+        }
+    }
+    Ok(modifiers)
+}
@@ -1,5 +1,5 @@
+use crate::generators::idents::move_dimension_prefix;
 use crate::models::Api;
-use convert_case::{Case, Casing};
 use std::collections::HashMap;
 
 lazy_static! {
@@ -148,14 +148,19 @@ lazy_static! {
             ("Advancedsettings", "AdvancedSettings"),
             ("Createsoundexinfo", "CreateSoundexInfo"),
             ("ErrorcallbackInfo", "ErrorCallbackInfo"),
+            ("DspLoudnessMeterInfoType", "DspLoudnessMeterInfo"),
             ("DspParameter3Dattributes", "DspParameterAttributes3d"),
             (
                 "DspParameter3DattributesMulti",
                 "DspParameterAttributes3dMulti",
             ),
             ("3DRolloffCallback", "RolloffCallback3d"),
+            ("OutputObject3Dinfo", "OutputObject3dInfo"),
             ("Channelpriority", "ChannelPriority"),
             ("Audioworklet", "AudioWorklet"),
+            ("Ohaudio", "OhAudio"),
+            // renamed in FMOD 2.03, keeps name of previous versions
+            ("DspFftWindowType", "DspFftWindow"),
             ("5Point1", "Mode5Point1"),
             ("7Point1", "Mode7Point1"),
             ("7Point1Point4", "Mode7Point1Point4"),
@@ -182,15 +187,65 @@ pub const ENUMERATOR_RENAMES: &[(&str, &str)] = &[
     ),
 ];
 
-impl Api {
-    pub fn patch_ident(ident: &str) -> String {
-        if KEYWORDS.contains(&&*ident.to_lowercase()) {
-            format!("{}_", ident)
-        } else {
-            ident.to_string()
-        }
-    }
+/// Array arguments paired with length argument where names don't follow
+/// conventions: function, array and length.
+pub const ARRAY_LENGTHS: &[(&str, &str, &str)] = &[
+    (
+        "FMOD_Studio_EventInstance_SetParametersByIDs",
+        "ids",
+        "count",
+    ),
+    ("FMOD_Studio_System_SetParametersByIDs", "ids", "count"),
+];
+
+/// Functions only useful on some platforms, generated behind cargo feature:
+/// function and feature name.
+pub const FEATURE_GATED_FUNCTIONS: &[(&str, &str)] = &[
+    ("FMOD_System_AttachChannelGroupToPort", "ports"),
+    ("FMOD_System_DetachChannelGroupFromPort", "ports"),
+];
+
+/// Functions missing in HTML5 (Emscripten) build of FMOD.
+pub const WASM_UNSUPPORTED_FUNCTIONS: &[&str] = &["FMOD_Thread_SetAttributes"];
+
+/// Structures with fields identical to FFI structure, generated with `#[repr(C)]` layout
+/// so references are passed to FMOD by pointer cast instead of converted temporaries.
+pub const LAYOUT_COMPATIBLE_STRUCTURES: &[&str] = &[
+    "FMOD_GUID",
+    "FMOD_VECTOR",
+    "FMOD_3D_ATTRIBUTES",
+    "FMOD_REVERB_PROPERTIES",
+];
 
+/// Enumerators paired with union field they select.
+pub type UnionSelectors = &'static [(&'static str, &'static str)];
+
+/// Structure unions discriminated by adjacent enumeration field:
+/// structure, tag field and union field selected by each enumerator.
+pub const TAGGED_UNIONS: &[(&str, &str, UnionSelectors)] = &[
+    (
+        "FMOD_STUDIO_USER_PROPERTY",
+        "type",
+        &[
+            ("FMOD_STUDIO_USER_PROPERTY_TYPE_INTEGER", "intvalue"),
+            ("FMOD_STUDIO_USER_PROPERTY_TYPE_BOOLEAN", "boolvalue"),
+            ("FMOD_STUDIO_USER_PROPERTY_TYPE_FLOAT", "floatvalue"),
+            ("FMOD_STUDIO_USER_PROPERTY_TYPE_STRING", "stringvalue"),
+        ],
+    ),
+    (
+        "FMOD_DSP_PARAMETER_DESC",
+        "type",
+        &[
+            ("FMOD_DSP_PARAMETER_TYPE_FLOAT", "floatdesc"),
+            ("FMOD_DSP_PARAMETER_TYPE_INT", "intdesc"),
+            ("FMOD_DSP_PARAMETER_TYPE_BOOL", "booldesc"),
+            ("FMOD_DSP_PARAMETER_TYPE_DATA", "datadesc"),
+        ],
+    ),
+];
+
+impl Api {
     pub fn patch_enumerator(name: &str) -> String {
         match ENUMERATOR_RENAMES.iter().find(|pair| pair.0 == name) {
             None => name.to_string(),
@@ -199,17 +254,7 @@ impl Api {
     }
 
     pub fn patch_variant_name(key: &str) -> String {
-        let key = if key.starts_with("3D") {
-            format!("{}3d", &key[2..]).to_case(Case::UpperCamel)
-        } else {
-            key.to_string()
-        };
-
-        let key = if key.starts_with("2D") {
-            format!("{}2d", &key[2..]).to_case(Case::UpperCamel)
-        } else {
-            key.to_string()
-        };
+        let key = move_dimension_prefix(key);
 
         let name = key;
         let name = match RENAMES.get(&name[..]) {
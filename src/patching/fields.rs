@@ -1,7 +1,105 @@
+use crate::models::{FieldDirection, FieldRole};
 use crate::Api;
 use quote::__private::TokenStream;
 
 impl Api {
+    pub fn patch_field_directions(&mut self) {
+        let fields = [
+            // written by FMOD before plugin callbacks
+            ("FMOD_DSP_STATE", "instance", FieldDirection::Output),
+            ("FMOD_DSP_STATE", "channelmask", FieldDirection::Output),
+            (
+                "FMOD_DSP_STATE",
+                "source_speakermode",
+                FieldDirection::Output,
+            ),
+            ("FMOD_DSP_STATE", "sidechaindata", FieldDirection::Output),
+            (
+                "FMOD_DSP_STATE",
+                "sidechainchannels",
+                FieldDirection::Output,
+            ),
+            ("FMOD_DSP_STATE", "functions", FieldDirection::Output),
+            ("FMOD_DSP_STATE", "systemobject", FieldDirection::Output),
+            ("FMOD_CODEC_STATE", "functions", FieldDirection::Output),
+            ("FMOD_OUTPUT_STATE", "functions", FieldDirection::Output),
+            // written by FMOD Studio before event callbacks
+            (
+                "FMOD_STUDIO_PLUGIN_INSTANCE_PROPERTIES",
+                "name",
+                FieldDirection::Output,
+            ),
+            (
+                "FMOD_STUDIO_PLUGIN_INSTANCE_PROPERTIES",
+                "dsp",
+                FieldDirection::Output,
+            ),
+            (
+                "FMOD_STUDIO_TIMELINE_MARKER_PROPERTIES",
+                "name",
+                FieldDirection::Output,
+            ),
+            (
+                "FMOD_STUDIO_TIMELINE_MARKER_PROPERTIES",
+                "position",
+                FieldDirection::Output,
+            ),
+        ];
+        for (structure, field, direction) in fields {
+            self.field_directions
+                .insert(format!("{}+{}", structure, field), direction);
+        }
+    }
+
+    /// Fields computed from other parts of structure, hidden from Rust structures
+    /// and filled on conversion to FFI.
+    pub fn patch_field_roles(&mut self) {
+        let fields = [
+            ("FMOD_ADVANCEDSETTINGS", "cbSize", FieldRole::SizeOfSelf),
+            (
+                "FMOD_STUDIO_ADVANCEDSETTINGS",
+                "cbsize",
+                FieldRole::SizeOfSelf,
+            ),
+            ("FMOD_CREATESOUNDEXINFO", "cbsize", FieldRole::SizeOfSelf),
+            (
+                "FMOD_ADVANCEDSETTINGS",
+                "ASIONumChannels",
+                FieldRole::CountOf("ASIOChannelList".into()),
+            ),
+            ("FMOD_STUDIO_BANK_INFO", "size", FieldRole::SizeOfSelf),
+            (
+                "FMOD_CREATESOUNDEXINFO",
+                "inclusionlistnum",
+                FieldRole::CountOf("inclusionlist".into()),
+            ),
+            (
+                "FMOD_DSP_DESCRIPTION",
+                "numparameters",
+                FieldRole::CountOf("paramdesc".into()),
+            ),
+            (
+                "FMOD_DSP_PARAMETER_FFT",
+                "numchannels",
+                FieldRole::CountOf("spectrum".into()),
+            ),
+            (
+                "FMOD_OUTPUT_OBJECT3DINFO",
+                "bufferlength",
+                FieldRole::CountOf("buffer".into()),
+            ),
+            (
+                "FMOD_DSP_METERING_INFO",
+                "numchannels",
+                FieldRole::CountOf("peaklevel".into()),
+            ),
+        ];
+        for (structure, field, role) in fields {
+            self.field_roles
+                .insert(format!("{}+{}", structure, field), role);
+        }
+    }
+
     pub fn patch_rust_struct_field_definition(
         &self,
         structure: &str,
@@ -20,43 +118,21 @@ impl Api {
             ("FMOD_CREATESOUNDEXINFO", "inclusionlist") => {
                 quote! { pub inclusionlist: Option<Vec<i32>> }
             }
-            //
-            ("FMOD_CREATESOUNDEXINFO", "inclusionlistnum") => quote! {},
-            ("FMOD_ADVANCEDSETTINGS", "cbSize") => quote! {},
-            ("FMOD_STUDIO_ADVANCEDSETTINGS", "cbsize") => quote! {},
-            ("FMOD_CREATESOUNDEXINFO", "cbsize") => quote! {},
-            ("FMOD_DSP_DESCRIPTION", "numparameters") => quote! {},
-            ("FMOD_DSP_PARAMETER_FFT", "numchannels") => quote! {},
+            ("FMOD_DSP_METERING_INFO", "peaklevel") => quote! { pub peaklevel: Vec<f32> },
+            ("FMOD_DSP_METERING_INFO", "rmslevel") => quote! { pub rmslevel: Vec<f32> },
+            ("FMOD_DSP_LOUDNESS_METER_INFO_TYPE", "loudnesshistogram") => {
+                quote! { pub loudnesshistogram: Vec<f32> }
+            }
             _ => return None,
         };
         Some(expression)
     }
 
-    /*
-        fn is_convertable(structure: &Structure, field: &Field) -> bool {
-        match (&structure.name[..], &field.name[..]) {
-            ("FMOD_ADVANCEDSETTINGS", "cbSize") => false,
-            ("FMOD_STUDIO_ADVANCEDSETTINGS", "cbsize") => false,
-            ("FMOD_CREATESOUNDEXINFO", "cbsize") => false,
-            ("FMOD_DSP_DESCRIPTION", "numparameters") => false,
-            ("FMOD_DSP_PARAMETER_FFT", "numchannels") => false,
-            _ => true,
-        }
-    }
-         */
-
     pub fn patch_field_try_from(&self, structure: &str, field: &str) -> Option<TokenStream> {
         let expression = match (structure, field) {
-            ("FMOD_ADVANCEDSETTINGS", "cbSize") => quote! {},
-            ("FMOD_STUDIO_ADVANCEDSETTINGS", "cbsize") => quote! {},
-            ("FMOD_CREATESOUNDEXINFO", "cbsize") => quote! {},
-            ("FMOD_DSP_DESCRIPTION", "numparameters") => quote! {},
-            ("FMOD_DSP_PARAMETER_FFT", "numchannels") => quote! {},
-            //
             ("FMOD_CREATESOUNDEXINFO", "inclusionlist") => quote! {
                 ptr_opt!(value.inclusionlist, to_vec!(value.inclusionlist, value.inclusionlistnum))
             },
-            ("FMOD_CREATESOUNDEXINFO", "inclusionlistnum") => quote! {},
             ("FMOD_CREATESOUNDEXINFO", "dlsname") => quote! {
                 ptr_opt!(value.dlsname, to_string!(value.dlsname)?)
             },
@@ -73,10 +149,10 @@ impl Api {
                 quote! { attr3d_array8(value.relative.map(Attributes3d::try_from).into_iter().collect::<Result<Vec<Attributes3d>, Error>>()?) }
             }
             ("FMOD_ADVANCEDSETTINGS", "ASIOChannelList") => {
-                quote! { to_vec!(value.ASIOChannelList, value.ASIONumChannels, |ptr| to_string!(ptr))? }
+                quote! { ptr_opt!(value.ASIOChannelList, to_vec!(value.ASIOChannelList, value.ASIONumChannels, |ptr| to_string!(ptr))?).unwrap_or_default() }
             }
             ("FMOD_ADVANCEDSETTINGS", "ASIOSpeakerList") => {
-                quote! { to_vec!(value.ASIOSpeakerList, value.ASIONumChannels, Speaker::from)? }
+                quote! { ptr_opt!(value.ASIOSpeakerList, to_vec!(value.ASIOSpeakerList, value.ASIONumChannels, Speaker::from)?).unwrap_or_default() }
             }
             ("FMOD_OUTPUT_OBJECT3DINFO", "buffer") => {
                 quote! { to_vec!(value.buffer, value.bufferlength) }
@@ -88,7 +164,7 @@ impl Api {
                 quote! { to_vec!(value.bufferchannelmask, value.numbuffers) }
             }
             ("FMOD_DSP_BUFFER_ARRAY", "buffers") => {
-                quote! { to_vec!(value.buffers, value.numbuffers, |ptr| Ok(*ptr))? }
+                quote! { to_vec!(value.buffers, value.numbuffers, |ptr| Ok(unsafe { *ptr }))? }
             }
             ("FMOD_DSP_PARAMETER_FLOAT_MAPPING_PIECEWISE_LINEAR", "pointparamvalues") => {
                 quote! { to_vec!(value.pointparamvalues, value.numpoints) }
@@ -103,14 +179,23 @@ impl Api {
                 quote! { vec![] } // TODO
             }
             ("FMOD_DSP_PARAMETER_FFT", "spectrum") => {
-                quote! { to_vec!(value.spectrum.as_ptr(), value.numchannels, |ptr| Ok(to_vec!(ptr, value.length)))? }
+                quote! { to_vec!(value.spectrum.as_ptr(), value.numchannels.clamp(0, 32), |ptr| Ok(to_vec!(ptr, value.length)))? }
             }
             ("FMOD_DSP_DESCRIPTION", "paramdesc") => {
-                quote! { to_vec!(*value.paramdesc, value.numparameters, DspParameterDesc::try_from)? }
+                quote! { to_vec!(unsafe { *value.paramdesc }, value.numparameters, DspParameterDesc::try_from)? }
             }
             ("FMOD_DSP_STATE", "sidechaindata") => {
                 quote! { to_vec!(value.sidechaindata, value.sidechainchannels) }
             }
+            ("FMOD_DSP_METERING_INFO", "peaklevel") => {
+                quote! { value.peaklevel.iter().take(value.numchannels.max(0) as usize).copied().collect() }
+            }
+            ("FMOD_DSP_LOUDNESS_METER_INFO_TYPE", "loudnesshistogram") => {
+                quote! { value.loudnesshistogram.to_vec() }
+            }
+            ("FMOD_DSP_METERING_INFO", "rmslevel") => {
+                quote! { value.rmslevel.iter().take(value.numchannels.max(0) as usize).copied().collect() }
+            }
             _ => return None,
         };
         Some(expression)
@@ -136,17 +221,18 @@ impl Api {
             ("FMOD_CREATESOUNDEXINFO", "fsbguid") => {
                 quote! { opt_ptr!(self.fsbguid, |v| &mut v.into() as *mut _) }
             }
-            ("FMOD_CREATESOUNDEXINFO", "cbsize") => {
-                quote! { size_of::<ffi::FMOD_CREATESOUNDEXINFO>() as i32 }
+            // strings must outlive the conversion, see into_ffi structure patches
+            ("FMOD_STUDIO_ADVANCEDSETTINGS", "encryptionkey") => {
+                quote! { null() }
             }
-            ("FMOD_ADVANCEDSETTINGS", "cbSize") => {
-                quote! { size_of::<ffi::FMOD_ADVANCEDSETTINGS>() as i32 }
+            ("FMOD_DSP_PARAMETER_DESC", "description") => {
+                quote! { null() }
             }
-            ("FMOD_STUDIO_ADVANCEDSETTINGS", "cbsize") => {
-                quote! { size_of::<ffi::FMOD_STUDIO_ADVANCEDSETTINGS>() as i32 }
+            ("FMOD_CODEC_DESCRIPTION", "name") => {
+                quote! { null() }
             }
-            ("FMOD_DSP_DESCRIPTION", "numparameters") => {
-                quote! { self.paramdesc.len() as i32 }
+            ("FMOD_OUTPUT_DESCRIPTION", "name") => {
+                quote! { null() }
             }
             ("FMOD_DSP_PARAMETER_3DATTRIBUTES_MULTI", "relative") => {
                 quote! { self.relative.map(Attributes3d::into) }
@@ -155,10 +241,13 @@ impl Api {
                 quote! { self.buffer.as_ptr() as *mut _ }
             }
             ("FMOD_ADVANCEDSETTINGS", "ASIOChannelList") => {
-                quote! { self.asio_channel_list.into_iter().map(|val| val.as_ptr()).collect::<Vec<_>>().as_mut_ptr().cast() }
+                quote! { null_mut() }
             }
             ("FMOD_ADVANCEDSETTINGS", "ASIOSpeakerList") => {
-                quote! { self.asio_speaker_list.into_iter().map(|val| val.into()).collect::<Vec<_>>().as_mut_ptr() }
+                quote! { null_mut() }
+            }
+            ("FMOD_ADVANCEDSETTINGS", "ASIONumChannels") => {
+                quote! { 0 }
             }
             ("FMOD_DSP_BUFFER_ARRAY", "buffernumchannels") => {
                 quote! { self.buffernumchannels.as_ptr() as *mut _ }
@@ -187,12 +276,25 @@ impl Api {
             ("FMOD_DSP_STATE", "sidechaindata") => {
                 quote! { self.sidechaindata.as_ptr() as *mut _ }
             }
-            ("FMOD_DSP_PARAMETER_FFT", "numchannels") => {
-                quote! { self.spectrum.len() as i32 }
-            }
             ("FMOD_DSP_PARAMETER_FFT", "spectrum") => {
                 quote! { [null_mut(); 32] }
             }
+            // channel counts must not exceed fixed arrays of 32 channels
+            ("FMOD_DSP_PARAMETER_FFT", "numchannels") => {
+                quote! { self.spectrum.len().min(32) as _ }
+            }
+            ("FMOD_DSP_METERING_INFO", "numchannels") => {
+                quote! { self.peaklevel.len().min(32) as _ }
+            }
+            ("FMOD_DSP_METERING_INFO", "peaklevel") => {
+                quote! { std::array::from_fn(|index| self.peaklevel.get(index).copied().unwrap_or_default()) }
+            }
+            ("FMOD_DSP_LOUDNESS_METER_INFO_TYPE", "loudnesshistogram") => {
+                quote! { std::array::from_fn(|index| self.loudnesshistogram.get(index).copied().unwrap_or_default()) }
+            }
+            ("FMOD_DSP_METERING_INFO", "rmslevel") => {
+                quote! { std::array::from_fn(|index| self.rmslevel.get(index).copied().unwrap_or_default()) }
+            }
             _ => return None,
         };
         Some(expression)
@@ -30,19 +30,7 @@ impl Signature {
             return true;
         }
 
-        // FMOD_Sound_Set3DCustomRolloff
-        if function.name == "FMOD_Sound_Set3DCustomRolloff" && argument.name == "numpoints" {
-            self.targets
-                .push(quote! { let numpoints = points.len() as i32; });
-            self.inputs.push(quote! { numpoints });
-            return true;
-        }
-        if function.name == "FMOD_Sound_Set3DCustomRolloff" && argument.name == "points" {
-            self.arguments.push(quote! { points: Vec<Vector> });
-            self.inputs
-                .push(quote! { vec_as_mut_ptr(points, |point| point.into()) });
-            return true;
-        }
+        // FMOD_Sound_Get3DCustomRolloff
         if function.name == "FMOD_Sound_Get3DCustomRolloff" && argument.name == "numpoints" {
             self.targets
                 .push(quote! { let mut numpoints = i32::default(); });
@@ -58,19 +46,7 @@ impl Signature {
             return true;
         }
 
-        // FMOD_Channel_Set3DCustomRolloff
-        if function.name == "FMOD_Channel_Set3DCustomRolloff" && argument.name == "numpoints" {
-            self.targets
-                .push(quote! { let numpoints = points.len() as i32; });
-            self.inputs.push(quote! { numpoints });
-            return true;
-        }
-        if function.name == "FMOD_Channel_Set3DCustomRolloff" && argument.name == "points" {
-            self.arguments.push(quote! { points: Vec<Vector> });
-            self.inputs
-                .push(quote! { vec_as_mut_ptr(points, |point| point.into()) });
-            return true;
-        }
+        // FMOD_Channel_Get3DCustomRolloff
         if function.name == "FMOD_Channel_Get3DCustomRolloff" && argument.name == "numpoints" {
             self.targets
                 .push(quote! { let mut numpoints = i32::default(); });
@@ -86,18 +62,6 @@ impl Signature {
             return true;
         }
 
-        if function.name == "FMOD_ChannelGroup_Set3DCustomRolloff" && argument.name == "numpoints" {
-            self.targets
-                .push(quote! { let numpoints = points.len() as i32; });
-            self.inputs.push(quote! { numpoints });
-            return true;
-        }
-        if function.name == "FMOD_ChannelGroup_Set3DCustomRolloff" && argument.name == "points" {
-            self.arguments.push(quote! { points: Vec<Vector> });
-            self.inputs
-                .push(quote! { vec_as_mut_ptr(points, |point| point.into()) });
-            return true;
-        }
         if function.name == "FMOD_ChannelGroup_Get3DCustomRolloff" && argument.name == "numpoints" {
             self.targets
                 .push(quote! { let mut numpoints = i32::default(); });
@@ -124,7 +88,7 @@ impl Signature {
                 .push(quote! { let mut array = vec![null_mut(); capacity as usize]; });
             self.inputs.push(quote! { array.as_mut_ptr() });
             self.outputs
-                .push(quote! { array.into_iter().take(count as usize).map(EventDescription::from).collect() });
+                .push(quote! { array.into_iter().take(count as usize).filter_map(EventDescription::try_from_ptr).collect() });
             self.return_types.push(quote! { Vec<EventDescription> });
             return true;
         }
@@ -140,7 +104,7 @@ impl Signature {
                 .push(quote! { let mut array = vec![null_mut(); capacity as usize]; });
             self.inputs.push(quote! { array.as_mut_ptr() });
             self.outputs
-                .push(quote! { array.into_iter().take(count as usize).map(Bus::from).collect() });
+                .push(quote! { array.into_iter().take(count as usize).filter_map(Bus::try_from_ptr).collect() });
             self.return_types.push(quote! { Vec<Bus> });
             return true;
         }
@@ -156,7 +120,7 @@ impl Signature {
                 .push(quote! { let mut array = vec![null_mut(); capacity as usize]; });
             self.inputs.push(quote! { array.as_mut_ptr() });
             self.outputs
-                .push(quote! { array.into_iter().take(count as usize).map(Vca::from).collect() });
+                .push(quote! { array.into_iter().take(count as usize).filter_map(Vca::try_from_ptr).collect() });
             self.return_types.push(quote! { Vec<Vca> });
             return true;
         }
@@ -175,7 +139,7 @@ impl Signature {
             self.targets
                 .push(quote! { let mut array = vec![null_mut(); capacity as usize]; });
             self.inputs.push(quote! { array.as_mut_ptr() });
-            self.outputs.push(quote! { array.into_iter().take(count as usize).map(EventInstance::from).collect() });
+            self.outputs.push(quote! { array.into_iter().take(count as usize).filter_map(EventInstance::try_from_ptr).collect() });
             self.return_types.push(quote! { Vec<EventInstance> });
             return true;
         }
@@ -191,7 +155,7 @@ impl Signature {
                 .push(quote! { let mut array = vec![null_mut(); capacity as usize]; });
             self.inputs.push(quote! { array.as_mut_ptr() });
             self.outputs
-                .push(quote! { array.into_iter().take(count as usize).map(Bank::from).collect() });
+                .push(quote! { array.into_iter().take(count as usize).filter_map(Bank::try_from_ptr).collect() });
             self.return_types.push(quote! { Vec<Bank> });
             return true;
         }
@@ -216,6 +180,73 @@ impl Signature {
             return true;
         }
 
+        if function.name == "FMOD_Sound_ReadData" && argument.name == "buffer" {
+            self.arguments.push(quote! { buffer: &mut [u8] });
+            self.inputs
+                .push(quote! { buffer.as_mut_ptr() as *mut c_void });
+            return true;
+        }
+        if function.name == "FMOD_Sound_ReadData" && argument.name == "length" {
+            self.inputs.push(quote! { buffer.len() as u32 });
+            return true;
+        }
+        if function.name == "FMOD_Sound_ReadData" && argument.name == "read" {
+            self.targets.push(quote! { let mut read = u32::default(); });
+            self.inputs.push(quote! { &mut read });
+            self.outputs.push(quote! { read as usize });
+            self.return_types.push(quote! { usize });
+            return true;
+        }
+
+        let parameter_by_id = [
+            "FMOD_Studio_EventInstance_GetParameterByID",
+            "FMOD_Studio_System_GetParameterByID",
+        ];
+        if parameter_by_id.contains(&&function.name[..]) && argument.name == "value" {
+            self.targets
+                .push(quote! { let mut value = f32::default(); });
+            self.inputs.push(quote! { &mut value });
+            return true;
+        }
+        if parameter_by_id.contains(&&function.name[..]) && argument.name == "finalvalue" {
+            self.targets
+                .push(quote! { let mut finalvalue = f32::default(); });
+            self.inputs.push(quote! { &mut finalvalue });
+            self.outputs
+                .push(quote! { ParameterValue { value, final_value: finalvalue } });
+            self.return_types.push(quote! { ParameterValue });
+            return true;
+        }
+
+        if function.name == "FMOD_System_GetSpeakerPosition"
+            && ["x", "y"].contains(&&argument.name[..])
+        {
+            let name = format_ident!("{}", argument.name);
+            self.targets
+                .push(quote! { let mut #name = f32::default(); });
+            self.inputs.push(quote! { &mut #name });
+            self.outputs.push(quote! { #name });
+            self.return_types.push(quote! { f32 });
+            return true;
+        }
+        if function.name == "FMOD_System_GetSpeakerPosition" && argument.name == "active" {
+            self.targets
+                .push(quote! { let mut active = ffi::FMOD_BOOL::default(); });
+            self.inputs.push(quote! { &mut active });
+            self.outputs.push(quote! { to_bool!(active) });
+            self.return_types.push(quote! { bool });
+            return true;
+        }
+
+        if function.name == "FMOD_System_GetSpeakerModeChannels" && argument.name == "channels" {
+            self.targets
+                .push(quote! { let mut channels = i32::default(); });
+            self.inputs.push(quote! { &mut channels });
+            self.outputs.push(quote! { channels });
+            self.return_types.push(quote! { i32 });
+            return true;
+        }
+
         return false;
     }
 }
@@ -228,43 +259,45 @@ impl Api {
                 pub fn create_stream(
                     &self,
                     name_or_data: &str,
-                    mode: impl Into<ffi::FMOD_MODE>,
+                    mode: Mode,
                     exinfo: Option<CreateSoundexInfo>,
                 ) -> Result<Sound, Error> {
-                    unsafe {
-                        let mut sound = null_mut();
-                        match ffi::FMOD_System_CreateStream(
+                    let name_or_data = CString::new(name_or_data)?;
+                    let mut exinfo: Option<ffi::FMOD_CREATESOUNDEXINFO> = exinfo.map(Into::into);
+                    let exinfo = exinfo.as_mut().map_or(null_mut(), |exinfo| exinfo as *mut _);
+                    let mut sound = null_mut();
+                    match profile_call!("FMOD_System_CreateStream", unsafe {
+                        ffi::FMOD_System_CreateStream(
                             self.pointer,
-                            CString::new(name_or_data)?.as_ptr(),
+                            name_or_data.as_ptr(),
                             mode.into(),
-                            exinfo
-                                .map(|value| &mut value.into() as *mut _)
-                                .unwrap_or(null_mut()),
+                            exinfo,
                             &mut sound,
-                        ) {
-                            ffi::FMOD_OK => Ok(Sound::from(sound)),
-                            error => Err(err_fmod!("FMOD_System_CreateStream", error)),
-                        }
+                        )
+                    }) {
+                        ffi::FMOD_OK => Ok(Sound::from(sound)),
+                        error => Err(err_fmod!("FMOD_System_CreateStream", error)),
                     }
                 }
                 pub fn create_stream_from(
                     &self,
                     data: &[u8],
-                    mode: impl Into<ffi::FMOD_MODE>,
+                    mode: Mode,
                     exinfo: CreateSoundexInfo,
                 ) -> Result<Sound, Error> {
-                    unsafe {
-                        let mut sound = null_mut();
-                        match ffi::FMOD_System_CreateStream(
+                    let mut exinfo: ffi::FMOD_CREATESOUNDEXINFO = exinfo.into();
+                    let mut sound = null_mut();
+                    match profile_call!("FMOD_System_CreateStream", unsafe {
+                        ffi::FMOD_System_CreateStream(
                             self.pointer,
                             data.as_ptr() as *const _,
                             mode.into(),
-                            &mut exinfo.into() as *mut _,
+                            &mut exinfo,
                             &mut sound,
-                        ) {
-                            ffi::FMOD_OK => Ok(Sound::from(sound)),
-                            error => Err(err_fmod!("FMOD_System_CreateStream", error)),
-                        }
+                        )
+                    }) {
+                        ffi::FMOD_OK => Ok(Sound::from(sound)),
+                        error => Err(err_fmod!("FMOD_System_CreateStream", error)),
                     }
                 }
             },
@@ -272,46 +305,117 @@ impl Api {
         self.function_patches.insert(
             "FMOD_System_CreateSound".to_string(),
             quote! {
-                pub fn create_sound(
+                pub fn create_sound<'a>(
                     &self,
-                    name_or_data: &str,
-                    mode: impl Into<ffi::FMOD_MODE>,
+                    source: impl Into<SoundSource<'a>>,
+                    mode: Mode,
                     exinfo: Option<CreateSoundexInfo>,
                 ) -> Result<Sound, Error> {
-                    unsafe {
-                        let mut sound = null_mut();
-                        match ffi::FMOD_System_CreateSound(
-                            self.pointer,
-                            CString::new(name_or_data)?.as_ptr(),
-                            mode.into(),
+                    let mut mode: ffi::FMOD_MODE = mode.into();
+                    let mut exinfo: Option<ffi::FMOD_CREATESOUNDEXINFO> = exinfo.map(Into::into);
+                    let name;
+                    let name_or_data = match source.into() {
+                        SoundSource::File(path) => {
+                            name = CString::new(path)?;
+                            name.as_ptr()
+                        }
+                        SoundSource::Stream(path) => {
+                            mode |= ffi::FMOD_CREATESTREAM;
+                            name = CString::new(path)?;
+                            name.as_ptr()
+                        }
+                        SoundSource::Memory(data) => {
+                            mode |= ffi::FMOD_OPENMEMORY;
                             exinfo
-                                .map(|value| &mut value.into() as *mut _)
-                                .unwrap_or(null_mut()),
-                            &mut sound,
-                        ) {
-                            ffi::FMOD_OK => Ok(Sound::from(sound)),
-                            error => Err(err_fmod!("FMOD_System_CreateSound", error)),
+                                .get_or_insert_with(ffi::FMOD_CREATESOUNDEXINFO::default)
+                                .length = data.len() as u32;
+                            data.as_ptr() as *const c_char
                         }
+                    };
+                    let exinfo = exinfo.as_mut().map_or(null_mut(), |exinfo| exinfo as *mut _);
+                    let mut sound = null_mut();
+                    match profile_call!("FMOD_System_CreateSound", unsafe {
+                        ffi::FMOD_System_CreateSound(self.pointer, name_or_data, mode, exinfo, &mut sound)
+                    }) {
+                        ffi::FMOD_OK => Ok(Sound::from(sound)),
+                        error => Err(err_fmod!("FMOD_System_CreateSound", error)),
                     }
                 }
                 pub fn create_sound_from(
                     &self,
                     data: &[u8],
-                    mode: impl Into<ffi::FMOD_MODE>,
+                    mode: Mode,
                     exinfo: CreateSoundexInfo,
                 ) -> Result<Sound, Error> {
-                    unsafe {
-                        let mut sound = null_mut();
-                        match ffi::FMOD_System_CreateSound(
+                    let mut exinfo: ffi::FMOD_CREATESOUNDEXINFO = exinfo.into();
+                    let mut sound = null_mut();
+                    match profile_call!("FMOD_System_CreateSound", unsafe {
+                        ffi::FMOD_System_CreateSound(
                             self.pointer,
                             data.as_ptr() as *const _,
                             mode.into(),
-                            &mut exinfo.into() as *mut _,
+                            &mut exinfo,
                             &mut sound,
-                        ) {
-                            ffi::FMOD_OK => Ok(Sound::from(sound)),
-                            error => Err(err_fmod!("FMOD_System_CreateSound", error)),
+                        )
+                    }) {
+                        ffi::FMOD_OK => Ok(Sound::from(sound)),
+                        error => Err(err_fmod!("FMOD_System_CreateSound", error)),
+                    }
+                }
+            },
+        );
+        self.function_patches.insert(
+            "FMOD_Studio_System_Release".to_string(),
+            quote! {
+                pub fn release(&self) -> Result<(), Error> {
+                    self.clear_callback_rust()?;
+                    match profile_call!("FMOD_Studio_System_Release", unsafe { ffi::FMOD_Studio_System_Release(self.pointer) }) {
+                        ffi::FMOD_OK => {
+                            release_studio_bank_load_callbacks(self.pointer);
+                            Ok(())
                         }
+                        error => Err(err_fmod!("FMOD_Studio_System_Release", error)),
+                    }
+                }
+            },
+        );
+        self.function_patches.insert(
+            "FMOD_Studio_CommandReplay_Release".to_string(),
+            quote! {
+                /// Frees callbacks set by `set_callbacks` together with replay.
+                pub fn release(&self) -> Result<(), Error> {
+                    self.clear_callbacks()?;
+                    match profile_call!("FMOD_Studio_CommandReplay_Release", unsafe { ffi::FMOD_Studio_CommandReplay_Release(self.pointer) }) {
+                        ffi::FMOD_OK => Ok(()),
+                        error => Err(err_fmod!("FMOD_Studio_CommandReplay_Release", error)),
+                    }
+                }
+            },
+        );
+        self.function_patches.insert(
+            "FMOD_Studio_Bank_Unload".to_string(),
+            quote! {
+                pub fn unload(&self) -> Result<(), Error> {
+                    match profile_call!("FMOD_Studio_Bank_Unload", unsafe { ffi::FMOD_Studio_Bank_Unload(self.pointer) }) {
+                        ffi::FMOD_OK => {
+                            release_bank_load_callbacks(self.pointer);
+                            Ok(())
+                        }
+                        error => Err(err_fmod!("FMOD_Studio_Bank_Unload", error)),
+                    }
+                }
+            },
+        );
+        self.function_patches.insert(
+            "FMOD_Studio_System_UnloadAll".to_string(),
+            quote! {
+                pub fn unload_all(&self) -> Result<(), Error> {
+                    match profile_call!("FMOD_Studio_System_UnloadAll", unsafe { ffi::FMOD_Studio_System_UnloadAll(self.pointer) }) {
+                        ffi::FMOD_OK => {
+                            release_studio_bank_load_callbacks(self.pointer);
+                            Ok(())
+                        }
+                        error => Err(err_fmod!("FMOD_Studio_System_UnloadAll", error)),
                     }
                 }
             },
@@ -322,20 +426,283 @@ impl Api {
                 pub fn load_bank_memory(
                     &self,
                     buffer: &[u8],
-                    flags: impl Into<ffi::FMOD_STUDIO_LOAD_BANK_FLAGS>,
+                    flags: LoadBankFlags,
                 ) -> Result<Bank, Error> {
-                    unsafe {
-                        let mut bank = null_mut();
-                        match ffi::FMOD_Studio_System_LoadBankMemory(
+                    let mut bank = null_mut();
+                    match profile_call!("FMOD_Studio_System_LoadBankMemory", unsafe {
+                        ffi::FMOD_Studio_System_LoadBankMemory(
                             self.pointer,
                             buffer.as_ptr() as *const std::os::raw::c_char,
                             buffer.len() as std::os::raw::c_int,
                             LoadMemoryMode::Memory.into(),
                             flags.into(),
                             &mut bank,
-                        ) {
-                            ffi::FMOD_OK => Ok(Bank::from(bank)),
-                            error => Err(err_fmod!("FMOD_Studio_System_LoadBankMemory", error)),
+                        )
+                    }) {
+                        ffi::FMOD_OK => Ok(Bank::from(bank)),
+                        error => Err(err_fmod!("FMOD_Studio_System_LoadBankMemory", error)),
+                    }
+                }
+            },
+        );
+        self.function_patches.insert(
+            "FMOD_System_SetAdvancedSettings".to_string(),
+            quote! {
+                pub fn set_advanced_settings(&self, settings: AdvancedSettings) -> Result<(), Error> {
+                    let mut guard = Guard::default();
+                    let mut settings = settings.into_ffi(&mut guard)?;
+                    match profile_call!("FMOD_System_SetAdvancedSettings", unsafe { ffi::FMOD_System_SetAdvancedSettings(self.pointer, &mut settings) }) {
+                        ffi::FMOD_OK => Ok(()),
+                        error => Err(err_fmod!("FMOD_System_SetAdvancedSettings", error)),
+                    }
+                }
+            },
+        );
+        self.function_patches.insert(
+            "FMOD_Studio_System_SetAdvancedSettings".to_string(),
+            quote! {
+                pub fn set_advanced_settings(&self, settings: StudioAdvancedSettings) -> Result<(), Error> {
+                    let mut guard = Guard::default();
+                    let mut settings = settings.into_ffi(&mut guard)?;
+                    match profile_call!("FMOD_Studio_System_SetAdvancedSettings", unsafe { ffi::FMOD_Studio_System_SetAdvancedSettings(self.pointer, &mut settings) }) {
+                        ffi::FMOD_OK => Ok(()),
+                        error => Err(err_fmod!("FMOD_Studio_System_SetAdvancedSettings", error)),
+                    }
+                }
+            },
+        );
+        // FMOD references plugin descriptions after registration,
+        // so the guard is intentionally kept alive for the process lifetime
+        self.function_patches.insert(
+            "FMOD_System_RegisterDSP".to_string(),
+            quote! {
+                pub fn register_dsp(&self, description: DspDescription) -> Result<u32, Error> {
+                    let mut guard = Guard::default();
+                    let description = description.into_ffi(&mut guard)?;
+                    let mut handle = u32::default();
+                    match profile_call!("FMOD_System_RegisterDSP", unsafe { ffi::FMOD_System_RegisterDSP(self.pointer, &description, &mut handle) }) {
+                        ffi::FMOD_OK => {
+                            std::mem::forget(guard);
+                            Ok(handle)
+                        }
+                        error => Err(err_fmod!("FMOD_System_RegisterDSP", error)),
+                    }
+                }
+            },
+        );
+        self.function_patches.insert(
+            "FMOD_System_RegisterCodec".to_string(),
+            quote! {
+                pub fn register_codec(&self, description: CodecDescription, priority: u32) -> Result<u32, Error> {
+                    let mut guard = Guard::default();
+                    let mut description = description.into_ffi(&mut guard)?;
+                    let mut handle = u32::default();
+                    match profile_call!("FMOD_System_RegisterCodec", unsafe { ffi::FMOD_System_RegisterCodec(self.pointer, &mut description, &mut handle, priority) }) {
+                        ffi::FMOD_OK => {
+                            std::mem::forget(guard);
+                            Ok(handle)
+                        }
+                        error => Err(err_fmod!("FMOD_System_RegisterCodec", error)),
+                    }
+                }
+            },
+        );
+        self.function_patches.insert(
+            "FMOD_System_RegisterOutput".to_string(),
+            quote! {
+                pub fn register_output(&self, description: OutputDescription) -> Result<u32, Error> {
+                    let mut guard = Guard::default();
+                    let description = description.into_ffi(&mut guard)?;
+                    let mut handle = u32::default();
+                    match profile_call!("FMOD_System_RegisterOutput", unsafe { ffi::FMOD_System_RegisterOutput(self.pointer, &description, &mut handle) }) {
+                        ffi::FMOD_OK => {
+                            std::mem::forget(guard);
+                            Ok(handle)
+                        }
+                        error => Err(err_fmod!("FMOD_System_RegisterOutput", error)),
+                    }
+                }
+            },
+        );
+        self.function_patches.insert(
+            "FMOD_Sound_Lock".to_string(),
+            quote! {
+                pub fn lock(&self, offset: u32, length: u32) -> Result<SoundLockGuard, Error> {
+                    let mut ptr1 = null_mut();
+                    let mut ptr2 = null_mut();
+                    let mut len1 = u32::default();
+                    let mut len2 = u32::default();
+                    match profile_call!("FMOD_Sound_Lock", unsafe {
+                        ffi::FMOD_Sound_Lock(
+                            self.pointer,
+                            offset,
+                            length,
+                            &mut ptr1,
+                            &mut ptr2,
+                            &mut len1,
+                            &mut len2,
+                        )
+                    }) {
+                        ffi::FMOD_OK => Ok(SoundLockGuard {
+                            sound: *self,
+                            ptr1,
+                            ptr2,
+                            len1,
+                            len2,
+                        }),
+                        error => Err(err_fmod!("FMOD_Sound_Lock", error)),
+                    }
+                }
+            },
+        );
+        self.function_patches.insert(
+            "FMOD_System_GetDriverInfo".to_string(),
+            quote! {
+                pub fn get_driver_info(&self, id: i32) -> Result<DriverInfo, Error> {
+                    let mut guid = ffi::FMOD_GUID::default();
+                    let mut systemrate = i32::default();
+                    let mut speakermode = ffi::FMOD_SPEAKERMODE::default();
+                    let mut speakermodechannels = i32::default();
+                    let name = read_string_growing(256, |buf| {
+                        match profile_call!("FMOD_System_GetDriverInfo", unsafe {
+                            ffi::FMOD_System_GetDriverInfo(
+                                self.pointer,
+                                id,
+                                buf.as_mut_ptr() as *mut c_char,
+                                buf.len() as i32,
+                                &mut guid,
+                                &mut systemrate,
+                                &mut speakermode,
+                                &mut speakermodechannels,
+                            )
+                        }) {
+                            ffi::FMOD_OK => Ok(()),
+                            error => Err(err_fmod!("FMOD_System_GetDriverInfo", error)),
+                        }
+                    })?;
+                    Ok(DriverInfo {
+                        id,
+                        name,
+                        guid: Guid::try_from(guid)?,
+                        system_rate: systemrate,
+                        speaker_mode: SpeakerMode::from(speakermode)?,
+                        speaker_mode_channels: speakermodechannels,
+                    })
+                }
+            },
+        );
+        self.function_patches.insert(
+            "FMOD_System_GetRecordDriverInfo".to_string(),
+            quote! {
+                pub fn get_record_driver_info(&self, id: i32) -> Result<RecordDriverInfo, Error> {
+                    let mut guid = ffi::FMOD_GUID::default();
+                    let mut systemrate = i32::default();
+                    let mut speakermode = ffi::FMOD_SPEAKERMODE::default();
+                    let mut speakermodechannels = i32::default();
+                    let mut state = ffi::FMOD_DRIVER_STATE::default();
+                    let name = read_string_growing(256, |buf| {
+                        match profile_call!("FMOD_System_GetRecordDriverInfo", unsafe {
+                            ffi::FMOD_System_GetRecordDriverInfo(
+                                self.pointer,
+                                id,
+                                buf.as_mut_ptr() as *mut c_char,
+                                buf.len() as i32,
+                                &mut guid,
+                                &mut systemrate,
+                                &mut speakermode,
+                                &mut speakermodechannels,
+                                &mut state,
+                            )
+                        }) {
+                            ffi::FMOD_OK => Ok(()),
+                            error => Err(err_fmod!("FMOD_System_GetRecordDriverInfo", error)),
+                        }
+                    })?;
+                    Ok(RecordDriverInfo {
+                        driver: DriverInfo {
+                            id,
+                            name,
+                            guid: Guid::try_from(guid)?,
+                            system_rate: systemrate,
+                            speaker_mode: SpeakerMode::from(speakermode)?,
+                            speaker_mode_channels: speakermodechannels,
+                        },
+                        state,
+                    })
+                }
+            },
+        );
+        self.function_patches.insert(
+            "FMOD_Sound_GetSyncPointInfo".to_string(),
+            quote! {
+                /// Returns sync point name and offset in given unit.
+                pub fn get_sync_point_info(
+                    &self,
+                    point: SyncPoint,
+                    offset_type: TimeUnit,
+                ) -> Result<(String, u32), Error> {
+                    let mut offset = 0;
+                    let name = read_string_growing(64, |buf| {
+                        match profile_call!("FMOD_Sound_GetSyncPointInfo", unsafe {
+                            ffi::FMOD_Sound_GetSyncPointInfo(
+                                self.pointer,
+                                point.as_mut_ptr(),
+                                buf.as_mut_ptr() as *mut c_char,
+                                buf.len() as i32,
+                                &mut offset,
+                                offset_type.into(),
+                            )
+                        }) {
+                            ffi::FMOD_OK => Ok(()),
+                            error => Err(err_fmod!("FMOD_Sound_GetSyncPointInfo", error)),
+                        }
+                    })?;
+                    Ok((name, offset))
+                }
+            },
+        );
+        self.function_patches.insert(
+            "FMOD_ChannelGroup_GetName".to_string(),
+            quote! {
+                pub fn get_name(&self) -> Result<String, Error> {
+                    read_string_growing(64, |buf| {
+                        match profile_call!("FMOD_ChannelGroup_GetName", unsafe {
+                            ffi::FMOD_ChannelGroup_GetName(
+                                self.pointer,
+                                buf.as_mut_ptr() as *mut c_char,
+                                buf.len() as i32,
+                            )
+                        }) {
+                            ffi::FMOD_OK => Ok(()),
+                            error => Err(err_fmod!("FMOD_ChannelGroup_GetName", error)),
+                        }
+                    })
+                }
+            },
+        );
+        self.function_patches.insert(
+            "FMOD_Studio_CommandReplay_GetCommandString".to_string(),
+            quote! {
+                pub fn get_command_string(&self, command_index: i32) -> Result<String, Error> {
+                    let mut buf = vec![0u8; 256];
+                    loop {
+                        match profile_call!("FMOD_Studio_CommandReplay_GetCommandString", unsafe {
+                            ffi::FMOD_Studio_CommandReplay_GetCommandString(
+                                self.pointer,
+                                command_index,
+                                buf.as_mut_ptr() as *mut c_char,
+                                buf.len() as i32,
+                            )
+                        }) {
+                            ffi::FMOD_OK => {
+                                let length = buf.iter().position(|&byte| byte == 0).unwrap_or(buf.len());
+                                buf.truncate(length);
+                                return Ok(into_string!(unsafe { CString::from_vec_unchecked(buf) })?);
+                            }
+                            ffi::FMOD_ERR_TRUNCATED if buf.len() < 65536 => {
+                                buf = vec![0u8; buf.len() * 2];
+                            }
+                            error => return Err(err_fmod!("FMOD_Studio_CommandReplay_GetCommandString", error)),
                         }
                     }
                 }
@@ -345,128 +712,118 @@ impl Api {
             "FMOD_Studio_Bank_GetPath".to_string(),
             quote! {
                 pub fn get_path(&self) -> Result<String, Error> {
-                    unsafe {
-                        let mut retrieved = i32::default();
-                        match ffi::FMOD_Studio_Bank_GetPath(self.pointer, null_mut(), 0, &mut retrieved) {
-                            ffi::FMOD_OK => {
-                                let mut buf = vec![0u8; retrieved as usize];
-                                match ffi::FMOD_Studio_Bank_GetPath(
+                    let mut retrieved = i32::default();
+                    match profile_call!("FMOD_Studio_Bank_GetPath", unsafe { ffi::FMOD_Studio_Bank_GetPath(self.pointer, null_mut(), 0, &mut retrieved) }) {
+                        ffi::FMOD_OK => {
+                            let mut buf = vec![0u8; retrieved as usize];
+                            match profile_call!("FMOD_Studio_Bank_GetPath", unsafe {
+                                ffi::FMOD_Studio_Bank_GetPath(
                                     self.pointer,
                                     buf.as_mut_ptr() as *mut _,
                                     retrieved,
                                     &mut retrieved
-                                ) {
-                                    ffi::FMOD_OK => Ok(
-                                        CString::from_vec_with_nul_unchecked(buf)
-                                            .into_string()
-                                            .map_err(Error::String)?
-                                    ),
-                                    error => Err(err_fmod!("FMOD_Studio_Bank_GetPath", error)),
-                                }
-                            }
-                            error => {
-                                Err(err_fmod!("FMOD_Studio_Bank_GetPath", error))
+                                )
+                            }) {
+                                ffi::FMOD_OK => Ok(
+                                    into_string!(unsafe { CString::from_vec_with_nul_unchecked(buf) })?
+                                ),
+                                error => Err(err_fmod!("FMOD_Studio_Bank_GetPath", error)),
                             }
                         }
+                        error => {
+                            Err(err_fmod!("FMOD_Studio_Bank_GetPath", error))
+                        }
                     }
                 }
             }
         );
         self.function_patches.insert("FMOD_Studio_VCA_GetPath".to_string(), quote! {
             pub fn get_path(&self) -> Result<String, Error> {
-                unsafe {
-                    let mut retrieved = i32::default();
-                    match ffi::FMOD_Studio_VCA_GetPath(self.pointer, null_mut(), 0, &mut retrieved) {
-                        ffi::FMOD_OK => {
-                            let mut buf = vec![0u8; retrieved as usize];
-                            match ffi::FMOD_Studio_VCA_GetPath(
+                let mut retrieved = i32::default();
+                match profile_call!("FMOD_Studio_VCA_GetPath", unsafe { ffi::FMOD_Studio_VCA_GetPath(self.pointer, null_mut(), 0, &mut retrieved) }) {
+                    ffi::FMOD_OK => {
+                        let mut buf = vec![0u8; retrieved as usize];
+                        match profile_call!("FMOD_Studio_VCA_GetPath", unsafe {
+                            ffi::FMOD_Studio_VCA_GetPath(
                                 self.pointer,
                                 buf.as_mut_ptr() as *mut _,
                                 retrieved,
                                 &mut retrieved,
-                            ) {
-                                ffi::FMOD_OK => Ok(CString::from_vec_with_nul_unchecked(buf)
-                                    .into_string()
-                                    .map_err(Error::String)?),
-                                error => Err(err_fmod!("FMOD_Studio_VCA_GetPath", error)),
-                            }
+                            )
+                        }) {
+                            ffi::FMOD_OK => Ok(into_string!(unsafe { CString::from_vec_with_nul_unchecked(buf) })?),
+                            error => Err(err_fmod!("FMOD_Studio_VCA_GetPath", error)),
                         }
-                        error => Err(err_fmod!("FMOD_Studio_VCA_GetPath", error)),
                     }
+                    error => Err(err_fmod!("FMOD_Studio_VCA_GetPath", error)),
                 }
             }
         });
         self.function_patches.insert("FMOD_Studio_Bus_GetPath".to_string(), quote! {
             pub fn get_path(&self) -> Result<String, Error> {
-                unsafe {
-                    let mut retrieved = i32::default();
-                    match ffi::FMOD_Studio_Bus_GetPath(self.pointer, null_mut(), 0, &mut retrieved) {
-                        ffi::FMOD_OK => {
-                            let mut buf = vec![0u8; retrieved as usize];
-                            match ffi::FMOD_Studio_Bus_GetPath(
+                let mut retrieved = i32::default();
+                match profile_call!("FMOD_Studio_Bus_GetPath", unsafe { ffi::FMOD_Studio_Bus_GetPath(self.pointer, null_mut(), 0, &mut retrieved) }) {
+                    ffi::FMOD_OK => {
+                        let mut buf = vec![0u8; retrieved as usize];
+                        match profile_call!("FMOD_Studio_Bus_GetPath", unsafe {
+                            ffi::FMOD_Studio_Bus_GetPath(
                                 self.pointer,
                                 buf.as_mut_ptr() as *mut _,
                                 retrieved,
                                 &mut retrieved,
-                            ) {
-                                ffi::FMOD_OK => Ok(CString::from_vec_with_nul_unchecked(buf)
-                                    .into_string()
-                                    .map_err(Error::String)?),
-                                error => Err(err_fmod!("FMOD_Studio_Bus_GetPath", error)),
-                            }
+                            )
+                        }) {
+                            ffi::FMOD_OK => Ok(into_string!(unsafe { CString::from_vec_with_nul_unchecked(buf) })?),
+                            error => Err(err_fmod!("FMOD_Studio_Bus_GetPath", error)),
                         }
-                        error => Err(err_fmod!("FMOD_Studio_Bus_GetPath", error)),
                     }
+                    error => Err(err_fmod!("FMOD_Studio_Bus_GetPath", error)),
                 }
             }
         });
         self.function_patches.insert("FMOD_Studio_System_LookupPath".to_string(), quote! {
-            pub fn lookup_path(&self, id: Guid) -> Result<String, Error> {
-                unsafe {
-                    let mut retrieved = i32::default();
-                    let id = id.into();
-                    match ffi::FMOD_Studio_System_LookupPath(self.pointer, &id, null_mut(), 0, &mut retrieved) {
-                        ffi::FMOD_OK => {
-                            let mut buf = vec![0u8; retrieved as usize];
-                            match ffi::FMOD_Studio_System_LookupPath(
+            pub fn lookup_path(&self, id: &Guid) -> Result<String, Error> {
+                let mut retrieved = i32::default();
+                let id = id.as_ffi();
+                match profile_call!("FMOD_Studio_System_LookupPath", unsafe { ffi::FMOD_Studio_System_LookupPath(self.pointer, id, null_mut(), 0, &mut retrieved) }) {
+                    ffi::FMOD_OK => {
+                        let mut buf = vec![0u8; retrieved as usize];
+                        match profile_call!("FMOD_Studio_System_LookupPath", unsafe {
+                            ffi::FMOD_Studio_System_LookupPath(
                                 self.pointer,
-                                &id,
+                                id,
                                 buf.as_mut_ptr() as *mut _,
                                 retrieved,
                                 &mut retrieved,
-                            ) {
-                                ffi::FMOD_OK => Ok(CString::from_vec_with_nul_unchecked(buf)
-                                    .into_string()
-                                    .map_err(Error::String)?),
-                                error => Err(err_fmod!("FMOD_Studio_System_LookupPath", error)),
-                            }
+                            )
+                        }) {
+                            ffi::FMOD_OK => Ok(into_string!(unsafe { CString::from_vec_with_nul_unchecked(buf) })?),
+                            error => Err(err_fmod!("FMOD_Studio_System_LookupPath", error)),
                         }
-                        error => Err(err_fmod!("FMOD_Studio_System_LookupPath", error)),
                     }
+                    error => Err(err_fmod!("FMOD_Studio_System_LookupPath", error)),
                 }
             }
         });
         self.function_patches.insert("FMOD_Studio_EventDescription_GetPath".to_string(), quote! {
             pub fn get_path(&self) -> Result<String, Error> {
-                unsafe {
-                    let mut retrieved = i32::default();
-                    match ffi::FMOD_Studio_EventDescription_GetPath(self.pointer, null_mut(), 0, &mut retrieved) {
-                        ffi::FMOD_OK => {
-                            let mut buf = vec![0u8; retrieved as usize];
-                            match ffi::FMOD_Studio_EventDescription_GetPath(
+                let mut retrieved = i32::default();
+                match profile_call!("FMOD_Studio_EventDescription_GetPath", unsafe { ffi::FMOD_Studio_EventDescription_GetPath(self.pointer, null_mut(), 0, &mut retrieved) }) {
+                    ffi::FMOD_OK => {
+                        let mut buf = vec![0u8; retrieved as usize];
+                        match profile_call!("FMOD_Studio_EventDescription_GetPath", unsafe {
+                            ffi::FMOD_Studio_EventDescription_GetPath(
                                 self.pointer,
                                 buf.as_mut_ptr() as *mut _,
                                 retrieved,
                                 &mut retrieved,
-                            ) {
-                                ffi::FMOD_OK => Ok(CString::from_vec_with_nul_unchecked(buf)
-                                    .into_string()
-                                    .map_err(Error::String)?),
-                                error => Err(err_fmod!("FMOD_Studio_EventDescription_GetPath", error)),
-                            }
+                            )
+                        }) {
+                            ffi::FMOD_OK => Ok(into_string!(unsafe { CString::from_vec_with_nul_unchecked(buf) })?),
+                            error => Err(err_fmod!("FMOD_Studio_EventDescription_GetPath", error)),
                         }
-                        error => Err(err_fmod!("FMOD_Studio_EventDescription_GetPath", error)),
                     }
+                    error => Err(err_fmod!("FMOD_Studio_EventDescription_GetPath", error)),
                 }
             }
         });
@@ -474,9 +831,7 @@ impl Api {
             "FMOD_Studio_System_IsValid".to_string(),
             quote! {
                 pub fn is_valid(&self) -> bool {
-                    unsafe {
-                        to_bool!(ffi::FMOD_Studio_System_IsValid(self.pointer))
-                    }
+                    to_bool!(unsafe { ffi::FMOD_Studio_System_IsValid(self.pointer) })
                 }
             },
         );
@@ -484,9 +839,7 @@ impl Api {
             "FMOD_Studio_EventDescription_IsValid".to_string(),
             quote! {
                 pub fn is_valid(&self) -> bool {
-                    unsafe {
-                        to_bool!(ffi::FMOD_Studio_EventDescription_IsValid(self.pointer))
-                    }
+                    to_bool!(unsafe { ffi::FMOD_Studio_EventDescription_IsValid(self.pointer) })
                 }
             },
         );
@@ -494,9 +847,7 @@ impl Api {
             "FMOD_Studio_EventInstance_IsValid".to_string(),
             quote! {
                 pub fn is_valid(&self) -> bool {
-                    unsafe {
-                        to_bool!(ffi::FMOD_Studio_EventInstance_IsValid(self.pointer))
-                    }
+                    to_bool!(unsafe { ffi::FMOD_Studio_EventInstance_IsValid(self.pointer) })
                 }
             },
         );
@@ -504,9 +855,7 @@ impl Api {
             "FMOD_Studio_Bus_IsValid".to_string(),
             quote! {
                 pub fn is_valid(&self) -> bool {
-                    unsafe {
-                        to_bool!(ffi::FMOD_Studio_Bus_IsValid(self.pointer))
-                    }
+                    to_bool!(unsafe { ffi::FMOD_Studio_Bus_IsValid(self.pointer) })
                 }
             },
         );
@@ -514,9 +863,7 @@ impl Api {
             "FMOD_Studio_VCA_IsValid".to_string(),
             quote! {
                 pub fn is_valid(&self) -> bool {
-                    unsafe {
-                        to_bool!(ffi::FMOD_Studio_VCA_IsValid(self.pointer))
-                    }
+                    to_bool!(unsafe { ffi::FMOD_Studio_VCA_IsValid(self.pointer) })
                 }
             },
         );
@@ -524,9 +871,7 @@ impl Api {
             "FMOD_Studio_Bank_IsValid".to_string(),
             quote! {
                 pub fn is_valid(&self) -> bool {
-                    unsafe {
-                        to_bool!(ffi::FMOD_Studio_Bank_IsValid(self.pointer))
-                    }
+                    to_bool!(unsafe { ffi::FMOD_Studio_Bank_IsValid(self.pointer) })
                 }
             },
         );
@@ -534,9 +879,7 @@ impl Api {
             "FMOD_Studio_CommandReplay_IsValid".to_string(),
             quote! {
                 pub fn is_valid(&self) -> bool {
-                    unsafe {
-                        to_bool!(ffi::FMOD_Studio_CommandReplay_IsValid(self.pointer))
-                    }
+                    to_bool!(unsafe { ffi::FMOD_Studio_CommandReplay_IsValid(self.pointer) })
                 }
             },
         );
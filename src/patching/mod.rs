@@ -3,14 +3,33 @@ use crate::models::Api;
 pub mod dictionary;
 mod fields;
 mod functions;
+mod opaque_types;
 mod post_processing;
 mod structures;
 
 impl Api {
     pub fn patch_all(&mut self) {
         self.apply_postprocessing();
+        self.patch_field_directions();
+        self.patch_field_roles();
+        self.patch_code();
+    }
+
+    /// Hand-written code calls generated methods by their assigned names,
+    /// so it is patched again once method names are assigned.
+    pub fn repatch_code(&mut self) {
+        self.function_patches.clear();
+        self.opaque_type_patches.clear();
+        self.structure_patches.clear();
+        self.structure_derives.clear();
+        self.patch_code();
+    }
+
+    fn patch_code(&mut self) {
         self.patch_functions();
+        self.patch_opaque_types();
         self.patch_structures();
+        self.patch_callback_structures();
         self.patch_structure_derives();
     }
 }
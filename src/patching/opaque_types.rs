@@ -0,0 +1,2180 @@
+use crate::Api;
+use quote::__private::TokenStream;
+
+const DSP_PARAMETER_DATA: &[(&str, &str)] = &[
+    (
+        "FMOD_DSP_PARAMETER_OVERALLGAIN",
+        "FMOD_DSP_PARAMETER_DATA_TYPE_OVERALLGAIN",
+    ),
+    (
+        "FMOD_DSP_PARAMETER_3DATTRIBUTES",
+        "FMOD_DSP_PARAMETER_DATA_TYPE_3DATTRIBUTES",
+    ),
+    (
+        "FMOD_DSP_PARAMETER_SIDECHAIN",
+        "FMOD_DSP_PARAMETER_DATA_TYPE_SIDECHAIN",
+    ),
+    ("FMOD_DSP_PARAMETER_FFT", "FMOD_DSP_PARAMETER_DATA_TYPE_FFT"),
+    (
+        "FMOD_DSP_PARAMETER_3DATTRIBUTES_MULTI",
+        "FMOD_DSP_PARAMETER_DATA_TYPE_3DATTRIBUTES_MULTI",
+    ),
+    (
+        "FMOD_DSP_PARAMETER_ATTENUATION_RANGE",
+        "FMOD_DSP_PARAMETER_DATA_TYPE_ATTENUATION_RANGE",
+    ),
+    // since FMOD 2.03
+    (
+        "FMOD_DSP_PARAMETER_DYNAMIC_RESPONSE",
+        "FMOD_DSP_PARAMETER_DATA_TYPE_DYNAMIC_RESPONSE",
+    ),
+];
+
+/// Hot data parameters read every frame, borrowed from DSP memory instead of converted.
+const DSP_PARAMETER_VIEWS: &[(&str, &str)] = &[("FMOD_DSP_PARAMETER_FFT", "FftView")];
+
+fn generate_view_accessors(structure: &str) -> TokenStream {
+    match structure {
+        "FMOD_DSP_PARAMETER_FFT" => quote! {
+            pub fn length(&self) -> usize {
+                self.raw.length.max(0) as usize
+            }
+
+            pub fn channels(&self) -> usize {
+                (self.raw.numchannels.max(0) as usize).min(self.raw.spectrum.len())
+            }
+
+            pub fn spectrum(&self, channel: usize) -> &'a [f32] {
+                let data = match self.raw.spectrum.get(channel) {
+                    Some(data) if channel < self.channels() && !data.is_null() => *data,
+                    _ => return &[],
+                };
+                unsafe { slice::from_raw_parts(data, self.length()) }
+            }
+        },
+        _ => quote! {},
+    }
+}
+
+impl Api {
+    fn patch_opaque_type(&mut self, key: &str, helpers: TokenStream) {
+        self.opaque_type_patches
+            .entry(key.to_string())
+            .or_default()
+            .extend(helpers);
+    }
+
+    fn patch_dsp_parameter_data(&mut self) {
+        let mut implementations = vec![];
+        for (structure, data_type) in DSP_PARAMETER_DATA {
+            if !self.is_structure(structure) {
+                continue;
+            }
+            let name = format_ident!("{}", Api::patch_structure_name(structure));
+            let raw = format_ident!("{}", structure);
+            let data_type = format_ident!("{}", data_type);
+            implementations.push(quote! {
+                impl DspParameterData for #name {
+                    const DATA_TYPE: ffi::FMOD_DSP_PARAMETER_DATA_TYPE = ffi::#data_type;
+                    type Raw = ffi::#raw;
+                    fn from_raw(raw: Self::Raw) -> Result<Self, Error> {
+                        Self::try_from(raw)
+                    }
+                    fn into_raw(self) -> Self::Raw {
+                        self.into()
+                    }
+                }
+            });
+        }
+        let get_data_parameter_index = self.method_ident("FMOD_DSP_GetDataParameterIndex");
+        let get_parameter_data = self.method_ident("FMOD_DSP_GetParameterData");
+        let get_num_inputs = self.method_ident("FMOD_DSP_GetNumInputs");
+        let get_input = self.method_ident("FMOD_DSP_GetInput");
+        let get_num_outputs = self.method_ident("FMOD_DSP_GetNumOutputs");
+        let get_output = self.method_ident("FMOD_DSP_GetOutput");
+        self.patch_opaque_type(
+            "FMOD_DSP",
+            quote! {
+                pub trait DspParameterData: Sized {
+                    const DATA_TYPE: ffi::FMOD_DSP_PARAMETER_DATA_TYPE;
+                    type Raw: Copy;
+                    fn from_raw(raw: Self::Raw) -> Result<Self, Error>;
+                    fn into_raw(self) -> Self::Raw;
+                }
+
+                #(#implementations)*
+
+                impl Dsp {
+                    pub fn get_data_parameter<T: DspParameterData>(&self) -> Result<T, Error> {
+                        let index = self.#get_data_parameter_index(T::DATA_TYPE)?;
+                        let (data, length, _) = self.#get_parameter_data(index, 0)?;
+                        if data.is_null() || (length as usize) < size_of::<T::Raw>() {
+                            return Err(Error::InvalidParameter {
+                                function: "FMOD_DSP_GetParameterData",
+                                reason: "data is missing or smaller than parameter type",
+                            });
+                        }
+                        T::from_raw(unsafe { *(data as *const T::Raw) })
+                    }
+
+                    pub fn set_data_parameter<T: DspParameterData>(&self, value: T) -> Result<(), Error> {
+                        let index = self.#get_data_parameter_index(T::DATA_TYPE)?;
+                        let mut raw = value.into_raw();
+                        self.set_parameter_data(
+                            index,
+                            &mut raw as *mut T::Raw as *mut c_void,
+                            size_of::<T::Raw>() as u32,
+                        )
+                    }
+
+                    /// Walks DSPs connected to inputs of this unit with their connections.
+                    pub fn inputs(&self) -> Result<impl Iterator<Item = Result<(Dsp, DspConnection), Error>>, Error> {
+                        let this = *self;
+                        let count = this.#get_num_inputs()?;
+                        Ok((0..count).map(move |index| this.#get_input(index)))
+                    }
+
+                    /// Walks DSPs this unit outputs to with their connections.
+                    pub fn outputs(&self) -> Result<impl Iterator<Item = Result<(Dsp, DspConnection), Error>>, Error> {
+                        let this = *self;
+                        let count = this.#get_num_outputs()?;
+                        Ok((0..count).map(move |index| this.#get_output(index)))
+                    }
+                }
+            },
+        );
+    }
+
+    fn patch_dsp_parameter_views(&mut self) {
+        let mut views = vec![];
+        for (structure, view) in DSP_PARAMETER_VIEWS {
+            let data_type = DSP_PARAMETER_DATA
+                .iter()
+                .find(|(name, _)| name == structure)
+                .map(|(_, data_type)| format_ident!("{}", data_type));
+            let data_type = match data_type {
+                Some(data_type) if self.is_structure(structure) => data_type,
+                _ => continue,
+            };
+            let raw = format_ident!("{}", structure);
+            let name = format_ident!("{}", view);
+            let accessors = generate_view_accessors(structure);
+            views.push(quote! {
+                /// Borrowed view of data parameter, valid until next mixer update.
+                #[derive(Debug, Clone, Copy)]
+                pub struct #name<'a> {
+                    raw: &'a ffi::#raw,
+                }
+
+                impl<'a> DspParameterView<'a> for #name<'a> {
+                    const DATA_TYPE: ffi::FMOD_DSP_PARAMETER_DATA_TYPE = ffi::#data_type;
+                    type Raw = ffi::#raw;
+                    fn from_raw(raw: &'a Self::Raw) -> Self {
+                        Self { raw }
+                    }
+                }
+
+                impl<'a> #name<'a> {
+                    pub fn raw(&self) -> &'a ffi::#raw {
+                        self.raw
+                    }
+
+                    #accessors
+                }
+            });
+        }
+        if views.is_empty() {
+            return;
+        }
+        let get_data_parameter_index = self.method_ident("FMOD_DSP_GetDataParameterIndex");
+        let get_parameter_data = self.method_ident("FMOD_DSP_GetParameterData");
+        self.patch_opaque_type(
+            "FMOD_DSP",
+            quote! {
+                pub trait DspParameterView<'a>: Sized {
+                    const DATA_TYPE: ffi::FMOD_DSP_PARAMETER_DATA_TYPE;
+                    type Raw: 'a;
+                    fn from_raw(raw: &'a Self::Raw) -> Self;
+                }
+
+                #(#views)*
+
+                impl Dsp {
+                    /// Reads data parameter in place without copying, e.g. FFT spectrum every frame.
+                    pub fn view_data_parameter<'a, T: DspParameterView<'a>>(&'a self) -> Result<T, Error> {
+                        let index = self.#get_data_parameter_index(T::DATA_TYPE)?;
+                        let (data, length, _) = self.#get_parameter_data(index, 0)?;
+                        if data.is_null() || (length as usize) < size_of::<T::Raw>() {
+                            return Err(Error::InvalidParameter {
+                                function: "FMOD_DSP_GetParameterData",
+                                reason: "data is missing or smaller than parameter type",
+                            });
+                        }
+                        Ok(T::from_raw(unsafe { &*(data as *const T::Raw) }))
+                    }
+                }
+            },
+        );
+    }
+
+    fn patch_memory_allocator(&mut self) {
+        self.patch_opaque_type(
+            "FMOD_SYSTEM",
+            quote! {
+                /// FMOD doesn't pass allocation size on free, so it is stored before each block.
+                const MEMORY_HEADER: usize = 16;
+
+                /// Allocator installed in FMOD, reset when FMOD rejects it.
+                static MEMORY_ALLOCATOR: std::sync::RwLock<Option<&'static (dyn std::alloc::GlobalAlloc + Sync)>> =
+                    std::sync::RwLock::new(None);
+
+                fn memory_allocator() -> Option<&'static (dyn std::alloc::GlobalAlloc + Sync)> {
+                    MEMORY_ALLOCATOR.read().ok().and_then(|allocator| *allocator)
+                }
+
+                /// Allocator forwarding to Rust global allocator.
+                pub struct RustAllocator;
+
+                unsafe impl std::alloc::GlobalAlloc for RustAllocator {
+                    unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+                        unsafe { std::alloc::alloc(layout) }
+                    }
+
+                    unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+                        unsafe { std::alloc::dealloc(ptr, layout) }
+                    }
+
+                    unsafe fn realloc(&self, ptr: *mut u8, layout: std::alloc::Layout, new_size: usize) -> *mut u8 {
+                        unsafe { std::alloc::realloc(ptr, layout, new_size) }
+                    }
+                }
+
+                fn memory_block_layout(size: usize) -> Option<std::alloc::Layout> {
+                    std::alloc::Layout::from_size_align(size.checked_add(MEMORY_HEADER)?, MEMORY_HEADER).ok()
+                }
+
+                unsafe fn memory_block_commit(block: *mut u8, size: usize) -> *mut c_void {
+                    if block.is_null() {
+                        return null_mut();
+                    }
+                    unsafe {
+                        *(block as *mut usize) = size;
+                        block.add(MEMORY_HEADER) as *mut c_void
+                    }
+                }
+
+                unsafe extern "C" fn memory_alloc(
+                    size: u32,
+                    _kind: ffi::FMOD_MEMORY_TYPE,
+                    _source: *const c_char,
+                ) -> *mut c_void {
+                    match (memory_allocator(), memory_block_layout(size as usize)) {
+                        (Some(allocator), Some(layout)) => unsafe {
+                            memory_block_commit(allocator.alloc(layout), size as usize)
+                        },
+                        _ => null_mut(),
+                    }
+                }
+
+                unsafe extern "C" fn memory_realloc(
+                    ptr: *mut c_void,
+                    size: u32,
+                    kind: ffi::FMOD_MEMORY_TYPE,
+                    source: *const c_char,
+                ) -> *mut c_void {
+                    if ptr.is_null() {
+                        return unsafe { memory_alloc(size, kind, source) };
+                    }
+                    let block = unsafe { (ptr as *mut u8).sub(MEMORY_HEADER) };
+                    let stored = unsafe { *(block as *const usize) };
+                    match (memory_allocator(), memory_block_layout(stored)) {
+                        (Some(allocator), Some(layout)) => unsafe {
+                            memory_block_commit(
+                                allocator.realloc(block, layout, size as usize + MEMORY_HEADER),
+                                size as usize,
+                            )
+                        },
+                        _ => null_mut(),
+                    }
+                }
+
+                unsafe extern "C" fn memory_free(
+                    ptr: *mut c_void,
+                    _kind: ffi::FMOD_MEMORY_TYPE,
+                    _source: *const c_char,
+                ) {
+                    if ptr.is_null() {
+                        return;
+                    }
+                    let block = unsafe { (ptr as *mut u8).sub(MEMORY_HEADER) };
+                    let stored = unsafe { *(block as *const usize) };
+                    if let (Some(allocator), Some(layout)) = (memory_allocator(), memory_block_layout(stored)) {
+                        unsafe { allocator.dealloc(block, layout) };
+                    }
+                }
+
+                /// Memory management of FMOD, must be configured before any system is created.
+                pub struct Memory;
+
+                impl Memory {
+                    /// Makes FMOD allocate through Rust global allocator.
+                    pub fn use_rust_allocator() -> Result<(), Error> {
+                        Self::use_allocator(&RustAllocator)
+                    }
+
+                    /// Makes FMOD allocate through given allocator, it can be set only once per process
+                    /// unless FMOD rejects it.
+                    pub fn use_allocator(allocator: &'static (dyn std::alloc::GlobalAlloc + Sync)) -> Result<(), Error> {
+                        match MEMORY_ALLOCATOR.write() {
+                            Ok(mut installed) if installed.is_none() => *installed = Some(allocator),
+                            _ => return Err(err_fmod!("FMOD_Memory_Initialize", ffi::FMOD_ERR_INITIALIZED)),
+                        }
+                        match profile_call!("FMOD_Memory_Initialize", unsafe {
+                            ffi::FMOD_Memory_Initialize(
+                                null_mut(),
+                                0,
+                                Some(memory_alloc),
+                                Some(memory_realloc),
+                                Some(memory_free),
+                                ffi::FMOD_MEMORY_ALL,
+                            )
+                        }) {
+                            ffi::FMOD_OK => Ok(()),
+                            error => {
+                                if let Ok(mut installed) = MEMORY_ALLOCATOR.write() {
+                                    *installed = None;
+                                }
+                                Err(err_fmod!("FMOD_Memory_Initialize", error))
+                            }
+                        }
+                    }
+
+                    /// Makes FMOD allocate only from fixed pool, length must be multiple of 512.
+                    pub fn use_pool(pool: &'static mut [u8]) -> Result<(), Error> {
+                        match profile_call!("FMOD_Memory_Initialize", unsafe {
+                            ffi::FMOD_Memory_Initialize(
+                                pool.as_mut_ptr() as *mut c_void,
+                                pool.len() as i32,
+                                None,
+                                None,
+                                None,
+                                ffi::FMOD_MEMORY_ALL,
+                            )
+                        }) {
+                            ffi::FMOD_OK => Ok(()),
+                            error => Err(err_fmod!("FMOD_Memory_Initialize", error)),
+                        }
+                    }
+
+                    /// Returns currently allocated and maximum allocated bytes.
+                    pub fn get_stats(blocking: bool) -> Result<(i32, i32), Error> {
+                        let mut current_allocated = i32::default();
+                        let mut max_allocated = i32::default();
+                        match profile_call!("FMOD_Memory_GetStats", unsafe {
+                            ffi::FMOD_Memory_GetStats(&mut current_allocated, &mut max_allocated, from_bool!(blocking))
+                        }) {
+                            ffi::FMOD_OK => Ok((current_allocated, max_allocated)),
+                            error => Err(err_fmod!("FMOD_Memory_GetStats", error)),
+                        }
+                    }
+                }
+            },
+        );
+    }
+
+    fn patch_thread_attributes(&mut self) {
+        let flags = [
+            "FMOD_THREAD_AFFINITY",
+            "FMOD_THREAD_PRIORITY",
+            "FMOD_THREAD_STACK_SIZE",
+        ];
+        let available = flags
+            .iter()
+            .all(|key| self.flags.iter().any(|flags| &flags.name == key))
+            && self
+                .enumerations
+                .iter()
+                .any(|enumeration| enumeration.name == "FMOD_THREAD_TYPE");
+        if !available {
+            return;
+        }
+        self.patch_opaque_type(
+            "FMOD_SYSTEM",
+            quote! {
+                /// Scheduling attributes of FMOD threads, must be applied before system is created.
+                #[derive(Debug, Clone, Copy, PartialEq)]
+                pub struct ThreadConfig {
+                    pub affinity: ThreadAffinity,
+                    pub priority: ThreadPriority,
+                    pub stack_size: ThreadStackSize,
+                }
+
+                impl Default for ThreadConfig {
+                    fn default() -> Self {
+                        ThreadConfig {
+                            affinity: ThreadAffinity::GROUP_DEFAULT,
+                            priority: ThreadPriority::DEFAULT,
+                            stack_size: ThreadStackSize::DEFAULT,
+                        }
+                    }
+                }
+
+                impl ThreadConfig {
+                    pub fn with_affinity(mut self, affinity: ThreadAffinity) -> Self {
+                        self.affinity = affinity;
+                        self
+                    }
+
+                    pub fn with_priority(mut self, priority: ThreadPriority) -> Self {
+                        self.priority = priority;
+                        self
+                    }
+
+                    /// Stack size in bytes.
+                    pub fn with_stack_size(mut self, bytes: u32) -> Self {
+                        self.stack_size = ThreadStackSize(bytes as _);
+                        self
+                    }
+
+                    #[cfg(not(target_family = "wasm"))]
+                    pub fn apply(&self, kind: ThreadType) -> Result<(), Error> {
+                        match profile_call!("FMOD_Thread_SetAttributes", unsafe {
+                            ffi::FMOD_Thread_SetAttributes(
+                                kind.into(),
+                                self.affinity.into(),
+                                self.priority.into(),
+                                self.stack_size.into(),
+                            )
+                        }) {
+                            ffi::FMOD_OK => Ok(()),
+                            error => Err(err_fmod!("FMOD_Thread_SetAttributes", error)),
+                        }
+                    }
+                }
+            },
+        );
+    }
+
+    pub fn patch_opaque_types(&mut self) {
+        self.patch_dsp_parameter_data();
+        self.patch_dsp_parameter_views();
+        self.patch_memory_allocator();
+        self.patch_thread_attributes();
+        let get_num_drivers = self.method_ident("FMOD_System_GetNumDrivers");
+        let get_driver_info = self.method_ident("FMOD_System_GetDriverInfo");
+        let get_output = self.method_ident("FMOD_System_GetOutput");
+        let get_driver = self.method_ident("FMOD_System_GetDriver");
+        let get_speaker_position = self.method_ident("FMOD_System_GetSpeakerPosition");
+        let get_record_driver_info = self.method_ident("FMOD_System_GetRecordDriverInfo");
+        self.patch_opaque_type(
+            "FMOD_SYSTEM",
+            quote! {
+                #[derive(Debug, Clone)]
+                pub struct DriverInfo {
+                    pub id: i32,
+                    pub name: String,
+                    pub guid: Guid,
+                    pub system_rate: i32,
+                    pub speaker_mode: SpeakerMode,
+                    pub speaker_mode_channels: i32,
+                }
+
+                /// Output plugin in use with its drivers.
+                #[derive(Debug, Clone)]
+                pub struct OutputInfo {
+                    pub output: OutputType,
+                    pub driver: i32,
+                    pub drivers: Vec<DriverInfo>,
+                }
+
+                #[derive(Debug, Clone)]
+                pub struct RecordDriverInfo {
+                    pub driver: DriverInfo,
+                    pub state: ffi::FMOD_DRIVER_STATE,
+                }
+
+                #[derive(Debug, Clone, Copy)]
+                pub enum SoundSource<'a> {
+                    File(&'a str),
+                    Stream(&'a str),
+                    Memory(&'a [u8]),
+                }
+
+                impl<'a> From<&'a str> for SoundSource<'a> {
+                    fn from(path: &'a str) -> Self {
+                        SoundSource::File(path)
+                    }
+                }
+
+                impl<'a> From<&'a [u8]> for SoundSource<'a> {
+                    fn from(data: &'a [u8]) -> Self {
+                        SoundSource::Memory(data)
+                    }
+                }
+
+                impl System {
+                    pub fn drivers(&self) -> Result<Vec<DriverInfo>, Error> {
+                        (0..self.#get_num_drivers()?)
+                            .map(|id| self.#get_driver_info(id))
+                            .collect()
+                    }
+
+                    pub fn output_info(&self) -> Result<OutputInfo, Error> {
+                        Ok(OutputInfo {
+                            output: self.#get_output()?,
+                            driver: self.#get_driver()?,
+                            drivers: self.drivers()?,
+                        })
+                    }
+
+                    /// Switches output type and returns drivers it provides.
+                    pub fn use_output(&self, output: OutputType) -> Result<Vec<DriverInfo>, Error> {
+                        self.set_output(output)?;
+                        self.drivers()
+                    }
+
+                    /// Selects driver by exact name, falling back to case insensitive match.
+                    pub fn select_driver_by_name(&self, name: &str) -> Result<DriverInfo, Error> {
+                        let drivers = self.drivers()?;
+                        let driver = drivers
+                            .iter()
+                            .find(|driver| driver.name == name)
+                            .or_else(|| drivers.iter().find(|driver| driver.name.eq_ignore_ascii_case(name)))
+                            .ok_or_else(|| Error::DriverNotFound(name.to_string()))?;
+                        self.set_driver(driver.id)?;
+                        Ok(driver.clone())
+                    }
+
+                    /// Speakers of software format speaker mode with their positions,
+                    /// raw speaker mode has no fixed layout and gives empty list.
+                    pub fn speaker_layout(&self) -> Result<Vec<(Speaker, f32, f32, bool)>, Error> {
+                        let mut sample_rate = 0;
+                        let mut speaker_mode = ffi::FMOD_SPEAKERMODE::default();
+                        let mut raw_speakers = 0;
+                        match profile_call!("FMOD_System_GetSoftwareFormat", unsafe {
+                            ffi::FMOD_System_GetSoftwareFormat(self.pointer, &mut sample_rate, &mut speaker_mode, &mut raw_speakers)
+                        }) {
+                            ffi::FMOD_OK => {}
+                            error => return Err(err_fmod!("FMOD_System_GetSoftwareFormat", error)),
+                        }
+                        let mask = SpeakerMode::from(speaker_mode)?.channel_mask().unwrap_or_default();
+                        mask.speakers()
+                            .into_iter()
+                            .map(|speaker| {
+                                let (x, y, active) = self.#get_speaker_position(speaker)?;
+                                Ok((speaker, x, y, active))
+                            })
+                            .collect()
+                    }
+
+                    pub fn record_drivers(&self) -> Result<Vec<RecordDriverInfo>, Error> {
+                        let mut count = 0;
+                        let mut connected = 0;
+                        match profile_call!("FMOD_System_GetRecordNumDrivers", unsafe {
+                            ffi::FMOD_System_GetRecordNumDrivers(self.pointer, &mut count, &mut connected)
+                        }) {
+                            ffi::FMOD_OK => {}
+                            error => return Err(err_fmod!("FMOD_System_GetRecordNumDrivers", error)),
+                        }
+                        (0..count)
+                            .map(|id| self.#get_record_driver_info(id))
+                            .collect()
+                    }
+                }
+            },
+        );
+        let get_num_groups = self.method_ident("FMOD_ChannelGroup_GetNumGroups");
+        let get_group = self.method_ident("FMOD_ChannelGroup_GetGroup");
+        self.patch_opaque_type(
+            "FMOD_CHANNELGROUP",
+            quote! {
+                impl ChannelGroup {
+                    /// Walks groups attached directly to this group.
+                    pub fn children(&self) -> Result<impl Iterator<Item = Result<ChannelGroup, Error>>, Error> {
+                        let this = *self;
+                        let count = this.#get_num_groups()?;
+                        Ok((0..count).map(move |index| this.#get_group(index)))
+                    }
+
+                    /// Returns group this one is attached to, master group has none.
+                    pub fn parent(&self) -> Result<Option<ChannelGroup>, Error> {
+                        let mut group = null_mut();
+                        match profile_call!("FMOD_ChannelGroup_GetParentGroup", unsafe { ffi::FMOD_ChannelGroup_GetParentGroup(self.pointer, &mut group) }) {
+                            ffi::FMOD_OK => Ok(ChannelGroup::try_from_ptr(group)),
+                            error => Err(err_fmod!("FMOD_ChannelGroup_GetParentGroup", error)),
+                        }
+                    }
+                }
+            },
+        );
+        self.patch_opaque_type(
+            "FMOD_SOUND",
+            quote! {
+                pub struct SoundLockGuard {
+                    sound: Sound,
+                    ptr1: *mut c_void,
+                    ptr2: *mut c_void,
+                    len1: u32,
+                    len2: u32,
+                }
+
+                impl SoundLockGuard {
+                    pub fn first(&mut self) -> &mut [u8] {
+                        unsafe { to_slice_mut(self.ptr1, self.len1) }
+                    }
+
+                    pub fn second(&mut self) -> &mut [u8] {
+                        unsafe { to_slice_mut(self.ptr2, self.len2) }
+                    }
+                }
+
+                impl Drop for SoundLockGuard {
+                    fn drop(&mut self) {
+                        let _ = self.sound.unlock(self.ptr1, self.ptr2, self.len1, self.len2);
+                    }
+                }
+
+                unsafe fn to_slice_mut<'a>(pointer: *mut c_void, length: u32) -> &'a mut [u8] {
+                    if pointer.is_null() {
+                        &mut []
+                    } else {
+                        unsafe { slice::from_raw_parts_mut(pointer as *mut u8, length as usize) }
+                    }
+                }
+            },
+        );
+        let get_record_driver_info = self.method_ident("FMOD_System_GetRecordDriverInfo");
+        self.patch_opaque_type(
+            "FMOD_SYSTEM",
+            quote! {
+                pub trait RecordSample: Copy + Default {
+                    const FORMAT: ffi::FMOD_SOUND_FORMAT;
+                }
+
+                impl RecordSample for i16 {
+                    const FORMAT: ffi::FMOD_SOUND_FORMAT = ffi::FMOD_SOUND_FORMAT_PCM16;
+                }
+
+                impl RecordSample for f32 {
+                    const FORMAT: ffi::FMOD_SOUND_FORMAT = ffi::FMOD_SOUND_FORMAT_PCMFLOAT;
+                }
+
+                /// Microphone capture into looping user sound read as ring buffer.
+                pub struct Recorder<T: RecordSample> {
+                    system: System,
+                    sound: Sound,
+                    driver: i32,
+                    channels: usize,
+                    rate: i32,
+                    length: u32,
+                    position: u32,
+                    samples: Vec<T>,
+                }
+
+                impl<T: RecordSample> Recorder<T> {
+                    pub fn start(system: System, driver: i32, seconds: f32) -> Result<Self, Error> {
+                        let info = system.#get_record_driver_info(driver)?.driver;
+                        let channels = info.speaker_mode_channels.max(1);
+                        let length = ((info.system_rate as f32 * seconds) as u32).max(1);
+                        let mut exinfo = ffi::FMOD_CREATESOUNDEXINFO::default();
+                        exinfo.cbsize = size_of::<ffi::FMOD_CREATESOUNDEXINFO>() as i32;
+                        exinfo.numchannels = channels;
+                        exinfo.format = T::FORMAT;
+                        exinfo.defaultfrequency = info.system_rate;
+                        exinfo.length = length * channels as u32 * size_of::<T>() as u32;
+                        let mut sound = null_mut();
+                        let sound = match profile_call!("FMOD_System_CreateSound", unsafe {
+                            ffi::FMOD_System_CreateSound(
+                                system.as_mut_ptr(),
+                                null(),
+                                ffi::FMOD_OPENUSER | ffi::FMOD_LOOP_NORMAL,
+                                &mut exinfo,
+                                &mut sound,
+                            )
+                        }) {
+                            ffi::FMOD_OK => Sound::from(sound),
+                            error => return Err(err_fmod!("FMOD_System_CreateSound", error)),
+                        };
+                        if let Err(error) = system.record_start(driver, sound, true) {
+                            let _ = sound.release();
+                            return Err(error);
+                        }
+                        Ok(Recorder {
+                            system,
+                            sound,
+                            driver,
+                            channels: channels as usize,
+                            rate: info.system_rate,
+                            length,
+                            position: 0,
+                            samples: vec![],
+                        })
+                    }
+
+                    pub fn channels(&self) -> usize {
+                        self.channels
+                    }
+
+                    pub fn sample_rate(&self) -> i32 {
+                        self.rate
+                    }
+
+                    /// Returns interleaved samples recorded since previous read.
+                    pub fn read(&mut self) -> Result<&[T], Error> {
+                        let mut position = 0;
+                        match profile_call!("FMOD_System_GetRecordPosition", unsafe {
+                            ffi::FMOD_System_GetRecordPosition(self.system.as_mut_ptr(), self.driver, &mut position)
+                        }) {
+                            ffi::FMOD_OK => {}
+                            error => return Err(err_fmod!("FMOD_System_GetRecordPosition", error)),
+                        }
+                        self.samples.clear();
+                        let available = (position + self.length - self.position) % self.length;
+                        if available > 0 {
+                            let frame = (self.channels * size_of::<T>()) as u32;
+                            let mut guard = self.sound.lock(self.position * frame, available * frame)?;
+                            Self::extend(&mut self.samples, guard.first());
+                            Self::extend(&mut self.samples, guard.second());
+                        }
+                        self.position = position;
+                        Ok(&self.samples)
+                    }
+
+                    fn extend(samples: &mut Vec<T>, bytes: &[u8]) {
+                        samples.extend(bytes.chunks_exact(size_of::<T>()).map(|sample| unsafe {
+                            std::ptr::read_unaligned(sample.as_ptr() as *const T)
+                        }));
+                    }
+
+                    pub fn stop(self) -> Result<(), Error> {
+                        self.system.record_stop(self.driver)?;
+                        self.sound.release()
+                    }
+                }
+            },
+        );
+        let get_num_sync_points = self.method_ident("FMOD_Sound_GetNumSyncPoints");
+        let get_sync_point = self.method_ident("FMOD_Sound_GetSyncPoint");
+        let get_sync_point_info = self.method_ident("FMOD_Sound_GetSyncPointInfo");
+        self.patch_opaque_type(
+            "FMOD_SOUND",
+            quote! {
+                /// Sync point of sound with its name and offset, e.g. beat marker of music track.
+                #[derive(Debug, Clone)]
+                pub struct SyncPointMarker {
+                    pub point: SyncPoint,
+                    pub name: String,
+                    pub offset: u32,
+                }
+
+                impl Sound {
+                    /// Sync points in order stored by sound, offsets are in given unit.
+                    pub fn sync_point_markers(&self, offset_type: TimeUnit) -> Result<Vec<SyncPointMarker>, Error> {
+                        let count = self.#get_num_sync_points()?;
+                        let mut markers = Vec::with_capacity(count.max(0) as usize);
+                        for index in 0..count {
+                            let point = self.#get_sync_point(index)?;
+                            let (name, offset) = self.#get_sync_point_info(point, offset_type)?;
+                            markers.push(SyncPointMarker { point, name, offset });
+                        }
+                        Ok(markers)
+                    }
+                }
+            },
+        );
+        self.patch_opaque_type(
+            "FMOD_SOUND",
+            quote! {
+                /// Decoded PCM data of sound as normalized interleaved samples, read errors are yielded once and end iteration.
+                pub struct SampleReader {
+                    sound: Sound,
+                    format: ffi::FMOD_SOUND_FORMAT,
+                    channels: usize,
+                    buffer: Vec<u8>,
+                    offset: usize,
+                    finished: bool,
+                }
+
+                impl SampleReader {
+                    pub fn channels(&self) -> usize {
+                        self.channels
+                    }
+
+                    fn sample_size(&self) -> usize {
+                        match self.format {
+                            ffi::FMOD_SOUND_FORMAT_PCM8 => 1,
+                            ffi::FMOD_SOUND_FORMAT_PCM16 => 2,
+                            ffi::FMOD_SOUND_FORMAT_PCM24 => 3,
+                            _ => 4,
+                        }
+                    }
+
+                    fn decode(&self, bytes: &[u8]) -> f32 {
+                        match self.format {
+                            ffi::FMOD_SOUND_FORMAT_PCM8 => bytes[0] as i8 as f32 / 128.0,
+                            ffi::FMOD_SOUND_FORMAT_PCM16 => {
+                                i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0
+                            }
+                            ffi::FMOD_SOUND_FORMAT_PCM24 => {
+                                (i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8) as f32 / 8388608.0
+                            }
+                            ffi::FMOD_SOUND_FORMAT_PCM32 => {
+                                i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32 / 2147483648.0
+                            }
+                            _ => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+                        }
+                    }
+
+                    fn fill(&mut self) -> Result<(), Error> {
+                        let size = self.sample_size() * self.channels;
+                        self.buffer.resize(size * 1024, 0);
+                        self.offset = 0;
+                        let mut read = 0;
+                        let result = profile_call!("FMOD_Sound_ReadData", unsafe {
+                            ffi::FMOD_Sound_ReadData(
+                                self.sound.as_mut_ptr(),
+                                self.buffer.as_mut_ptr() as *mut c_void,
+                                self.buffer.len() as u32,
+                                &mut read,
+                            )
+                        });
+                        match result {
+                            ffi::FMOD_OK => {}
+                            ffi::FMOD_ERR_FILE_EOF => self.finished = true,
+                            error => {
+                                self.finished = true;
+                                self.buffer.clear();
+                                return Err(err_fmod!("FMOD_Sound_ReadData", error));
+                            }
+                        }
+                        self.buffer.truncate(read as usize - read as usize % size);
+                        Ok(())
+                    }
+                }
+
+                impl Iterator for SampleReader {
+                    type Item = Result<f32, Error>;
+
+                    fn next(&mut self) -> Option<Result<f32, Error>> {
+                        let size = self.sample_size();
+                        if self.offset + size > self.buffer.len() {
+                            if self.finished {
+                                return None;
+                            }
+                            if let Err(error) = self.fill() {
+                                return Some(Err(error));
+                            }
+                            if self.buffer.is_empty() {
+                                return None;
+                            }
+                        }
+                        let sample = self.decode(&self.buffer[self.offset..self.offset + size]);
+                        self.offset += size;
+                        Some(Ok(sample))
+                    }
+                }
+
+                impl Sound {
+                    /// Reads sound data from start, sound should be opened with `FMOD_OPENONLY`.
+                    pub fn samples(&self) -> Result<SampleReader, Error> {
+                        let mut sound_type = ffi::FMOD_SOUND_TYPE::default();
+                        let mut format = ffi::FMOD_SOUND_FORMAT::default();
+                        let mut channels = 0;
+                        let mut bits = 0;
+                        match profile_call!("FMOD_Sound_GetFormat", unsafe {
+                            ffi::FMOD_Sound_GetFormat(self.pointer, &mut sound_type, &mut format, &mut channels, &mut bits)
+                        }) {
+                            ffi::FMOD_OK => {}
+                            error => return Err(err_fmod!("FMOD_Sound_GetFormat", error)),
+                        }
+                        match format {
+                            ffi::FMOD_SOUND_FORMAT_PCM8
+                            | ffi::FMOD_SOUND_FORMAT_PCM16
+                            | ffi::FMOD_SOUND_FORMAT_PCM24
+                            | ffi::FMOD_SOUND_FORMAT_PCM32
+                            | ffi::FMOD_SOUND_FORMAT_PCMFLOAT => {}
+                            _ => return Err(err_fmod!("FMOD_Sound_GetFormat", ffi::FMOD_ERR_FORMAT)),
+                        }
+                        match profile_call!("FMOD_Sound_SeekData", unsafe { ffi::FMOD_Sound_SeekData(self.pointer, 0) }) {
+                            ffi::FMOD_OK => {}
+                            error => return Err(err_fmod!("FMOD_Sound_SeekData", error)),
+                        }
+                        Ok(SampleReader {
+                            sound: *self,
+                            format,
+                            channels: channels.max(1) as usize,
+                            buffer: vec![],
+                            offset: 0,
+                            finished: false,
+                        })
+                    }
+
+                    /// Minimum and maximum sample values of equal sound parts, for waveform previews.
+                    pub fn waveform(&self, points: usize) -> Result<Vec<(f32, f32)>, Error> {
+                        let mut length = 0;
+                        match profile_call!("FMOD_Sound_GetLength", unsafe { ffi::FMOD_Sound_GetLength(self.pointer, &mut length, ffi::FMOD_TIMEUNIT_PCM) }) {
+                            ffi::FMOD_OK => {}
+                            error => return Err(err_fmod!("FMOD_Sound_GetLength", error)),
+                        }
+                        let points = points.max(1);
+                        let samples = self.samples()?;
+                        let channels = samples.channels();
+                        let frames = (length as usize / points).max(1);
+                        let mut waveform = vec![(0.0f32, 0.0f32); points];
+                        for (index, sample) in samples.enumerate() {
+                            let sample = sample?;
+                            let point = (index / channels / frames).min(points - 1);
+                            let (min, max) = &mut waveform[point];
+                            *min = min.min(sample);
+                            *max = max.max(sample);
+                        }
+                        Ok(waveform)
+                    }
+                }
+            },
+        );
+        let get_polygon_num_vertices = self.method_ident("FMOD_Geometry_GetPolygonNumVertices");
+        let get_polygon_vertex = self.method_ident("FMOD_Geometry_GetPolygonVertex");
+        self.patch_opaque_type(
+            "FMOD_GEOMETRY",
+            quote! {
+                impl Geometry {
+                    pub fn get_polygon_vertices(&self, index: i32) -> Result<Vec<Vector>, Error> {
+                        let count = self.#get_polygon_num_vertices(index)?;
+                        (0..count)
+                            .map(|vertex| self.#get_polygon_vertex(index, vertex))
+                            .collect()
+                    }
+                }
+            },
+        );
+        let get_core_system = self.method_ident("FMOD_Studio_System_GetCoreSystem");
+        let get_advanced_settings = self.method_ident("FMOD_System_GetAdvancedSettings");
+        self.patch_opaque_type(
+            "FMOD_STUDIO_SYSTEM",
+            quote! {
+                pub struct StudioBuilder {
+                    max_channels: i32,
+                    studio_flags: StudioInitFlags,
+                    flags: InitFlags,
+                    advanced_settings: Option<StudioAdvancedSettings>,
+                    core_advanced_settings: Option<AdvancedSettings>,
+                    software_format: Option<(i32, SpeakerMode, i32)>,
+                    profile_port: Option<u16>,
+                }
+
+                impl Default for StudioBuilder {
+                    fn default() -> Self {
+                        StudioBuilder {
+                            max_channels: 1024,
+                            studio_flags: StudioInitFlags::NORMAL,
+                            flags: InitFlags::NORMAL,
+                            advanced_settings: None,
+                            core_advanced_settings: None,
+                            software_format: None,
+                            profile_port: None,
+                        }
+                    }
+                }
+
+                impl StudioBuilder {
+                    pub fn new() -> Self {
+                        Self::default()
+                    }
+
+                    pub fn max_channels(mut self, max_channels: i32) -> Self {
+                        self.max_channels = max_channels;
+                        self
+                    }
+
+                    pub fn studio_flags(mut self, flags: impl Into<StudioInitFlags>) -> Self {
+                        self.studio_flags = flags.into();
+                        self
+                    }
+
+                    pub fn flags(mut self, flags: impl Into<InitFlags>) -> Self {
+                        self.flags = flags.into();
+                        self
+                    }
+
+                    pub fn advanced_settings(mut self, settings: StudioAdvancedSettings) -> Self {
+                        self.advanced_settings = Some(settings);
+                        self
+                    }
+
+                    pub fn core_advanced_settings(mut self, settings: AdvancedSettings) -> Self {
+                        self.core_advanced_settings = Some(settings);
+                        self
+                    }
+
+                    pub fn software_format(
+                        mut self,
+                        sample_rate: i32,
+                        speaker_mode: SpeakerMode,
+                        raw_speakers: i32,
+                    ) -> Self {
+                        self.software_format = Some((sample_rate, speaker_mode, raw_speakers));
+                        self
+                    }
+
+                    /// Enables connection of FMOD Studio live update and core profiler.
+                    ///
+                    /// ```no_run
+                    /// let studio = libfmod::Studio::builder().live_update(None).build()?;
+                    /// # Ok::<(), libfmod::Error>(())
+                    /// ```
+                    pub fn live_update(mut self, port: Option<u16>) -> Self {
+                        self.studio_flags |= StudioInitFlags::LIVEUPDATE;
+                        self.profiling(port)
+                    }
+
+                    pub fn profiling(mut self, port: Option<u16>) -> Self {
+                        self.flags |= InitFlags::PROFILE_ENABLE;
+                        self.profile_port = port;
+                        self
+                    }
+
+                    pub fn build(self) -> Result<Studio, Error> {
+                        if self.max_channels <= 0 {
+                            return Err(Error::InvalidParameter {
+                                function: "StudioBuilder::build",
+                                reason: "max channels must be positive",
+                            });
+                        }
+                        let studio = Studio::create()?;
+                        match self.initialize(studio) {
+                            Ok(()) => Ok(studio),
+                            Err(error) => {
+                                let _ = studio.release();
+                                Err(error)
+                            }
+                        }
+                    }
+
+                    fn initialize(self, studio: Studio) -> Result<(), Error> {
+                        // advanced and format settings are only accepted before initialization
+                        let system = studio.#get_core_system()?;
+                        if let Some(settings) = self.advanced_settings {
+                            studio.set_advanced_settings(settings)?;
+                        }
+                        let core_advanced_settings = match (self.core_advanced_settings, self.profile_port) {
+                            (Some(settings), None) => Some(settings),
+                            (settings, Some(port)) => {
+                                let mut settings = match settings {
+                                    Some(settings) => settings,
+                                    None => system.#get_advanced_settings()?,
+                                };
+                                settings.profile_port = port;
+                                Some(settings)
+                            }
+                            (None, None) => None,
+                        };
+                        if let Some(settings) = core_advanced_settings {
+                            system.set_advanced_settings(settings)?;
+                        }
+                        if let Some((sample_rate, speaker_mode, raw_speakers)) = self.software_format {
+                            system.set_software_format(sample_rate, speaker_mode, raw_speakers)?;
+                        }
+                        studio.initialize(self.max_channels, self.studio_flags, self.flags, None)
+                    }
+                }
+
+                impl Studio {
+                    pub fn builder() -> StudioBuilder {
+                        StudioBuilder::new()
+                    }
+                }
+            },
+        );
+        // core only crate has no studio usage, the field stays to keep the same shape
+        let studio_usage = if self.is_structure("FMOD_STUDIO_CPU_USAGE") {
+            quote! { StudioCpuUsage }
+        } else {
+            quote! { std::convert::Infallible }
+        };
+        self.patch_opaque_type(
+            "FMOD_SYSTEM",
+            quote! {
+                /// Engine statistics combined for overlay displays.
+                #[derive(Debug, Clone)]
+                pub struct Diagnostics {
+                    pub studio: Option<#studio_usage>,
+                    pub core: CpuUsage,
+                    pub current_allocated: i32,
+                    pub max_allocated: i32,
+                }
+
+                impl Diagnostics {
+                    fn collect(studio: Option<#studio_usage>, core: CpuUsage) -> Result<Self, Error> {
+                        let mut current_allocated = i32::default();
+                        let mut max_allocated = i32::default();
+                        match profile_call!("FMOD_Memory_GetStats", unsafe {
+                            ffi::FMOD_Memory_GetStats(&mut current_allocated, &mut max_allocated, from_bool!(false))
+                        }) {
+                            ffi::FMOD_OK => Ok(Diagnostics {
+                                studio,
+                                core,
+                                current_allocated,
+                                max_allocated,
+                            }),
+                            error => Err(err_fmod!("FMOD_Memory_GetStats", error)),
+                        }
+                    }
+                }
+
+                impl System {
+                    pub fn diagnostics(&self) -> Result<Diagnostics, Error> {
+                        let mut usage = ffi::FMOD_CPU_USAGE::default();
+                        match profile_call!("FMOD_System_GetCPUUsage", unsafe { ffi::FMOD_System_GetCPUUsage(self.pointer, &mut usage) }) {
+                            ffi::FMOD_OK => Diagnostics::collect(None, CpuUsage::try_from(usage)?),
+                            error => Err(err_fmod!("FMOD_System_GetCPUUsage", error)),
+                        }
+                    }
+                }
+            },
+        );
+        self.patch_opaque_type(
+            "FMOD_STUDIO_SYSTEM",
+            quote! {
+                impl Studio {
+                    pub fn diagnostics(&self) -> Result<Diagnostics, Error> {
+                        let mut usage = ffi::FMOD_STUDIO_CPU_USAGE::default();
+                        let mut usage_core = ffi::FMOD_CPU_USAGE::default();
+                        match profile_call!("FMOD_Studio_System_GetCPUUsage", unsafe {
+                            ffi::FMOD_Studio_System_GetCPUUsage(self.pointer, &mut usage, &mut usage_core)
+                        }) {
+                            ffi::FMOD_OK => Diagnostics::collect(
+                                Some(StudioCpuUsage::try_from(usage)?),
+                                CpuUsage::try_from(usage_core)?,
+                            ),
+                            error => Err(err_fmod!("FMOD_Studio_System_GetCPUUsage", error)),
+                        }
+                    }
+                }
+            },
+        );
+        let get_buffer_usage = self.method_ident("FMOD_Studio_System_GetBufferUsage");
+        self.patch_opaque_type(
+            "FMOD_STUDIO_SYSTEM",
+            quote! {
+                /// Statistics of Studio system taken at one frame.
+                #[derive(Debug, Clone)]
+                pub struct PerformanceSample {
+                    pub diagnostics: Diagnostics,
+                    pub buffer_usage: BufferUsage,
+                }
+
+                /// Samples Studio statistics once per frame and keeps rolling averages
+                /// over last frames, intended for audio debug overlays.
+                #[derive(Debug, Clone)]
+                pub struct PerformanceMonitor {
+                    window: usize,
+                    samples: std::collections::VecDeque<PerformanceSample>,
+                }
+
+                impl PerformanceMonitor {
+                    pub fn new(window: usize) -> Self {
+                        let window = window.max(1);
+                        PerformanceMonitor {
+                            window,
+                            samples: std::collections::VecDeque::with_capacity(window),
+                        }
+                    }
+
+                    pub fn sample(&mut self, studio: &Studio) -> Result<&PerformanceSample, Error> {
+                        let sample = PerformanceSample {
+                            diagnostics: studio.diagnostics()?,
+                            buffer_usage: studio.#get_buffer_usage()?,
+                        };
+                        if self.samples.len() == self.window {
+                            self.samples.pop_front();
+                        }
+                        self.samples.push_back(sample);
+                        Ok(&self.samples[self.samples.len() - 1])
+                    }
+
+                    pub fn latest(&self) -> Option<&PerformanceSample> {
+                        self.samples.back()
+                    }
+
+                    pub fn samples(&self) -> impl Iterator<Item = &PerformanceSample> {
+                        self.samples.iter()
+                    }
+
+                    pub fn average(&self, metric: impl Fn(&PerformanceSample) -> f32) -> f32 {
+                        if self.samples.is_empty() {
+                            return 0.0;
+                        }
+                        self.samples.iter().map(metric).sum::<f32>() / self.samples.len() as f32
+                    }
+
+                    pub fn average_dsp(&self) -> f32 {
+                        self.average(|sample| sample.diagnostics.core.dsp)
+                    }
+
+                    pub fn average_stream(&self) -> f32 {
+                        self.average(|sample| sample.diagnostics.core.stream)
+                    }
+
+                    pub fn average_update(&self) -> f32 {
+                        self.average(|sample| sample.diagnostics.core.update)
+                    }
+
+                    pub fn average_command_queue_usage(&self) -> f32 {
+                        self.average(|sample| sample.buffer_usage.studiocommandqueue.currentusage as f32)
+                    }
+
+                    pub fn average_allocated(&self) -> f32 {
+                        self.average(|sample| sample.diagnostics.current_allocated as f32)
+                    }
+
+                    pub fn max_stall_time(&self) -> f32 {
+                        self.samples
+                            .iter()
+                            .map(|sample| {
+                                let usage = &sample.buffer_usage;
+                                usage.studiocommandqueue.stalltime.max(usage.studiohandle.stalltime)
+                            })
+                            .fold(0.0, f32::max)
+                    }
+
+                    pub fn clear(&mut self) {
+                        self.samples.clear();
+                    }
+                }
+            },
+        );
+        if self.is_structure("FMOD_STUDIO_USER_PROPERTY") {
+            let get_user_property_count =
+                self.method_ident("FMOD_Studio_EventDescription_GetUserPropertyCount");
+            let get_user_property_by_index =
+                self.method_ident("FMOD_Studio_EventDescription_GetUserPropertyByIndex");
+            self.patch_opaque_type(
+                "FMOD_STUDIO_EVENTDESCRIPTION",
+                quote! {
+                    impl EventDescription {
+                        /// Reads all user properties with values decoded from union by property type.
+                        pub fn user_properties(&self) -> Result<Vec<(String, UserPropertyValue)>, Error> {
+                            (0..self.#get_user_property_count()?)
+                                .map(|index| {
+                                    let property = self.#get_user_property_by_index(index)?;
+                                    let value = property.value()?;
+                                    Ok((property.name, value))
+                                })
+                                .collect()
+                        }
+                    }
+                },
+            );
+        }
+        let get_3d_attributes = self.method_ident("FMOD_Studio_EventInstance_Get3DAttributes");
+        let get_core_system = self.method_ident("FMOD_Studio_System_GetCoreSystem");
+        self.patch_opaque_type(
+            "FMOD_STUDIO_EVENTINSTANCE",
+            quote! {
+                pub trait ProgrammerSoundCallback {
+                    fn create_sound(
+                        &mut self,
+                        instance: EventInstance,
+                        properties: &mut ProgrammerSoundPropertiesRef,
+                    ) -> Result<(), FmodResult>;
+                    fn destroy_sound(
+                        &mut self,
+                        _instance: EventInstance,
+                        properties: &mut ProgrammerSoundPropertiesRef,
+                    ) -> Result<(), FmodResult> {
+                        if let Some(sound) = properties.sound() {
+                            sound.release()?;
+                        }
+                        Ok(())
+                    }
+                }
+
+                type ProgrammerSoundCell = std::sync::Mutex<Box<dyn ProgrammerSoundCallback>>;
+                type ProgrammerSoundUserData = std::sync::Arc<ProgrammerSoundCell>;
+
+                /// Programmer sound callbacks by event instance, kept aside from instance user data
+                /// so the user data stays available to caller. Entries hold `Arc` raw pointers,
+                /// running callback keeps its own reference so replacing it from other thread is safe.
+                static PROGRAMMER_SOUND_CALLBACKS: std::sync::Mutex<Vec<(usize, usize)>> =
+                    std::sync::Mutex::new(Vec::new());
+
+                fn take_programmer_sound_callback(event: *mut ffi::FMOD_STUDIO_EVENTINSTANCE) -> Option<ProgrammerSoundUserData> {
+                    let mut callbacks = PROGRAMMER_SOUND_CALLBACKS.lock().ok()?;
+                    let index = callbacks.iter().position(|(instance, _)| *instance == event as usize)?;
+                    let userdata = callbacks.swap_remove(index).1 as *const ProgrammerSoundCell;
+                    Some(unsafe { std::sync::Arc::from_raw(userdata) })
+                }
+
+                fn find_programmer_sound_callback(event: *mut ffi::FMOD_STUDIO_EVENTINSTANCE) -> Option<ProgrammerSoundUserData> {
+                    let callbacks = PROGRAMMER_SOUND_CALLBACKS.lock().ok()?;
+                    let (_, userdata) = callbacks.iter().find(|(instance, _)| *instance == event as usize)?;
+                    let userdata = *userdata as *const ProgrammerSoundCell;
+                    // reference is added while entry is locked, so it can't be dropped in between
+                    unsafe {
+                        std::sync::Arc::increment_strong_count(userdata);
+                        Some(std::sync::Arc::from_raw(userdata))
+                    }
+                }
+
+                unsafe extern "C" fn programmer_sound_callback(
+                    kind: ffi::FMOD_STUDIO_EVENT_CALLBACK_TYPE,
+                    event: *mut ffi::FMOD_STUDIO_EVENTINSTANCE,
+                    parameters: *mut c_void,
+                ) -> ffi::FMOD_RESULT {
+                    if kind == ffi::FMOD_STUDIO_EVENT_CALLBACK_DESTROYED {
+                        drop(take_programmer_sound_callback(event));
+                        return ffi::FMOD_OK;
+                    }
+                    let userdata = match find_programmer_sound_callback(event) {
+                        Some(userdata) => userdata,
+                        None => return ffi::FMOD_OK,
+                    };
+                    let mut callback = match userdata.lock() {
+                        Ok(callback) => callback,
+                        Err(_) => return ffi::FMOD_OK,
+                    };
+                    let instance = EventInstance::from(event);
+                    let mut properties = match unsafe { ProgrammerSoundPropertiesRef::from_ptr(parameters as *mut _) } {
+                        Some(properties) => properties,
+                        None => return ffi::FMOD_OK,
+                    };
+                    let result = match kind {
+                        ffi::FMOD_STUDIO_EVENT_CALLBACK_CREATE_PROGRAMMER_SOUND => {
+                            callback.create_sound(instance, &mut properties)
+                        }
+                        ffi::FMOD_STUDIO_EVENT_CALLBACK_DESTROY_PROGRAMMER_SOUND => {
+                            callback.destroy_sound(instance, &mut properties)
+                        }
+                        _ => Ok(()),
+                    };
+                    match result {
+                        Ok(()) => ffi::FMOD_OK,
+                        Err(error) => error.to_raw(),
+                    }
+                }
+
+                impl EventInstance {
+                    /// Replaces instance callback, the programmer sound callback is dropped
+                    /// when replaced or the instance is destroyed. Instance user data is not used.
+                    pub fn set_programmer_sound_callback(
+                        &self,
+                        callback: impl ProgrammerSoundCallback + 'static,
+                    ) -> Result<(), Error> {
+                        drop(take_programmer_sound_callback(self.pointer));
+                        let callback: Box<dyn ProgrammerSoundCallback> = Box::new(callback);
+                        let userdata: ProgrammerSoundUserData = std::sync::Arc::new(std::sync::Mutex::new(callback));
+                        if let Ok(mut callbacks) = PROGRAMMER_SOUND_CALLBACKS.lock() {
+                            callbacks.push((self.pointer as usize, std::sync::Arc::into_raw(userdata) as usize));
+                        }
+                        let result = self.set_callback(
+                            Some(programmer_sound_callback),
+                            ffi::FMOD_STUDIO_EVENT_CALLBACK_CREATE_PROGRAMMER_SOUND
+                                | ffi::FMOD_STUDIO_EVENT_CALLBACK_DESTROY_PROGRAMMER_SOUND
+                                | ffi::FMOD_STUDIO_EVENT_CALLBACK_DESTROYED,
+                        );
+                        if result.is_err() {
+                            drop(take_programmer_sound_callback(self.pointer));
+                        }
+                        result
+                    }
+                }
+
+                impl EventInstance {
+                    /// Moves instance keeping its velocity and orientation.
+                    pub fn set_3d_position(&self, position: impl Into<Vector>) -> Result<(), Error> {
+                        let mut attributes = self.#get_3d_attributes()?;
+                        attributes.position = position.into();
+                        self.set_3d_attributes(&attributes)
+                    }
+
+                    pub fn set_3d_velocity(&self, velocity: impl Into<Vector>) -> Result<(), Error> {
+                        let mut attributes = self.#get_3d_attributes()?;
+                        attributes.velocity = velocity.into();
+                        self.set_3d_attributes(&attributes)
+                    }
+
+                    /// Turns instance, vectors are normalized so FMOD doesn't reject them.
+                    pub fn set_3d_orientation(&self, forward: impl Into<Vector>, up: impl Into<Vector>) -> Result<(), Error> {
+                        let attributes = self.#get_3d_attributes()?.with_orientation(forward, up);
+                        self.set_3d_attributes(&attributes)
+                    }
+                }
+
+                impl<'a> ProgrammerSoundPropertiesRef<'a> {
+                    pub fn assign(&mut self, sound: Sound, subsound_index: i32) {
+                        self.set_sound(sound);
+                        self.set_subsound_index(subsound_index);
+                    }
+                }
+
+                impl Studio {
+                    /// Creates sound of audio table entry, raw sound info is passed to FMOD as is
+                    /// because `name_or_data` points into bank memory for banks loaded from memory.
+                    pub fn create_programmer_sound(
+                        &self,
+                        key: &str,
+                        mode: Mode,
+                    ) -> Result<(Sound, i32), Error> {
+                        let key = CString::new(key)?;
+                        let mut info = ffi::FMOD_STUDIO_SOUND_INFO::default();
+                        match profile_call!("FMOD_Studio_System_GetSoundInfo", unsafe { ffi::FMOD_Studio_System_GetSoundInfo(self.pointer, key.as_ptr(), &mut info) }) {
+                            ffi::FMOD_OK => {}
+                            error => return Err(err_fmod!("FMOD_Studio_System_GetSoundInfo", error)),
+                        }
+                        let system = self.#get_core_system()?;
+                        let mode: ffi::FMOD_MODE = mode.into();
+                        let mut sound = null_mut();
+                        match profile_call!("FMOD_System_CreateSound", unsafe {
+                            ffi::FMOD_System_CreateSound(
+                                system.as_mut_ptr(),
+                                info.name_or_data,
+                                info.mode | mode,
+                                &mut info.exinfo,
+                                &mut sound,
+                            )
+                        }) {
+                            ffi::FMOD_OK => Ok((Sound::from(sound), info.subsoundindex)),
+                            error => Err(err_fmod!("FMOD_System_CreateSound", error)),
+                        }
+                    }
+                }
+            },
+        );
+        self.patch_opaque_type(
+            "FMOD_STUDIO_EVENTINSTANCE",
+            quote! {
+                /// Event callback parameters decoded according to callback type.
+                pub enum EventCallbackData<'a> {
+                    None,
+                    ProgrammerSound(ProgrammerSoundPropertiesRef<'a>),
+                    PluginInstance(PluginInstancePropertiesRef<'a>),
+                    TimelineMarker(TimelineMarkerPropertiesRef<'a>),
+                    TimelineBeat(TimelineBeatPropertiesRef<'a>),
+                    Sound(Sound),
+                    EventInstance(EventInstance),
+                    Unrecognized(*mut c_void),
+                }
+
+                impl<'a> EventCallbackData<'a> {
+                    /// # Safety
+                    ///
+                    /// Parameters must be passed by FMOD to event callback of given type,
+                    /// the data is valid only during callback.
+                    pub unsafe fn decode(
+                        kind: ffi::FMOD_STUDIO_EVENT_CALLBACK_TYPE,
+                        parameters: *mut c_void,
+                    ) -> Self {
+                        if parameters.is_null() {
+                            return EventCallbackData::None;
+                        }
+                        let data = match kind {
+                            ffi::FMOD_STUDIO_EVENT_CALLBACK_CREATE_PROGRAMMER_SOUND
+                            | ffi::FMOD_STUDIO_EVENT_CALLBACK_DESTROY_PROGRAMMER_SOUND => {
+                                unsafe { ProgrammerSoundPropertiesRef::from_ptr(parameters as *mut _) }
+                                    .map(EventCallbackData::ProgrammerSound)
+                            }
+                            ffi::FMOD_STUDIO_EVENT_CALLBACK_PLUGIN_CREATED
+                            | ffi::FMOD_STUDIO_EVENT_CALLBACK_PLUGIN_DESTROYED => {
+                                unsafe { PluginInstancePropertiesRef::from_ptr(parameters as *mut _) }
+                                    .map(EventCallbackData::PluginInstance)
+                            }
+                            ffi::FMOD_STUDIO_EVENT_CALLBACK_TIMELINE_MARKER => {
+                                unsafe { TimelineMarkerPropertiesRef::from_ptr(parameters as *mut _) }
+                                    .map(EventCallbackData::TimelineMarker)
+                            }
+                            ffi::FMOD_STUDIO_EVENT_CALLBACK_TIMELINE_BEAT => {
+                                unsafe { TimelineBeatPropertiesRef::from_ptr(parameters as *mut _) }
+                                    .map(EventCallbackData::TimelineBeat)
+                            }
+                            ffi::FMOD_STUDIO_EVENT_CALLBACK_SOUND_PLAYED
+                            | ffi::FMOD_STUDIO_EVENT_CALLBACK_SOUND_STOPPED => {
+                                Sound::try_from_ptr(parameters as *mut _).map(EventCallbackData::Sound)
+                            }
+                            ffi::FMOD_STUDIO_EVENT_CALLBACK_START_EVENT_COMMAND => {
+                                EventInstance::try_from_ptr(parameters as *mut _)
+                                    .map(EventCallbackData::EventInstance)
+                            }
+                            _ => Some(EventCallbackData::Unrecognized(parameters)),
+                        };
+                        data.unwrap_or(EventCallbackData::None)
+                    }
+                }
+            },
+        );
+        let get_user_data = self.method_ident("FMOD_System_GetUserData");
+        self.patch_opaque_type(
+            "FMOD_SYSTEM",
+            quote! {
+                /// System callback parameters decoded according to callback type.
+                pub enum SystemCallbackData<'a> {
+                    None,
+                    MemoryAllocationFailed { file: &'a str, size: i32 },
+                    Thread { handle: *mut c_void, name: &'a str },
+                    Error(ErrorCallbackInfo),
+                    DeviceReinitialize { output: OutputType, driver: i32 },
+                    RecordPositionChanged { sound: Sound, position: u32 },
+                    Unrecognized(*mut c_void, *mut c_void),
+                }
+
+                impl<'a> SystemCallbackData<'a> {
+                    /// # Safety
+                    ///
+                    /// Command data must be passed by FMOD to system callback of given type,
+                    /// the data is valid only during callback.
+                    pub unsafe fn decode(
+                        kind: ffi::FMOD_SYSTEM_CALLBACK_TYPE,
+                        data1: *mut c_void,
+                        data2: *mut c_void,
+                    ) -> Result<Self, Error> {
+                        let str_from_ptr = |pointer: *mut c_void| -> Result<&'a str, Error> {
+                            if pointer.is_null() {
+                                Ok("")
+                            } else {
+                                unsafe { CStr::from_ptr(pointer as *const c_char) }.to_str().map_err(Error::Utf8)
+                            }
+                        };
+                        let data = match kind {
+                            ffi::FMOD_SYSTEM_CALLBACK_MEMORYALLOCATIONFAILED => {
+                                SystemCallbackData::MemoryAllocationFailed {
+                                    file: str_from_ptr(data1)?,
+                                    size: data2 as isize as i32,
+                                }
+                            }
+                            ffi::FMOD_SYSTEM_CALLBACK_THREADCREATED
+                            | ffi::FMOD_SYSTEM_CALLBACK_THREADDESTROYED => SystemCallbackData::Thread {
+                                handle: data1,
+                                name: str_from_ptr(data2)?,
+                            },
+                            ffi::FMOD_SYSTEM_CALLBACK_ERROR if !data1.is_null() => {
+                                let info = unsafe { *(data1 as *const ffi::FMOD_ERRORCALLBACK_INFO) };
+                                SystemCallbackData::Error(ErrorCallbackInfo::try_from(info)?)
+                            }
+                            ffi::FMOD_SYSTEM_CALLBACK_DEVICEREINITIALIZE => {
+                                SystemCallbackData::DeviceReinitialize {
+                                    output: OutputType::from_raw_lossy(data1 as isize as ffi::FMOD_OUTPUTTYPE),
+                                    driver: data2 as isize as i32,
+                                }
+                            }
+                            ffi::FMOD_SYSTEM_CALLBACK_RECORDPOSITIONCHANGED => {
+                                match Sound::try_from_ptr(data1 as *mut ffi::FMOD_SOUND) {
+                                    Some(sound) => SystemCallbackData::RecordPositionChanged {
+                                        sound,
+                                        position: data2 as usize as u32,
+                                    },
+                                    None => SystemCallbackData::None,
+                                }
+                            }
+                            _ if data1.is_null() && data2.is_null() => SystemCallbackData::None,
+                            _ => SystemCallbackData::Unrecognized(data1, data2),
+                        };
+                        Ok(data)
+                    }
+                }
+
+                pub trait SystemCallback {
+                    fn call(
+                        &mut self,
+                        system: System,
+                        kind: SystemCallbackType,
+                        data: SystemCallbackData,
+                    ) -> Result<(), FmodResult>;
+                }
+
+                type SystemCallbackUserData = Box<dyn SystemCallback>;
+
+                /// Systems which user data is owned by callback registration.
+                static SYSTEM_CALLBACK_OWNERS: std::sync::Mutex<Vec<usize>> = std::sync::Mutex::new(Vec::new());
+
+                unsafe extern "C" fn system_callback(
+                    system: *mut ffi::FMOD_SYSTEM,
+                    kind: ffi::FMOD_SYSTEM_CALLBACK_TYPE,
+                    data1: *mut c_void,
+                    data2: *mut c_void,
+                    userdata: *mut c_void,
+                ) -> ffi::FMOD_RESULT {
+                    if userdata.is_null() {
+                        return ffi::FMOD_OK;
+                    }
+                    let callback = unsafe { &mut *(userdata as *mut SystemCallbackUserData) };
+                    let data = match unsafe { SystemCallbackData::decode(kind, data1, data2) } {
+                        Ok(data) => data,
+                        Err(_) => SystemCallbackData::Unrecognized(data1, data2),
+                    };
+                    match callback.call(System::from(system), SystemCallbackType::from_bits_retain(kind), data) {
+                        Ok(()) => ffi::FMOD_OK,
+                        Err(error) => error.to_raw(),
+                    }
+                }
+
+                impl System {
+                    /// Occupies the system user data, the callback is dropped
+                    /// when replaced or cleared.
+                    pub fn set_callback_rust(
+                        &self,
+                        callback: impl SystemCallback + 'static,
+                        mask: SystemCallbackType,
+                    ) -> Result<(), Error> {
+                        self.clear_callback_rust()?;
+                        let callback: Box<SystemCallbackUserData> = Box::new(Box::new(callback));
+                        let userdata = Box::into_raw(callback);
+                        if let Err(error) = self.set_user_data(userdata as *mut c_void) {
+                            drop(unsafe { Box::from_raw(userdata) });
+                            return Err(error);
+                        }
+                        if let Ok(mut owners) = SYSTEM_CALLBACK_OWNERS.lock() {
+                            owners.push(self.pointer as usize);
+                        }
+                        self.set_callback(Some(system_callback), mask)
+                    }
+
+                    /// Drops callback registered by `set_callback_rust`,
+                    /// user data set by other code is left untouched.
+                    pub fn clear_callback_rust(&self) -> Result<(), Error> {
+                        let owned = match SYSTEM_CALLBACK_OWNERS.lock() {
+                            Ok(mut owners) => {
+                                let count = owners.len();
+                                owners.retain(|owner| *owner != self.pointer as usize);
+                                owners.len() != count
+                            }
+                            Err(_) => false,
+                        };
+                        if owned {
+                            let userdata = self.#get_user_data()?;
+                            self.set_callback(None, SystemCallbackType::empty())?;
+                            self.set_user_data(null_mut())?;
+                            if !userdata.is_null() {
+                                drop(unsafe { Box::from_raw(userdata as *mut SystemCallbackUserData) });
+                            }
+                        }
+                        Ok(())
+                    }
+                }
+            },
+        );
+        let get_user_data = self.method_ident("FMOD_Studio_System_GetUserData");
+        self.patch_opaque_type(
+            "FMOD_STUDIO_SYSTEM",
+            quote! {
+                /// Studio system callback parameters decoded according to callback type.
+                pub enum StudioCallbackData {
+                    None,
+                    BankUnload(Bank),
+                    Unrecognized(*mut c_void),
+                }
+
+                impl StudioCallbackData {
+                    /// # Safety
+                    ///
+                    /// Command data must be passed by FMOD to studio system callback of given type.
+                    pub unsafe fn decode(
+                        kind: ffi::FMOD_STUDIO_SYSTEM_CALLBACK_TYPE,
+                        data: *mut c_void,
+                    ) -> Self {
+                        match kind {
+                            ffi::FMOD_STUDIO_SYSTEM_CALLBACK_BANK_UNLOAD => {
+                                match Bank::try_from_ptr(data as *mut ffi::FMOD_STUDIO_BANK) {
+                                    Some(bank) => StudioCallbackData::BankUnload(bank),
+                                    None => StudioCallbackData::None,
+                                }
+                            }
+                            _ if data.is_null() => StudioCallbackData::None,
+                            _ => StudioCallbackData::Unrecognized(data),
+                        }
+                    }
+                }
+
+                pub trait StudioCallback {
+                    fn call(
+                        &mut self,
+                        studio: Studio,
+                        kind: StudioSystemCallbackType,
+                        data: StudioCallbackData,
+                    ) -> Result<(), FmodResult>;
+                }
+
+                impl<F> StudioCallback for F
+                where
+                    F: FnMut(Studio, StudioSystemCallbackType, StudioCallbackData) -> Result<(), FmodResult>,
+                {
+                    fn call(
+                        &mut self,
+                        studio: Studio,
+                        kind: StudioSystemCallbackType,
+                        data: StudioCallbackData,
+                    ) -> Result<(), FmodResult> {
+                        self(studio, kind, data)
+                    }
+                }
+
+                type StudioCallbackUserData = Box<dyn StudioCallback>;
+
+                /// Studio systems which user data is owned by callback registration.
+                static STUDIO_CALLBACK_OWNERS: std::sync::Mutex<Vec<usize>> = std::sync::Mutex::new(Vec::new());
+
+                unsafe extern "C" fn studio_callback(
+                    system: *mut ffi::FMOD_STUDIO_SYSTEM,
+                    kind: ffi::FMOD_STUDIO_SYSTEM_CALLBACK_TYPE,
+                    data: *mut c_void,
+                    userdata: *mut c_void,
+                ) -> ffi::FMOD_RESULT {
+                    if userdata.is_null() {
+                        return ffi::FMOD_OK;
+                    }
+                    let callback = unsafe { &mut *(userdata as *mut StudioCallbackUserData) };
+                    let data = unsafe { StudioCallbackData::decode(kind, data) };
+                    match callback.call(Studio::from(system), StudioSystemCallbackType::from_bits_retain(kind), data) {
+                        Ok(()) => ffi::FMOD_OK,
+                        Err(error) => error.to_raw(),
+                    }
+                }
+
+                impl Studio {
+                    /// Occupies the studio system user data, the callback is dropped
+                    /// when replaced, cleared or the studio system is released.
+                    pub fn set_callback_rust(
+                        &self,
+                        callback: impl StudioCallback + 'static,
+                        mask: StudioSystemCallbackType,
+                    ) -> Result<(), Error> {
+                        self.clear_callback_rust()?;
+                        let callback: Box<StudioCallbackUserData> = Box::new(Box::new(callback));
+                        let userdata = Box::into_raw(callback);
+                        if let Err(error) = self.set_user_data(userdata as *mut c_void) {
+                            drop(unsafe { Box::from_raw(userdata) });
+                            return Err(error);
+                        }
+                        if let Ok(mut owners) = STUDIO_CALLBACK_OWNERS.lock() {
+                            owners.push(self.pointer as usize);
+                        }
+                        self.set_callback(Some(studio_callback), mask)
+                    }
+
+                    pub fn clear_callback_rust(&self) -> Result<(), Error> {
+                        let owned = match STUDIO_CALLBACK_OWNERS.lock() {
+                            Ok(mut owners) => {
+                                let count = owners.len();
+                                owners.retain(|owner| *owner != self.pointer as usize);
+                                owners.len() != count
+                            }
+                            Err(_) => false,
+                        };
+                        if owned {
+                            let userdata = self.#get_user_data()?;
+                            self.set_callback(None, StudioSystemCallbackType::empty())?;
+                            self.set_user_data(null_mut())?;
+                            if !userdata.is_null() {
+                                drop(unsafe { Box::from_raw(userdata as *mut StudioCallbackUserData) });
+                            }
+                        }
+                        Ok(())
+                    }
+                }
+            },
+        );
+        self.patch_opaque_type(
+            "FMOD_DSP",
+            quote! {
+                impl Dsp {
+                    /// Reads output levels, metering must be enabled with `set_metering_enabled`.
+                    pub fn get_output_metering_info(&self) -> Result<DspMeteringInfo, Error> {
+                        let mut info = ffi::FMOD_DSP_METERING_INFO::default();
+                        match profile_call!("FMOD_DSP_GetMeteringInfo", unsafe { ffi::FMOD_DSP_GetMeteringInfo(self.pointer, null_mut(), &mut info) }) {
+                            ffi::FMOD_OK => DspMeteringInfo::try_from(info),
+                            error => Err(err_fmod!("FMOD_DSP_GetMeteringInfo", error)),
+                        }
+                    }
+
+                    /// Enables output metering keeping input metering state.
+                    pub fn enable_output_metering(&self) -> Result<(), Error> {
+                        let mut input = ffi::FMOD_BOOL::default();
+                        let mut output = ffi::FMOD_BOOL::default();
+                        match profile_call!("FMOD_DSP_GetMeteringEnabled", unsafe { ffi::FMOD_DSP_GetMeteringEnabled(self.pointer, &mut input, &mut output) }) {
+                            ffi::FMOD_OK if to_bool!(output) => Ok(()),
+                            ffi::FMOD_OK => match profile_call!("FMOD_DSP_SetMeteringEnabled", unsafe {
+                                ffi::FMOD_DSP_SetMeteringEnabled(self.pointer, input, from_bool!(true))
+                            }) {
+                                ffi::FMOD_OK => Ok(()),
+                                error => Err(err_fmod!("FMOD_DSP_SetMeteringEnabled", error)),
+                            },
+                            error => Err(err_fmod!("FMOD_DSP_GetMeteringEnabled", error)),
+                        }
+                    }
+                }
+            },
+        );
+        let get_dsp = self.method_ident("FMOD_Channel_GetDSP");
+        self.patch_opaque_type(
+            "FMOD_CHANNEL",
+            quote! {
+                impl Channel {
+                    /// Output levels of channel head DSP, metering is enabled on first call
+                    /// so levels become available after next mix.
+                    pub fn loudness(&self) -> Result<DspMeteringInfo, Error> {
+                        let dsp = self.#get_dsp(ffi::FMOD_CHANNELCONTROL_DSP_HEAD)?;
+                        dsp.enable_output_metering()?;
+                        dsp.get_output_metering_info()
+                    }
+                }
+            },
+        );
+        let get_command_count = self.method_ident("FMOD_Studio_CommandReplay_GetCommandCount");
+        let get_command_info = self.method_ident("FMOD_Studio_CommandReplay_GetCommandInfo");
+        let get_command_at_time = self.method_ident("FMOD_Studio_CommandReplay_GetCommandAtTime");
+        let get_user_data = self.method_ident("FMOD_Studio_CommandReplay_GetUserData");
+        self.patch_opaque_type(
+            "FMOD_STUDIO_COMMANDREPLAY",
+            quote! {
+                pub trait CommandReplayCallbacks {
+                    fn frame(
+                        &mut self,
+                        _replay: CommandReplay,
+                        _command_index: i32,
+                        _current_time: f32,
+                    ) -> Result<(), FmodResult> {
+                        Ok(())
+                    }
+                    fn load_bank(
+                        &mut self,
+                        _replay: CommandReplay,
+                        _command_index: i32,
+                        _bank_guid: Option<Guid>,
+                        _bank_filename: Option<String>,
+                        _flags: LoadBankFlags,
+                    ) -> Result<Option<Bank>, FmodResult> {
+                        Ok(None)
+                    }
+                    fn create_instance(
+                        &mut self,
+                        _replay: CommandReplay,
+                        _command_index: i32,
+                        _description: EventDescription,
+                    ) -> Result<Option<EventInstance>, FmodResult> {
+                        Ok(None)
+                    }
+                }
+
+                type CommandReplayUserData = Box<dyn CommandReplayCallbacks>;
+
+                /// Replays which user data is owned by callbacks registration.
+                static COMMAND_REPLAY_CALLBACK_OWNERS: std::sync::Mutex<Vec<usize>> = std::sync::Mutex::new(Vec::new());
+
+                unsafe extern "C" fn command_replay_frame(
+                    replay: *mut ffi::FMOD_STUDIO_COMMANDREPLAY,
+                    commandindex: i32,
+                    currenttime: f32,
+                    userdata: *mut c_void,
+                ) -> ffi::FMOD_RESULT {
+                    let callbacks = unsafe { &mut *(userdata as *mut CommandReplayUserData) };
+                    match callbacks.frame(CommandReplay::from(replay), commandindex, currenttime) {
+                        Ok(()) => ffi::FMOD_OK,
+                        Err(error) => error.to_raw(),
+                    }
+                }
+
+                unsafe extern "C" fn command_replay_load_bank(
+                    replay: *mut ffi::FMOD_STUDIO_COMMANDREPLAY,
+                    commandindex: i32,
+                    bankguid: *const ffi::FMOD_GUID,
+                    bankfilename: *const c_char,
+                    flags: ffi::FMOD_STUDIO_LOAD_BANK_FLAGS,
+                    bank: *mut *mut ffi::FMOD_STUDIO_BANK,
+                    userdata: *mut c_void,
+                ) -> ffi::FMOD_RESULT {
+                    let callbacks = unsafe { &mut *(userdata as *mut CommandReplayUserData) };
+                    let result = (|| {
+                        let bank_guid = ptr_opt!(bankguid, Guid::try_from(unsafe { *bankguid })?);
+                        let bank_filename = ptr_opt!(bankfilename, to_string!(bankfilename)?);
+                        callbacks.load_bank(
+                            CommandReplay::from(replay),
+                            commandindex,
+                            bank_guid,
+                            bank_filename,
+                            LoadBankFlags::from_bits_retain(flags),
+                        )
+                    })();
+                    match result {
+                        Ok(Some(loaded)) => {
+                            unsafe { *bank = loaded.as_mut_ptr() };
+                            ffi::FMOD_OK
+                        }
+                        Ok(None) => ffi::FMOD_OK,
+                        Err(error) => error.to_raw(),
+                    }
+                }
+
+                unsafe extern "C" fn command_replay_create_instance(
+                    replay: *mut ffi::FMOD_STUDIO_COMMANDREPLAY,
+                    commandindex: i32,
+                    eventdescription: *mut ffi::FMOD_STUDIO_EVENTDESCRIPTION,
+                    instance: *mut *mut ffi::FMOD_STUDIO_EVENTINSTANCE,
+                    userdata: *mut c_void,
+                ) -> ffi::FMOD_RESULT {
+                    let callbacks = unsafe { &mut *(userdata as *mut CommandReplayUserData) };
+                    match callbacks.create_instance(
+                        CommandReplay::from(replay),
+                        commandindex,
+                        EventDescription::from(eventdescription),
+                    ) {
+                        Ok(Some(created)) => {
+                            unsafe { *instance = created.as_mut_ptr() };
+                            ffi::FMOD_OK
+                        }
+                        Ok(None) => ffi::FMOD_OK,
+                        Err(error) => error.to_raw(),
+                    }
+                }
+
+                impl CommandReplay {
+                    /// Recorded commands, names are copied because FMOD frees them
+                    /// when replay is released.
+                    pub fn commands(&self) -> Result<impl Iterator<Item = Result<CommandInfo, Error>> + '_, Error> {
+                        let count = self.#get_command_count()?;
+                        Ok((0..count).map(move |index| self.#get_command_info(index)))
+                    }
+
+                    /// Seeks to command executed at given time and returns its index.
+                    pub fn seek_to_command_at_time(&self, time: f32) -> Result<i32, Error> {
+                        let index = self.#get_command_at_time(time)?;
+                        self.seek_to_command(index)?;
+                        Ok(index)
+                    }
+
+                    pub fn set_callbacks(
+                        &self,
+                        callbacks: impl CommandReplayCallbacks + 'static,
+                    ) -> Result<(), Error> {
+                        self.clear_callbacks()?;
+                        let callbacks: Box<CommandReplayUserData> = Box::new(Box::new(callbacks));
+                        let userdata = Box::into_raw(callbacks);
+                        if let Err(error) = self.set_user_data(userdata as *mut c_void) {
+                            drop(unsafe { Box::from_raw(userdata) });
+                            return Err(error);
+                        }
+                        if let Ok(mut owners) = COMMAND_REPLAY_CALLBACK_OWNERS.lock() {
+                            owners.push(self.pointer as usize);
+                        }
+                        self.set_frame_callback(Some(command_replay_frame))?;
+                        self.set_load_bank_callback(Some(command_replay_load_bank))?;
+                        self.set_create_instance_callback(Some(command_replay_create_instance))?;
+                        Ok(())
+                    }
+
+                    /// Drops callbacks registered by `set_callbacks`,
+                    /// user data set by other code is left untouched.
+                    pub fn clear_callbacks(&self) -> Result<(), Error> {
+                        let owned = match COMMAND_REPLAY_CALLBACK_OWNERS.lock() {
+                            Ok(mut owners) => {
+                                let count = owners.len();
+                                owners.retain(|owner| *owner != self.pointer as usize);
+                                owners.len() != count
+                            }
+                            Err(_) => false,
+                        };
+                        if owned {
+                            let userdata = self.#get_user_data()?;
+                            self.set_frame_callback(None)?;
+                            self.set_load_bank_callback(None)?;
+                            self.set_create_instance_callback(None)?;
+                            self.set_user_data(null_mut())?;
+                            if !userdata.is_null() {
+                                drop(unsafe { Box::from_raw(userdata as *mut CommandReplayUserData) });
+                            }
+                        }
+                        Ok(())
+                    }
+                }
+
+                impl Studio {
+                    pub fn run_command_replay(
+                        &self,
+                        filename: &str,
+                        flags: CommandReplayFlags,
+                        callbacks: impl CommandReplayCallbacks + 'static,
+                    ) -> Result<CommandReplay, Error> {
+                        let replay = self.load_command_replay(filename, flags)?;
+                        replay.set_callbacks(callbacks)?;
+                        replay.start()?;
+                        Ok(replay)
+                    }
+                }
+            },
+        );
+        self.patch_opaque_type(
+            "FMOD_STUDIO_SYSTEM",
+            quote! {
+                /// File opened by bank load callbacks, closed when dropped.
+                pub trait BankFile: Send {
+                    /// Fills buffer, returns less bytes only at end of file.
+                    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, FmodResult>;
+                    fn seek(&mut self, position: u32) -> Result<(), FmodResult>;
+                }
+
+                impl<T: std::io::Read + std::io::Seek + Send> BankFile for T {
+                    fn read(&mut self, buffer: &mut [u8]) -> Result<usize, FmodResult> {
+                        let mut total = 0;
+                        while total < buffer.len() {
+                            match std::io::Read::read(self, &mut buffer[total..]) {
+                                Ok(0) => break,
+                                Ok(read) => total += read,
+                                Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+                                Err(_) => return Err(FmodResult::ErrFileBad),
+                            }
+                        }
+                        Ok(total)
+                    }
+
+                    fn seek(&mut self, position: u32) -> Result<(), FmodResult> {
+                        std::io::Seek::seek(self, std::io::SeekFrom::Start(position as u64))
+                            .map(|_| ())
+                            .map_err(|_| FmodResult::ErrFileCouldnotseek)
+                    }
+                }
+
+                /// Source of bank data, FMOD may open bank several times to stream sample data.
+                pub trait BankLoadCallbacks: Send + Sync {
+                    /// Opens bank file and returns it with its size in bytes.
+                    fn open(&self) -> Result<(Box<dyn BankFile>, u32), FmodResult>;
+                }
+
+                impl<F> BankLoadCallbacks for F
+                where
+                    F: Fn() -> Result<(Box<dyn BankFile>, u32), FmodResult> + Send + Sync,
+                {
+                    fn open(&self) -> Result<(Box<dyn BankFile>, u32), FmodResult> {
+                        self()
+                    }
+                }
+
+                type BankLoadUserData = Box<dyn BankLoadCallbacks>;
+                type BankFileHandle = Box<dyn BankFile>;
+
+                /// Callbacks of loaded banks with their Studio systems, dropped when bank is unloaded
+                /// by itself, by `unload_all` or together with Studio system.
+                static BANK_LOAD_CALLBACKS: std::sync::Mutex<Vec<(usize, usize, usize)>> = std::sync::Mutex::new(Vec::new());
+
+                fn release_bank_load_callbacks(bank: *mut ffi::FMOD_STUDIO_BANK) {
+                    let userdata = match BANK_LOAD_CALLBACKS.lock() {
+                        Ok(mut banks) => match banks.iter().position(|(_, owner, _)| *owner == bank as usize) {
+                            Some(index) => banks.swap_remove(index).2,
+                            None => return,
+                        },
+                        Err(_) => return,
+                    };
+                    drop(unsafe { Box::from_raw(userdata as *mut BankLoadUserData) });
+                }
+
+                fn release_studio_bank_load_callbacks(studio: *mut ffi::FMOD_STUDIO_SYSTEM) {
+                    let released: Vec<usize> = match BANK_LOAD_CALLBACKS.lock() {
+                        Ok(mut banks) => {
+                            let (released, kept): (Vec<_>, Vec<_>) = banks.drain(..).partition(|(owner, _, _)| *owner == studio as usize);
+                            *banks = kept;
+                            released.into_iter().map(|(_, _, userdata)| userdata).collect()
+                        }
+                        Err(_) => return,
+                    };
+                    for userdata in released {
+                        drop(unsafe { Box::from_raw(userdata as *mut BankLoadUserData) });
+                    }
+                }
+
+                unsafe extern "C" fn bank_file_open(
+                    _name: *const c_char,
+                    filesize: *mut u32,
+                    handle: *mut *mut c_void,
+                    userdata: *mut c_void,
+                ) -> ffi::FMOD_RESULT {
+                    let callbacks = unsafe { &*(userdata as *const BankLoadUserData) };
+                    match callbacks.open() {
+                        Ok((file, size)) => {
+                            unsafe {
+                                *filesize = size;
+                                *handle = Box::into_raw(Box::new(file)) as *mut c_void;
+                            }
+                            ffi::FMOD_OK
+                        }
+                        Err(error) => error.to_raw(),
+                    }
+                }
+
+                unsafe extern "C" fn bank_file_close(
+                    handle: *mut c_void,
+                    _userdata: *mut c_void,
+                ) -> ffi::FMOD_RESULT {
+                    if !handle.is_null() {
+                        drop(unsafe { Box::from_raw(handle as *mut BankFileHandle) });
+                    }
+                    ffi::FMOD_OK
+                }
+
+                unsafe extern "C" fn bank_file_read(
+                    handle: *mut c_void,
+                    buffer: *mut c_void,
+                    sizebytes: u32,
+                    bytesread: *mut u32,
+                    _userdata: *mut c_void,
+                ) -> ffi::FMOD_RESULT {
+                    let file = unsafe { &mut *(handle as *mut BankFileHandle) };
+                    let buffer = unsafe { std::slice::from_raw_parts_mut(buffer as *mut u8, sizebytes as usize) };
+                    match file.read(buffer) {
+                        Ok(read) => {
+                            unsafe { *bytesread = read as u32 };
+                            if read < buffer.len() {
+                                ffi::FMOD_ERR_FILE_EOF
+                            } else {
+                                ffi::FMOD_OK
+                            }
+                        }
+                        Err(error) => error.to_raw(),
+                    }
+                }
+
+                unsafe extern "C" fn bank_file_seek(
+                    handle: *mut c_void,
+                    position: u32,
+                    _userdata: *mut c_void,
+                ) -> ffi::FMOD_RESULT {
+                    let file = unsafe { &mut *(handle as *mut BankFileHandle) };
+                    match file.seek(position) {
+                        Ok(()) => ffi::FMOD_OK,
+                        Err(error) => error.to_raw(),
+                    }
+                }
+
+                impl Studio {
+                    /// Loads bank with data provided by callbacks, for example streamed from archive.
+                    /// Callbacks are kept until the bank is unloaded.
+                    pub fn load_bank_custom_rust(
+                        &self,
+                        callbacks: impl BankLoadCallbacks + 'static,
+                        flags: LoadBankFlags,
+                    ) -> Result<Bank, Error> {
+                        let callbacks: Box<BankLoadUserData> = Box::new(Box::new(callbacks));
+                        let userdata = Box::into_raw(callbacks);
+                        let info = ffi::FMOD_STUDIO_BANK_INFO {
+                            userdata: userdata as *mut c_void,
+                            opencallback: Some(bank_file_open),
+                            closecallback: Some(bank_file_close),
+                            readcallback: Some(bank_file_read),
+                            seekcallback: Some(bank_file_seek),
+                            ..Default::default()
+                        };
+                        let mut bank = null_mut();
+                        match profile_call!("FMOD_Studio_System_LoadBankCustom", unsafe { ffi::FMOD_Studio_System_LoadBankCustom(self.pointer, &info, flags.into(), &mut bank) }) {
+                            ffi::FMOD_OK => {
+                                if let Ok(mut banks) = BANK_LOAD_CALLBACKS.lock() {
+                                    banks.push((self.pointer as usize, bank as usize, userdata as usize));
+                                }
+                                Ok(Bank::from(bank))
+                            }
+                            error => {
+                                drop(unsafe { Box::from_raw(userdata) });
+                                Err(err_fmod!("FMOD_Studio_System_LoadBankCustom", error))
+                            }
+                        }
+                    }
+                }
+            },
+        );
+    }
+}
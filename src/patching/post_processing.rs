@@ -1,11 +1,14 @@
+use std::collections::HashMap;
+
 use crate::models::{Api, Modifier, OpaqueType};
 
 impl Api {
     pub fn apply_postprocessing(&mut self) {
-        if !self
-            .opaque_types
-            .iter()
-            .any(|opaque_type| opaque_type.name == "FMOD_STUDIO_SYSTEM")
+        if !self.settings.core_only
+            && !self
+                .opaque_types
+                .iter()
+                .any(|opaque_type| opaque_type.name == "FMOD_STUDIO_SYSTEM")
         {
             self.opaque_types.push(OpaqueType {
                 name: "FMOD_STUDIO_SYSTEM".into(),
@@ -19,8 +22,14 @@ impl Api {
             "FMOD_Studio_EventDescription_Is3D+is3D",
             "FMOD_Studio_System_GetCoreSystem+coresystem",
             "FMOD_System_GetNumNestedPlugins+count",
+            "FMOD_Sound_GetSyncPoint+point",
+            "FMOD_Sound_AddSyncPoint+point",
         ];
         for key in not_specified_output {
+            // modifiers are validated against parsed functions
+            if self.settings.core_only && key.starts_with("FMOD_Studio_") {
+                continue;
+            }
             self.modifiers.insert(key.to_string(), Modifier::Out);
         }
         let not_output = &[
@@ -32,4 +41,13 @@ impl Api {
             self.modifiers.remove(&key.to_string());
         }
     }
+
+    pub fn apply_curated_modifiers(&mut self, curated: HashMap<String, Modifier>) {
+        for (key, modifier) in curated {
+            match modifier {
+                Modifier::None => self.modifiers.remove(&key),
+                modifier => self.modifiers.insert(key, modifier),
+            };
+        }
+    }
 }
@@ -1,17 +1,31 @@
+use crate::ffi::{describe_pointer, format_rust_ident};
+use crate::generators::lib::format_argument_ident;
+use crate::models::Type::{FundamentalType, UserType};
 use crate::patching::dictionary::RENAMES;
 use crate::Api;
 use convert_case::{Case, Casing};
-use quote::__private::TokenStream;
+
+const CALLBACK_STRUCTURES: &[(&str, &[&str])] = &[
+    ("FMOD_STUDIO_PLUGIN_INSTANCE_PROPERTIES", &[]),
+    (
+        "FMOD_STUDIO_PROGRAMMER_SOUND_PROPERTIES",
+        &["sound", "subsoundIndex"],
+    ),
+    ("FMOD_STUDIO_TIMELINE_MARKER_PROPERTIES", &[]),
+    ("FMOD_STUDIO_TIMELINE_BEAT_PROPERTIES", &[]),
+];
 
 impl Api {
     pub fn patch_structures(&mut self) {
+        let get_type = self.method_ident("FMOD_DSP_GetType");
+        let get_parameter_data = self.method_ident("FMOD_DSP_GetParameterData");
         self.structure_patches.insert("FMOD_DSP_PARAMETER_FFT".to_string(), quote! {
             impl TryFrom<Dsp> for DspParameterFft {
                 type Error = Error;
                 fn try_from(dsp: Dsp) -> Result<Self, Self::Error> {
-                    match dsp.get_type() {
+                    match dsp.#get_type() {
                         Ok(DspType::Fft) => {
-                            let (ptr, _, _) = dsp.get_parameter_data(ffi::FMOD_DSP_FFT_SPECTRUMDATA, 0)?;
+                            let (ptr, _, _) = dsp.#get_parameter_data(ffi::FMOD_DSP_FFT_SPECTRUMDATA, 0)?;
                             let fft = unsafe {
                                 *(ptr as *const ffi::FMOD_DSP_PARAMETER_FFT)
                             };
@@ -22,6 +36,32 @@ impl Api {
                 }
             }
         });
+        self.structure_patches.insert(
+            "FMOD_DSP_LOUDNESS_METER_INFO_TYPE".to_string(),
+            quote! {
+                impl TryFrom<Dsp> for DspLoudnessMeterInfo {
+                    type Error = Error;
+                    fn try_from(dsp: Dsp) -> Result<Self, Self::Error> {
+                        match dsp.#get_type() {
+                            Ok(DspType::LoudnessMeter) => {
+                                let (ptr, length, _) = dsp.#get_parameter_data(ffi::FMOD_DSP_LOUDNESS_METER_INFO, 0)?;
+                                if ptr.is_null() || length as usize != size_of::<ffi::FMOD_DSP_LOUDNESS_METER_INFO_TYPE>() {
+                                    return Err(Error::InvalidParameter {
+                                        function: "FMOD_DSP_GetParameterData",
+                                        reason: "loudness meter info is missing or has unexpected size",
+                                    });
+                                }
+                                let info = unsafe {
+                                    *(ptr as *const ffi::FMOD_DSP_LOUDNESS_METER_INFO_TYPE)
+                                };
+                                DspLoudnessMeterInfo::try_from(info)
+                            }
+                            _ => Err(Error::NotDspLoudnessMeter),
+                        }
+                    }
+                }
+            },
+        );
         self.structure_patches.insert(
             "FMOD_CREATESOUNDEXINFO".to_string(),
             quote! {
@@ -32,6 +72,114 @@ impl Api {
                 }
             },
         );
+        self.structure_patches.insert(
+            "FMOD_STUDIO_ADVANCEDSETTINGS".to_string(),
+            quote! {
+                impl StudioAdvancedSettings {
+                    pub fn into_ffi(self, guard: &mut Guard) -> Result<ffi::FMOD_STUDIO_ADVANCEDSETTINGS, Error> {
+                        let encryptionkey = if self.encryptionkey.is_empty() {
+                            null()
+                        } else {
+                            guard.string(&self.encryptionkey)?
+                        };
+                        let mut value: ffi::FMOD_STUDIO_ADVANCEDSETTINGS = self.into();
+                        value.encryptionkey = encryptionkey;
+                        Ok(value)
+                    }
+                }
+            },
+        );
+        self.structure_patches.insert(
+            "FMOD_ADVANCEDSETTINGS".to_string(),
+            quote! {
+                impl AdvancedSettings {
+                    pub fn into_ffi(self, guard: &mut Guard) -> Result<ffi::FMOD_ADVANCEDSETTINGS, Error> {
+                        let count = self.asio_channel_list.len();
+                        if !self.asio_speaker_list.is_empty() && self.asio_speaker_list.len() != count {
+                            return Err(Error::LengthMismatch {
+                                name: "asio_speaker_list",
+                                expected: count,
+                                actual: self.asio_speaker_list.len(),
+                            });
+                        }
+                        let mut channels = vec![];
+                        for name in &self.asio_channel_list {
+                            channels.push(guard.string(name)?);
+                        }
+                        let speakers: Vec<ffi::FMOD_SPEAKER> =
+                            self.asio_speaker_list.iter().map(|speaker| (*speaker).into()).collect();
+                        let mut value: ffi::FMOD_ADVANCEDSETTINGS = self.into();
+                        if !channels.is_empty() {
+                            value.ASIONumChannels = count as i32;
+                            value.ASIOChannelList = guard.keep(channels).cast();
+                        }
+                        if !speakers.is_empty() {
+                            value.ASIOSpeakerList = guard.keep(speakers);
+                        }
+                        Ok(value)
+                    }
+                }
+            },
+        );
+        self.structure_patches.insert(
+            "FMOD_DSP_PARAMETER_DESC".to_string(),
+            quote! {
+                impl DspParameterDesc {
+                    pub fn into_ffi(self, guard: &mut Guard) -> Result<ffi::FMOD_DSP_PARAMETER_DESC, Error> {
+                        let description = guard.string(&self.description)?;
+                        let mut value: ffi::FMOD_DSP_PARAMETER_DESC = self.into();
+                        value.description = description;
+                        Ok(value)
+                    }
+                }
+            },
+        );
+        self.structure_patches.insert(
+            "FMOD_DSP_DESCRIPTION".to_string(),
+            quote! {
+                impl DspDescription {
+                    pub fn into_ffi(mut self, guard: &mut Guard) -> Result<ffi::FMOD_DSP_DESCRIPTION, Error> {
+                        let mut parameters = vec![];
+                        for parameter in std::mem::take(&mut self.paramdesc) {
+                            parameters.push(parameter.into_ffi(guard)?);
+                        }
+                        let count = parameters.len();
+                        let parameters = guard.keep(parameters);
+                        let pointers = (0..count).map(|index| unsafe { parameters.add(index) }).collect();
+                        let mut value: ffi::FMOD_DSP_DESCRIPTION = self.into();
+                        value.numparameters = count as i32;
+                        value.paramdesc = guard.keep(pointers);
+                        Ok(value)
+                    }
+                }
+            },
+        );
+        self.structure_patches.insert(
+            "FMOD_CODEC_DESCRIPTION".to_string(),
+            quote! {
+                impl CodecDescription {
+                    pub fn into_ffi(self, guard: &mut Guard) -> Result<ffi::FMOD_CODEC_DESCRIPTION, Error> {
+                        let name = guard.string(&self.name)?;
+                        let mut value: ffi::FMOD_CODEC_DESCRIPTION = self.into();
+                        value.name = name;
+                        Ok(value)
+                    }
+                }
+            },
+        );
+        self.structure_patches.insert(
+            "FMOD_OUTPUT_DESCRIPTION".to_string(),
+            quote! {
+                impl OutputDescription {
+                    pub fn into_ffi(self, guard: &mut Guard) -> Result<ffi::FMOD_OUTPUT_DESCRIPTION, Error> {
+                        let name = guard.string(&self.name)?;
+                        let mut value: ffi::FMOD_OUTPUT_DESCRIPTION = self.into();
+                        value.name = name;
+                        Ok(value)
+                    }
+                }
+            },
+        );
         self.structure_patches.insert(
             "FMOD_GUID".to_string(),
             quote! {
@@ -48,6 +196,65 @@ impl Api {
                 }
             },
         );
+        self.structure_patches.insert(
+            "FMOD_OUTPUT_OBJECT3DINFO".to_string(),
+            quote! {
+                /// Object info passed to output plugin object update callback, borrowed for
+                /// duration of the callback since FMOD reuses the mixed buffer afterwards.
+                #[derive(Debug, Clone, Copy)]
+                pub struct OutputObject3dInfoRef<'a> {
+                    raw: &'a ffi::FMOD_OUTPUT_OBJECT3DINFO,
+                }
+
+                impl<'a> OutputObject3dInfoRef<'a> {
+                    /// # Safety
+                    /// Pointer must be valid for lifetime `'a`, as callback argument is.
+                    pub unsafe fn from_ptr(info: *const ffi::FMOD_OUTPUT_OBJECT3DINFO) -> Option<Self> {
+                        unsafe { info.as_ref() }.map(|raw| Self { raw })
+                    }
+
+                    /// Mono PCM samples of the object mixed by FMOD.
+                    pub fn buffer(&self) -> &'a [f32] {
+                        if self.raw.buffer.is_null() {
+                            &[]
+                        } else {
+                            unsafe { slice::from_raw_parts(self.raw.buffer, self.raw.bufferlength as usize) }
+                        }
+                    }
+
+                    pub fn position(&self) -> &'a Vector {
+                        Vector::from_ffi_ref(&self.raw.position)
+                    }
+
+                    pub fn gain(&self) -> f32 {
+                        self.raw.gain
+                    }
+
+                    pub fn spread(&self) -> f32 {
+                        self.raw.spread
+                    }
+
+                    pub fn priority(&self) -> f32 {
+                        self.raw.priority
+                    }
+
+                    /// Copies info with its buffer to keep it after the callback returns.
+                    pub fn to_owned(&self) -> Result<OutputObject3dInfo, Error> {
+                        OutputObject3dInfo::try_from(*self.raw)
+                    }
+                }
+            },
+        );
+        self.structure_patches.insert(
+            "FMOD_STUDIO_PARAMETER_ID".to_string(),
+            quote! {
+                #[derive(Debug, Clone, Copy, PartialEq)]
+                pub struct ParameterValue {
+                    pub value: f32,
+                    pub final_value: f32,
+                }
+            },
+        );
         self.structure_patches.insert(
             "FMOD_VECTOR".to_string(),
             quote! {
@@ -55,6 +262,24 @@ impl Api {
                     pub const fn new(x: f32, y: f32, z: f32) -> Self {
                         Vector { x, y, z }
                     }
+
+                    pub fn dot(&self, other: Vector) -> f32 {
+                        self.x * other.x + self.y * other.y + self.z * other.z
+                    }
+
+                    pub fn length(&self) -> f32 {
+                        self.dot(*self).sqrt()
+                    }
+
+                    /// Returns unit vector of the same direction, zero vector is returned as is.
+                    pub fn normalized(&self) -> Vector {
+                        let length = self.length();
+                        if length > 0.0 {
+                            Vector::new(self.x / length, self.y / length, self.z / length)
+                        } else {
+                            *self
+                        }
+                    }
                 }
                 impl From<[f32;3]> for Vector {
                     fn from(value: [f32;3]) -> Vector {
@@ -84,8 +309,175 @@ impl Api {
                         (value.x, value.y, value.z)
                     }
                 }
+                #[cfg(feature = "mint")]
+                impl From<mint::Vector3<f32>> for Vector {
+                    fn from(value: mint::Vector3<f32>) -> Vector {
+                        Vector::new(value.x, value.y, value.z)
+                    }
+                }
+                #[cfg(feature = "mint")]
+                impl From<Vector> for mint::Vector3<f32> {
+                    fn from(value: Vector) -> mint::Vector3<f32> {
+                        mint::Vector3 { x: value.x, y: value.y, z: value.z }
+                    }
+                }
+                #[cfg(feature = "glam")]
+                impl From<glam::Vec3> for Vector {
+                    fn from(value: glam::Vec3) -> Vector {
+                        Vector::new(value.x, value.y, value.z)
+                    }
+                }
+                #[cfg(feature = "glam")]
+                impl From<Vector> for glam::Vec3 {
+                    fn from(value: Vector) -> glam::Vec3 {
+                        glam::Vec3::new(value.x, value.y, value.z)
+                    }
+                }
             },
         );
+        self.structure_patches.insert(
+            "FMOD_3D_ATTRIBUTES".to_string(),
+            quote! {
+                impl Attributes3d {
+                    pub fn new(
+                        position: impl Into<Vector>,
+                        velocity: impl Into<Vector>,
+                        forward: impl Into<Vector>,
+                        up: impl Into<Vector>,
+                    ) -> Self {
+                        Attributes3d {
+                            position: position.into(),
+                            velocity: velocity.into(),
+                            forward: forward.into(),
+                            up: up.into(),
+                        }
+                    }
+
+                    pub fn at(position: impl Into<Vector>) -> Self {
+                        Attributes3d::new(
+                            position,
+                            Vector::new(0.0, 0.0, 0.0),
+                            Vector::new(0.0, 0.0, 1.0),
+                            Vector::new(0.0, 1.0, 0.0),
+                        )
+                    }
+
+                    /// Sets orientation FMOD accepts: unit length forward and up perpendicular to it.
+                    pub fn with_orientation(mut self, forward: impl Into<Vector>, up: impl Into<Vector>) -> Self {
+                        let forward = forward.into().normalized();
+                        let up = up.into();
+                        let along = up.dot(forward);
+                        let up = Vector::new(
+                            up.x - forward.x * along,
+                            up.y - forward.y * along,
+                            up.z - forward.z * along,
+                        );
+                        self.forward = forward;
+                        self.up = up.normalized();
+                        self
+                    }
+                }
+            },
+        );
+    }
+
+    fn patch_callback_structure(&mut self, key: &str, setters: &[&str]) {
+        let structure = match self
+            .structures
+            .iter()
+            .find(|structure| structure.name == key)
+        {
+            Some(structure) => structure.clone(),
+            None => return,
+        };
+        let name = format_ident!("{}Ref", Api::patch_structure_name(key));
+        let raw = format_ident!("{}", key);
+        let mut accessors = vec![];
+        for field in &structure.fields {
+            let getter = format_argument_ident(&field.name, self);
+            let setter = format_ident!("set_{}", getter);
+            let value = format_rust_ident(&field.name, self);
+            let pointer = describe_pointer(&field.as_const, &field.pointer);
+            let value_type = match (&field.field_type, pointer) {
+                (FundamentalType(name), "*const") if name == "char" => {
+                    accessors.push(quote! {
+                        pub fn #getter(&self) -> Result<&str, Error> {
+                            if self.raw.#value.is_null() {
+                                Ok("")
+                            } else {
+                                unsafe { CStr::from_ptr(self.raw.#value) }
+                                    .to_str()
+                                    .map_err(Error::Utf8)
+                            }
+                        }
+                    });
+                    continue;
+                }
+                (UserType(name), "*mut") if self.is_opaque_type(name) => {
+                    let handle = format_ident!("{}", Api::patch_structure_name(name));
+                    accessors.push(quote! {
+                        pub fn #getter(&self) -> Option<#handle> {
+                            #handle::try_from_ptr(self.raw.#value)
+                        }
+                    });
+                    if setters.contains(&&field.name[..]) {
+                        accessors.push(quote! {
+                            pub fn #setter(&mut self, value: #handle) {
+                                self.raw.#value = value.as_mut_ptr();
+                            }
+                        });
+                    }
+                    continue;
+                }
+                (FundamentalType(name), "") if name == "int" => quote! { i32 },
+                (FundamentalType(name), "") if name == "unsigned int" => quote! { u32 },
+                (FundamentalType(name), "") if name == "float" => quote! { f32 },
+                (FundamentalType(name), "") if name == "double" => quote! { f64 },
+                _ => continue,
+            };
+            accessors.push(quote! {
+                pub fn #getter(&self) -> #value_type {
+                    self.raw.#value
+                }
+            });
+            if setters.contains(&&field.name[..]) {
+                accessors.push(quote! {
+                    pub fn #setter(&mut self, value: #value_type) {
+                        self.raw.#value = value;
+                    }
+                });
+            }
+        }
+        let patch = quote! {
+            pub struct #name<'a> {
+                raw: &'a mut ffi::#raw,
+            }
+
+            impl<'a> #name<'a> {
+                /// # Safety
+                ///
+                /// Pointer must be valid and not aliased for lifetime `'a`, as callback argument is.
+                pub unsafe fn from_ptr(pointer: *mut ffi::#raw) -> Option<Self> {
+                    unsafe { pointer.as_mut() }.map(|raw| Self { raw })
+                }
+
+                pub fn as_mut_ptr(&mut self) -> *mut ffi::#raw {
+                    self.raw
+                }
+
+                #(#accessors)*
+            }
+        };
+        self.structure_patches
+            .entry(key.to_string())
+            .or_default()
+            .extend(patch);
+    }
+
+    pub fn patch_callback_structures(&mut self) {
+        for (key, setters) in CALLBACK_STRUCTURES {
+            self.patch_callback_structure(key, setters);
+        }
     }
 
     pub fn patch_structure_derives(&mut self) {
@@ -95,6 +487,22 @@ impl Api {
             "FMOD_VECTOR".to_string(),
             quote! { Debug, Clone, Copy, PartialEq },
         );
+        self.structure_derives.insert(
+            "FMOD_STUDIO_PARAMETER_ID".to_string(),
+            quote! { Debug, Clone, Copy, PartialEq, Eq, Hash },
+        );
+        self.structure_derives.insert(
+            "FMOD_3D_ATTRIBUTES".to_string(),
+            quote! { Debug, Clone, Copy, PartialEq },
+        );
+        self.structure_derives.insert(
+            "FMOD_REVERB_PROPERTIES".to_string(),
+            quote! { Debug, Clone, Copy, PartialEq },
+        );
+        self.structure_derives.insert(
+            "FMOD_GUID".to_string(),
+            quote! { Debug, Clone, Copy, PartialEq, Eq, Hash },
+        );
     }
 
     pub fn patch_structure_name(key: &str) -> String {
@@ -111,38 +519,4 @@ impl Api {
         };
         name.to_string()
     }
-
-    pub fn patch_ffi_structure_default(key: &str) -> Option<TokenStream> {
-        let definition = match key {
-            "FMOD_STUDIO_ADVANCEDSETTINGS" => quote! {
-                impl Default for FMOD_STUDIO_ADVANCEDSETTINGS {
-                    fn default() -> Self {
-                        let mut value: Self = unsafe { std::mem::zeroed() };
-                        value.cbsize = std::mem::size_of::<FMOD_STUDIO_ADVANCEDSETTINGS>() as _;
-                        value
-                    }
-                }
-            },
-            "FMOD_ADVANCEDSETTINGS" => quote! {
-                impl Default for FMOD_ADVANCEDSETTINGS {
-                    fn default() -> Self {
-                        let mut value: Self = unsafe { std::mem::zeroed() };
-                        value.cbSize = std::mem::size_of::<FMOD_ADVANCEDSETTINGS>() as _;
-                        value
-                    }
-                }
-            },
-            "FMOD_CREATESOUNDEXINFO" => quote! {
-                impl Default for FMOD_CREATESOUNDEXINFO {
-                    fn default() -> Self {
-                        let mut value: Self = unsafe { std::mem::zeroed() };
-                        value.cbsize = std::mem::size_of::<FMOD_CREATESOUNDEXINFO>() as _;
-                        value
-                    }
-                }
-            },
-            _ => return None,
-        };
-        Some(definition)
-    }
 }
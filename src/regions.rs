@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+const KEEP_START: &str = "// <libfmod-gen:keep>";
+const KEEP_END: &str = "// </libfmod-gen:keep>";
+
+/// Hand-edited region of destination file, markers included.
+pub struct Region {
+    /// Trimmed line preceding region and number of same lines before it,
+    /// `None` for region at the start of file.
+    pub anchor: Option<(String, usize)>,
+    /// Whether region is separated from anchor by blank line.
+    pub spaced: bool,
+    pub code: String,
+}
+
+/// Extracts hand-edited regions marked in destination file with lines they follow.
+pub fn extract_regions(existing: &str) -> Vec<Region> {
+    let mut regions = vec![];
+    let mut region: Option<Vec<&str>> = None;
+    let mut occurrences: HashMap<&str, usize> = HashMap::new();
+    let mut anchor: Option<(String, usize)> = None;
+    let mut spaced = false;
+    for line in existing.lines() {
+        let marker = line.trim();
+        match region.as_mut() {
+            None if marker == KEEP_START => region = Some(vec![line]),
+            None if marker.is_empty() => spaced = true,
+            None => {
+                let count = occurrences.entry(marker).or_default();
+                anchor = Some((marker.to_string(), *count));
+                *count += 1;
+                spaced = false;
+            }
+            Some(lines) => {
+                lines.push(line);
+                if marker == KEEP_END {
+                    regions.push(Region {
+                        anchor: anchor.clone(),
+                        spaced,
+                        code: lines.join("\n"),
+                    });
+                    region = None;
+                }
+            }
+        }
+    }
+    regions
+}
+
+fn push_region(code: &mut String, region: &Region) {
+    if region.spaced {
+        code.push('\n');
+    }
+    code.push_str(&region.code);
+    code.push('\n');
+}
+
+/// Inserts regions kept in existing destination file after the same lines of generated code,
+/// so manual additions survive regeneration in place and repeated runs give the same output.
+/// Regions which lines are gone from generated code are appended to the end.
+pub fn preserve_regions(existing: &str, generated: String) -> String {
+    let regions = extract_regions(existing);
+    if regions.is_empty() {
+        return generated;
+    }
+    let mut code = String::new();
+    let mut placed = vec![false; regions.len()];
+    for (index, region) in regions.iter().enumerate() {
+        if region.anchor.is_none() {
+            push_region(&mut code, region);
+            placed[index] = true;
+        }
+    }
+    let mut occurrences: HashMap<&str, usize> = HashMap::new();
+    for line in generated.trim_end().lines() {
+        code.push_str(line);
+        code.push('\n');
+        let marker = line.trim();
+        if marker.is_empty() {
+            continue;
+        }
+        let count = occurrences.entry(marker).or_default();
+        for (index, region) in regions.iter().enumerate() {
+            if let Some((anchor, occurrence)) = &region.anchor {
+                if !placed[index] && anchor == marker && occurrence == count {
+                    push_region(&mut code, region);
+                    placed[index] = true;
+                }
+            }
+        }
+        *count += 1;
+    }
+    for (index, region) in regions.iter().enumerate() {
+        if !placed[index] {
+            code.push('\n');
+            code.push_str(&region.code);
+            code.push('\n');
+        }
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GENERATED: &str = "use crate::ffi;\n\nimpl System {\n    pub fn update(&self) {}\n}\n\nimpl Sound {\n    pub fn release(&self) {}\n}\n";
+
+    #[test]
+    fn keeps_region_inside_impl_block() {
+        let existing = "use crate::ffi;\n\nimpl System {\n    pub fn update(&self) {}\n\n    // <libfmod-gen:keep>\n    pub fn custom(&self) {}\n    // </libfmod-gen:keep>\n}\n\nimpl Sound {\n    pub fn release(&self) {}\n}\n";
+        let code = preserve_regions(existing, GENERATED.to_string());
+        assert_eq!(code, existing);
+    }
+
+    #[test]
+    fn anchors_region_to_repeated_line() {
+        let existing = "use crate::ffi;\n\nimpl System {\n    pub fn update(&self) {}\n}\n\nimpl Sound {\n    pub fn release(&self) {}\n}\n// <libfmod-gen:keep>\nimpl Sound {}\n// </libfmod-gen:keep>\n";
+        let generated = format!("{}\nimpl Dsp {{}}\n", GENERATED);
+        let code = preserve_regions(existing, generated);
+        assert!(code.contains("    pub fn release(&self) {}\n}\n// <libfmod-gen:keep>\nimpl Sound {}\n// </libfmod-gen:keep>\n\nimpl Dsp {}\n"));
+        assert_eq!(
+            preserve_regions(&code, format!("{}\nimpl Dsp {{}}\n", GENERATED)),
+            code
+        );
+    }
+
+    #[test]
+    fn appends_region_without_anchor_in_generated_code() {
+        let existing = "impl Channel {\n    // <libfmod-gen:keep>\n    pub fn custom(&self) {}\n    // </libfmod-gen:keep>\n}\n";
+        let code = preserve_regions(existing, GENERATED.to_string());
+        assert!(code.starts_with(GENERATED));
+        assert!(code.ends_with("\n    // <libfmod-gen:keep>\n    pub fn custom(&self) {}\n    // </libfmod-gen:keep>\n"));
+    }
+}
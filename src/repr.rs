@@ -17,7 +17,11 @@ impl JsonConverter {
         T: DeserializeOwned,
         R: RuleType,
     {
-        let value = self.create_value(pair);
+        let declaration = format_declaration(pair.as_str());
+        let mut value = self.create_value(pair);
+        if let Value::Object(map) = &mut value {
+            map.insert("declaration".into(), Value::String(declaration));
+        }
         serde_json::from_value(value)
     }
 
@@ -45,3 +49,12 @@ impl JsonConverter {
         }
     }
 }
+
+/// Original C source of declaration with trailing whitespace removed from lines.
+fn format_declaration(source: &str) -> String {
+    source
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
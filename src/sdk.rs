@@ -0,0 +1,106 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::models::Error;
+
+pub const FMOD_SDK_DIR: &str = "FMOD_SDK_DIR";
+
+const HEADERS: &[&str] = &[
+    "api/studio/inc/fmod_studio.h",
+    "api/studio/inc/fmod_studio_common.h",
+    "api/core/inc/fmod.h",
+    "api/core/inc/fmod_common.h",
+    "api/core/inc/fmod_codec.h",
+    "api/core/inc/fmod_output.h",
+    "api/core/inc/fmod_dsp.h",
+    "api/core/inc/fmod_dsp_effects.h",
+    "api/core/inc/fmod_errors.h",
+];
+
+#[cfg(target_os = "windows")]
+const DEFAULT_PATHS: &[&str] = &[
+    "C:\\Program Files (x86)\\FMOD SoundSystem\\FMOD Studio API Windows",
+    "C:\\Program Files\\FMOD SoundSystem\\FMOD Studio API Windows",
+];
+
+#[cfg(target_os = "macos")]
+const DEFAULT_PATHS: &[&str] = &["/Volumes/FMOD Programmers API Mac"];
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const DEFAULT_PATHS: &[&str] = &[];
+
+/// Directories where SDK content may be placed relative to selected directory:
+/// Windows installer and Linux archive put it directly, macOS image has own
+/// folder and extracted archives are often nested into versioned folder.
+fn candidates(path: &Path) -> Vec<PathBuf> {
+    let mut candidates = vec![path.to_path_buf(), path.join("FMOD Programmers API")];
+    if let Ok(entries) = fs::read_dir(path) {
+        let mut nested: Vec<PathBuf> = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|nested| {
+                nested.is_dir()
+                    && nested
+                        .file_name()
+                        .map(|name| {
+                            name.to_string_lossy()
+                                .to_lowercase()
+                                .starts_with("fmodstudioapi")
+                        })
+                        .unwrap_or(false)
+            })
+            .collect();
+        nested.sort();
+        candidates.extend(nested);
+    }
+    candidates
+}
+
+fn missing_headers(root: &Path) -> Vec<&'static str> {
+    HEADERS
+        .iter()
+        .copied()
+        .filter(|header| !root.join(header).exists())
+        .collect()
+}
+
+/// Locates SDK root directory with api and doc folders, source argument takes precedence
+/// over FMOD_SDK_DIR environment variable and default installation paths.
+pub fn locate(source: Option<&str>) -> Result<PathBuf, Error> {
+    let paths: Vec<String> = match source {
+        Some(source) => vec![source.to_string()],
+        None => match env::var(FMOD_SDK_DIR) {
+            Ok(path) => vec![path],
+            Err(_) => DEFAULT_PATHS.iter().map(ToString::to_string).collect(),
+        },
+    };
+    if paths.is_empty() {
+        return Err(Error::Io(format!(
+            "FMOD SDK directory not specified, pass it as first argument or set {}",
+            FMOD_SDK_DIR
+        )));
+    }
+    let mut report = vec![];
+    for path in &paths {
+        for root in candidates(Path::new(path)) {
+            let missing = missing_headers(&root);
+            if missing.is_empty() {
+                return Ok(root);
+            }
+            if missing.len() < HEADERS.len() {
+                report.push(format!("{} misses {}", root.display(), missing.join(", ")));
+            }
+        }
+    }
+    if report.is_empty() {
+        report = paths
+            .iter()
+            .map(|path| format!("{} contains no FMOD headers", path))
+            .collect();
+    }
+    Err(Error::Io(format!(
+        "FMOD headers not found, make sure input is FMOD SDK directory with api, doc, plugin folders: {}",
+        report.join("; ")
+    )))
+}
@@ -0,0 +1,98 @@
+use crate::models::{Api, Argument, Error, Field, Type};
+
+impl Api {
+    fn is_known_type(&self, key: &str) -> bool {
+        self.is_structure(key)
+            || self.is_enumeration(key)
+            || self.is_flags(key)
+            || self.is_opaque_type(key)
+            || self.is_type_alias(key)
+            || self.is_callback(key)
+    }
+
+    fn validate_type(&self, owner: &str, value_type: &Type, violations: &mut Vec<String>) {
+        if let Type::UserType(name) = value_type {
+            if !self.is_known_type(name) {
+                violations.push(format!("{}: unresolved type {}", owner, name));
+            }
+        }
+    }
+
+    fn validate_arguments(
+        &self,
+        owner: &str,
+        arguments: &[Argument],
+        violations: &mut Vec<String>,
+    ) {
+        for argument in arguments {
+            let owner = format!("{}({})", owner, argument.name);
+            self.validate_type(&owner, &argument.argument_type, violations);
+        }
+    }
+
+    fn validate_field(&self, owner: &str, field: &Field, violations: &mut Vec<String>) {
+        let owner = format!("{}.{}", owner, field.name);
+        self.validate_type(&owner, &field.field_type, violations);
+        if let Some(dimension) = &field.as_array {
+            let dimension = dimension[1..dimension.len() - 1].trim();
+            if dimension.parse::<usize>().is_err() && !self.is_constant(dimension) {
+                violations.push(format!(
+                    "{}: unresolved array dimension {}",
+                    owner, dimension
+                ));
+            }
+        }
+    }
+
+    /// Checks references between declarations, so unresolved names are
+    /// reported before generation instead of producing broken code.
+    pub fn validate(&self) -> Result<(), Error> {
+        let mut violations = vec![];
+        for (_, functions) in &self.functions {
+            for function in functions {
+                self.validate_type(&function.name, &function.return_type, &mut violations);
+                self.validate_arguments(&function.name, &function.arguments, &mut violations);
+            }
+        }
+        for callback in &self.callbacks {
+            self.validate_type(&callback.name, &callback.return_type, &mut violations);
+            self.validate_arguments(&callback.name, &callback.arguments, &mut violations);
+        }
+        for structure in &self.structures {
+            let unions = structure.union.iter().flat_map(|union| &union.fields);
+            for field in structure.fields.iter().chain(unions) {
+                self.validate_field(&structure.name, field, &mut violations);
+            }
+        }
+        let mut modifiers: Vec<&String> = self.modifiers.keys().collect();
+        modifiers.sort();
+        for key in modifiers {
+            // documentation also describes C++ methods, only C functions and callbacks are checked
+            if !key.starts_with("FMOD_") {
+                continue;
+            }
+            let (function, argument) = key.split_once('+').unwrap_or((key, ""));
+            let has_argument =
+                |arguments: &[Argument]| arguments.iter().any(|arg| arg.name == argument);
+            let exists = self
+                .functions
+                .iter()
+                .flat_map(|(_, functions)| functions)
+                .filter(|candidate| candidate.name == function)
+                .any(|candidate| has_argument(&candidate.arguments))
+                || self
+                    .callbacks
+                    .iter()
+                    .filter(|candidate| candidate.name == function)
+                    .any(|candidate| has_argument(&candidate.arguments));
+            if !exists {
+                violations.push(format!("modifier {}: unknown function argument", key));
+            }
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::Validation(violations))
+        }
+    }
+}